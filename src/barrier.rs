@@ -0,0 +1,213 @@
+use crate::errors::Error;
+use crate::kv_client::{compare_create_revision, prefix_range_end, put_op, Kv};
+use crate::lease::Lease;
+use crate::rpc::{Compare_CompareResult, DeleteRangeRequest, PutRequest, RangeRequest, TxnRequest};
+use crate::transport::Client;
+use crate::watch::{wait_delete, wait_put};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Blocks processes in `wait` until the barrier key is released, compatible with etcd's
+/// `recipes.Barrier`.
+pub struct Barrier {
+    key: Vec<u8>,
+    kv: Kv,
+}
+
+/// Blocks processes in `enter` until `count` of them have entered, and in `leave` until all of
+/// them have left, compatible with etcd's `recipes.DoubleBarrier`.
+///
+/// Each participant's key is bound to its lease, so a crashed participant is dropped from the
+/// barrier once its lease expires.
+pub struct DoubleBarrier {
+    key: Vec<u8>,
+    count: usize,
+    lease: Rc<Lease>,
+    waiter: Rc<RefCell<Option<Vec<u8>>>>,
+}
+
+impl Barrier {
+    pub fn new<C: Into<Client>>(key: Vec<u8>, client: C) -> Barrier {
+        Barrier {
+            key,
+            kv: Kv::new(client),
+        }
+    }
+
+    /// Creates the barrier key, blocking processes in `wait` until it is released.
+    pub async fn hold(&self) -> Result<(), Error> {
+        let mut txn_request = TxnRequest::new();
+        txn_request.compare.push(compare_create_revision(
+            self.key.clone(),
+            Compare_CompareResult::EQUAL,
+            0,
+        ));
+        txn_request
+            .success
+            .push(put_op(self.key.clone(), Vec::new(), 0));
+
+        if self.kv.txn(txn_request).await?.succeeded {
+            Ok(())
+        } else {
+            Err(Error::KeyExists)
+        }
+    }
+
+    /// Deletes the barrier key, unblocking every waiting process.
+    pub async fn release(&self) -> Result<(), Error> {
+        let mut delete_range_request = DeleteRangeRequest::new();
+        delete_range_request.key = self.key.clone();
+        self.kv.delete_range(delete_range_request).await?;
+        Ok(())
+    }
+
+    /// Resolves once the barrier key is deleted, or immediately if it is not held.
+    ///
+    /// Puts to the key after it is read, such as another `hold` racing this one, do not release
+    /// the barrier. A release does, even if the key is held again before the watch catches up.
+    pub async fn wait(&self) -> Result<(), Error> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = self.key.clone();
+        range_request.count_only = true;
+
+        let response = self.kv.range(range_request).await?;
+        if response.count == 0 {
+            return Ok(());
+        }
+        wait_delete(
+            self.kv.client.clone(),
+            self.key.clone(),
+            response.get_header().revision + 1,
+        )
+        .await
+    }
+}
+
+impl DoubleBarrier {
+    pub fn new(key: Vec<u8>, count: usize, lease: Rc<Lease>) -> DoubleBarrier {
+        DoubleBarrier {
+            key,
+            count,
+            lease,
+            waiter: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Resolves once `count` processes have entered the barrier.
+    pub async fn enter(&self) -> Result<(), Error> {
+        let kv = Kv::new(self.lease.client.clone());
+        let waiters = self.waiters_prefix();
+
+        let (key, revision) = put_unique(kv.clone(), waiters.clone(), self.lease.lease_id).await?;
+        *self.waiter.borrow_mut() = Some(key);
+
+        let mut range_request = RangeRequest::new();
+        range_request.range_end = prefix_range_end(&waiters);
+        range_request.key = waiters;
+        range_request.count_only = true;
+
+        let entered = kv.range(range_request).await?.count as usize;
+        if entered > self.count {
+            return Err(Error::TooManyClients);
+        }
+
+        if entered == self.count {
+            // The last process in unblocks everyone else.
+            let mut put_request = PutRequest::new();
+            put_request.key = self.ready_key();
+            kv.put(put_request).await?;
+        } else {
+            wait_put(kv.client.clone(), self.ready_key(), revision).await?;
+        }
+        Ok(())
+    }
+
+    /// Resolves once every process has left the barrier.
+    pub async fn leave(&self) -> Result<(), Error> {
+        let kv = Kv::new(self.lease.client.clone());
+        let waiters = self.waiters_prefix();
+
+        loop {
+            let mut range_request = RangeRequest::new();
+            range_request.key = waiters.clone();
+            range_request.range_end = prefix_range_end(&waiters);
+
+            let response = kv.range(range_request).await?;
+            let lowest = response.kvs.iter().min_by_key(|kv| kv.mod_revision);
+            let highest = response.kvs.iter().max_by_key(|kv| kv.mod_revision);
+            let (lowest, highest) = match (lowest, highest) {
+                (Some(lowest), Some(highest)) => (lowest.clone(), highest.clone()),
+                _ => return Ok(()),
+            };
+            let is_lowest = self.waiter.borrow().as_ref() == Some(&lowest.key);
+
+            if response.kvs.len() == 1 {
+                // The last process out cleans up the barrier.
+                let mut delete_ready = DeleteRangeRequest::new();
+                delete_ready.key = self.ready_key();
+                futures::try_join!(
+                    kv.delete_range(delete_ready),
+                    delete_waiter(&kv, &self.waiter)
+                )?;
+                return Ok(());
+            }
+
+            // The lowest process waits for the highest to leave, and everyone else leaves and
+            // waits on the lowest. A crashed process's key goes away with its lease.
+            if is_lowest {
+                wait_delete(kv.client.clone(), highest.key, highest.mod_revision).await?;
+            } else {
+                delete_waiter(&kv, &self.waiter).await?;
+                wait_delete(kv.client.clone(), lowest.key, lowest.mod_revision).await?;
+            }
+        }
+    }
+
+    fn waiters_prefix(&self) -> Vec<u8> {
+        [self.key.clone(), b"/waiters/".to_vec()].concat()
+    }
+
+    fn ready_key(&self) -> Vec<u8> {
+        [self.key.clone(), b"/ready".to_vec()].concat()
+    }
+}
+
+/// Deletes this process's waiter key, if it still has one.
+async fn delete_waiter(kv: &Kv, waiter: &Rc<RefCell<Option<Vec<u8>>>>) -> Result<(), Error> {
+    let key = waiter.borrow_mut().take();
+    if let Some(key) = key {
+        let mut delete_range_request = DeleteRangeRequest::new();
+        delete_range_request.key = key;
+        kv.delete_range(delete_range_request).await?;
+    }
+    Ok(())
+}
+
+/// Creates an empty key under `prefix` named after the current time, bound to `lease_id`.
+///
+/// Returns the new key and the revision it was created at.
+async fn put_unique(kv: Kv, prefix: Vec<u8>, lease_id: i64) -> Result<(Vec<u8>, i64), Error> {
+    loop {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let key = [prefix.clone(), nanos.to_string().into_bytes()].concat();
+
+        let mut txn_request = TxnRequest::new();
+        txn_request.compare.push(compare_create_revision(
+            key.clone(),
+            Compare_CompareResult::EQUAL,
+            0,
+        ));
+        txn_request
+            .success
+            .push(put_op(key.clone(), Vec::new(), lease_id));
+
+        let response = kv.txn(txn_request).await?;
+        if response.succeeded {
+            return Ok((key, response.get_header().revision));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! A synchronous client for callers without an event loop, such as command line tools.
+//!
+//! ```no_run
+//! # fn example() -> Result<(), etcd::Error> {
+//! let client = etcd::blocking::Client::connect("localhost", 2379)?;
+//! if let Some(kv) = client.get(b"key".to_vec())? {
+//!     println!("{:?}", kv.value);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::errors::Error;
+use crate::kv::KeyValue;
+use crate::kv_client::Kv;
+use crate::lease::Lease;
+use crate::lock::Lock;
+use crate::rpc::{
+    CompactionRequest, CompactionResponse, DeleteRangeRequest, DeleteRangeResponse, PutRequest,
+    PutResponse, RangeRequest, RangeResponse, TxnRequest, TxnResponse, WatchCreateRequest,
+    WatchResponse,
+};
+use crate::stm::{self, Isolation};
+use crate::transport;
+use crate::watch::Watch;
+use futures::executor::{block_on, block_on_stream, BlockingStream};
+use std::rc::Rc;
+
+/// Blocks on each call until etcd responds.
+#[derive(Clone)]
+pub struct Client {
+    kv: Kv,
+}
+
+/// The reads and writes of one attempt at a blocking STM transaction.
+pub struct Stm<'a> {
+    stm: &'a mut stm::Stm,
+}
+
+/// Iterates over a watch's responses, blocking until each one arrives.
+pub struct WatchIter {
+    responses: BlockingStream<Watch>,
+}
+
+impl Client {
+    pub fn new<C: Into<transport::Client>>(client: C) -> Client {
+        Client {
+            kv: Kv::new(client),
+        }
+    }
+
+    /// Connects to etcd over plain HTTP/2.
+    pub fn connect(host: &str, port: u16) -> Result<Client, Error> {
+        let client = grpc::Client::new_plain(host, port, Default::default())?;
+        Ok(Client::new(client))
+    }
+
+    /// The underlying client, for the async APIs.
+    pub fn client(&self) -> transport::Client {
+        self.kv.client.clone()
+    }
+
+    pub fn get(&self, key: Vec<u8>) -> Result<Option<KeyValue>, Error> {
+        block_on(self.kv.get(key))
+    }
+
+    pub fn range(&self, range_request: RangeRequest) -> Result<RangeResponse, Error> {
+        block_on(self.kv.range(range_request))
+    }
+
+    pub fn put(&self, put_request: PutRequest) -> Result<PutResponse, Error> {
+        block_on(self.kv.put(put_request))
+    }
+
+    pub fn delete_range(
+        &self,
+        delete_range_request: DeleteRangeRequest,
+    ) -> Result<DeleteRangeResponse, Error> {
+        block_on(self.kv.delete_range(delete_range_request))
+    }
+
+    pub fn txn(&self, txn_request: TxnRequest) -> Result<TxnResponse, Error> {
+        block_on(self.kv.txn(txn_request))
+    }
+
+    pub fn compact(
+        &self,
+        compaction_request: CompactionRequest,
+    ) -> Result<CompactionResponse, Error> {
+        block_on(self.kv.compact(compaction_request))
+    }
+
+    /// Grants a lease, which is revoked when dropped.
+    pub fn lease(&self, ttl: i64) -> Result<Lease, Error> {
+        block_on(Lease::new(ttl, self.client()))
+    }
+
+    /// Blocks until the named lock is acquired. The lock is released when dropped.
+    pub fn lock(&self, name: Vec<u8>, lease: Rc<Lease>) -> Result<Lock, Error> {
+        block_on(Lock::new(name, lease))
+    }
+
+    /// Runs `apply` with serializable snapshot isolation until its writes commit without conflict.
+    pub fn stm<F, T>(&self, apply: F) -> Result<T, Error>
+    where
+        F: FnMut(&mut Stm) -> Result<T, Error>,
+    {
+        self.stm_isolated(Isolation::SerializableSnapshot, apply)
+    }
+
+    /// Runs `apply` with the given isolation until its writes commit without conflict.
+    pub fn stm_isolated<F, T>(&self, isolation: Isolation, mut apply: F) -> Result<T, Error>
+    where
+        F: FnMut(&mut Stm) -> Result<T, Error>,
+    {
+        let mut stm = stm::Stm::new(self.client(), isolation);
+
+        loop {
+            stm.reset();
+
+            let result = apply(&mut Stm { stm: &mut stm })?;
+            if block_on(stm.commit())? {
+                return Ok(result);
+            }
+
+            trace!("STM transaction conflicted, retrying...");
+        }
+    }
+
+    pub fn watch(&self, watch_create_request: WatchCreateRequest) -> WatchIter {
+        WatchIter {
+            responses: block_on_stream(Watch::new(watch_create_request, self.client())),
+        }
+    }
+}
+
+impl<'a> Stm<'a> {
+    /// The value of `key`, or an empty value if it does not exist.
+    pub fn get<K: Into<Vec<u8>>>(&mut self, key: K) -> Result<Vec<u8>, Error> {
+        block_on(self.stm.get(key))
+    }
+
+    /// The revision `key` was last modified at, or 0 if it does not exist.
+    pub fn rev<K: Into<Vec<u8>>>(&mut self, key: K) -> Result<i64, Error> {
+        block_on(self.stm.rev(key))
+    }
+
+    pub fn put<K: Into<Vec<u8>>, V: Into<Vec<u8>>>(&mut self, key: K, value: V) {
+        self.stm.put(key, value)
+    }
+
+    pub fn delete<K: Into<Vec<u8>>>(&mut self, key: K) {
+        self.stm.delete(key)
+    }
+}
+
+impl WatchIter {
+    /// Asks etcd to cancel the watch. Iteration ends once etcd confirms.
+    pub fn cancel(&mut self) {
+        self.responses.cancel()
+    }
+}
+
+impl Iterator for WatchIter {
+    type Item = Result<WatchResponse, Error>;
+
+    fn next(&mut self) -> Option<Result<WatchResponse, Error>> {
+        self.responses.next()
+    }
+}
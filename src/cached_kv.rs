@@ -0,0 +1,358 @@
+use crate::errors::Error;
+use crate::kv::{Event_EventType, KeyValue};
+use crate::kv_client::{prefix_key, prefix_range_end, Kv};
+use crate::rpc::{
+    RangeRequest, RangeRequest_SortOrder, RangeRequest_SortTarget, RangeResponse, ResponseHeader,
+    WatchCreateRequest, WatchResponse,
+};
+use crate::transport::Client;
+use crate::watch::Watch;
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::future::{select, Either};
+use futures::{FutureExt, TryStreamExt};
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+use std::time::Duration;
+
+/// A read-through cache of every key under a prefix, kept up to date by a watch.
+///
+/// The cache starts from a range of the prefix at some revision R and follows a watch from R + 1
+/// on a background thread. Each read is served from a single revision, and the response header
+/// carries that revision. If the watch fails, for instance because its revision was compacted,
+/// the cache ranges the prefix again and reads go to etcd until it has.
+///
+/// Reads outside the prefix or at a revision other than the cached one go to etcd too, and count
+/// as misses.
+pub struct CachedKv {
+    kv: Kv,
+    prefix: Vec<u8>,
+    range_end: Vec<u8>,
+    shared: Arc<Shared>,
+    // Dropping the sender stops the background thread.
+    _stop: oneshot::Sender<()>,
+}
+
+/// Counts of reads served from the cache and from etcd, and of full resyncs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub resyncs: u64,
+}
+
+struct Shared {
+    state: RwLock<State>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    resyncs: AtomicU64,
+}
+
+struct State {
+    synced: bool,
+    header: ResponseHeader,
+    entries: BTreeMap<Vec<u8>, KeyValue>,
+}
+
+impl CachedKv {
+    /// Ranges every key under `prefix` and starts following changes to them.
+    pub async fn new<C: Into<Client>>(client: C, prefix: Vec<u8>) -> Result<CachedKv, Error> {
+        let client = client.into();
+        let kv = Kv::new(client.clone());
+        let range_end = prefix_range_end(&prefix);
+        let shared = Arc::new(Shared {
+            state: RwLock::new(State {
+                synced: false,
+                header: ResponseHeader::new(),
+                entries: BTreeMap::new(),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            resyncs: AtomicU64::new(0),
+        });
+        let revision = sync(&kv, &prefix, &range_end, &shared).await?;
+
+        let (stop, stopped) = oneshot::channel();
+        let refresher = Refresher {
+            client,
+            prefix: prefix.clone(),
+            range_end: range_end.clone(),
+            shared: shared.clone(),
+        };
+        thread::Builder::new()
+            .name("etcd-cache".to_string())
+            .spawn(move || refresher.run(revision, stopped))?;
+
+        Ok(CachedKv {
+            kv,
+            prefix,
+            range_end,
+            shared,
+            _stop: stop,
+        })
+    }
+
+    pub async fn get(&self, key: Vec<u8>) -> Result<Option<KeyValue>, Error> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = key;
+        let response = self.range(range_request).await?;
+        Ok(response.kvs.into_iter().next())
+    }
+
+    /// Serves `range_request` from the cache if it can, and from etcd otherwise.
+    pub async fn range(&self, range_request: RangeRequest) -> Result<RangeResponse, Error> {
+        if let Some(response) = self.cached_range(&range_request) {
+            self.shared.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(response);
+        }
+
+        self.shared.misses.fetch_add(1, Ordering::Relaxed);
+        self.kv.range(range_request).await
+    }
+
+    /// The revision the cache has applied changes up to, or `None` while it is resyncing.
+    pub fn revision(&self) -> Option<i64> {
+        let state = self.shared.read();
+        if state.synced {
+            Some(state.header.revision)
+        } else {
+            None
+        }
+    }
+
+    pub fn metrics(&self) -> CacheMetrics {
+        CacheMetrics {
+            hits: self.shared.hits.load(Ordering::Relaxed),
+            misses: self.shared.misses.load(Ordering::Relaxed),
+            resyncs: self.shared.resyncs.load(Ordering::Relaxed),
+        }
+    }
+
+    fn cached_range(&self, request: &RangeRequest) -> Option<RangeResponse> {
+        if !contains(
+            &self.prefix,
+            &self.range_end,
+            &request.key,
+            &request.range_end,
+        ) {
+            return None;
+        }
+
+        let state = self.shared.read();
+        if !state.synced || (request.revision > 0 && request.revision != state.header.revision) {
+            return None;
+        }
+
+        let mut kvs: Vec<KeyValue> = state
+            .select(&request.key, &request.range_end)
+            .cloned()
+            .collect();
+        // Like etcd, count every key in the range, including those the revision filters drop.
+        let count = kvs.len() as i64;
+        kvs.retain(|kv| matches(request, kv));
+        sort(request, &mut kvs);
+
+        let mut response = RangeResponse::new();
+        response.set_header(state.header.clone());
+        response.count = count;
+        if request.limit > 0 && kvs.len() as i64 > request.limit {
+            kvs.truncate(request.limit as usize);
+            response.more = true;
+        }
+        if request.keys_only {
+            for kv in kvs.iter_mut() {
+                kv.value.clear();
+            }
+        }
+        if !request.count_only {
+            response.kvs = kvs.into();
+        }
+        Some(response)
+    }
+}
+
+impl CacheMetrics {
+    /// The fraction of reads served from the cache, or 0 before any reads.
+    pub fn hit_rate(&self) -> f64 {
+        let reads = self.hits + self.misses;
+        if reads == 0 {
+            0.0
+        } else {
+            self.hits as f64 / reads as f64
+        }
+    }
+}
+
+impl Shared {
+    /// Locks the cached state. A writer that panicked leaves at worst a partly applied response,
+    /// so poisoning is ignored rather than failing every later read.
+    fn read(&self) -> RwLockReadGuard<'_, State> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, State> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl State {
+    fn select<'a>(
+        &'a self,
+        key: &[u8],
+        range_end: &[u8],
+    ) -> Box<dyn Iterator<Item = &'a KeyValue> + 'a> {
+        let start = Bound::Included(key.to_vec());
+        match range_end {
+            [] => Box::new(self.entries.get(key).into_iter()),
+            [0] => Box::new(
+                self.entries
+                    .range((start, Bound::Unbounded))
+                    .map(|(_, kv)| kv),
+            ),
+            range_end if range_end <= key => Box::new(std::iter::empty()),
+            range_end => Box::new(
+                self.entries
+                    .range((start, Bound::Excluded(range_end.to_vec())))
+                    .map(|(_, kv)| kv),
+            ),
+        }
+    }
+
+    fn apply(&mut self, response: &WatchResponse) {
+        for event in response.events.iter() {
+            let kv = event.get_kv();
+            match event.field_type {
+                Event_EventType::PUT => {
+                    self.entries.insert(kv.key.clone(), kv.clone());
+                }
+                Event_EventType::DELETE => {
+                    self.entries.remove(&kv.key);
+                }
+            }
+        }
+        self.header.revision = response.get_header().revision;
+    }
+}
+
+/// Follows the watch on the prefix, resyncing whenever it fails, until told to stop.
+struct Refresher {
+    client: Client,
+    prefix: Vec<u8>,
+    range_end: Vec<u8>,
+    shared: Arc<Shared>,
+}
+
+impl Refresher {
+    fn run(self, mut revision: i64, mut stopped: oneshot::Receiver<()>) {
+        let kv = Kv::new(self.client.clone());
+        loop {
+            let error = match block_on(select(&mut stopped, self.follow(revision).boxed())) {
+                Either::Left(_) => return,
+                Either::Right((error, _)) => error,
+            };
+            warn!("Cache watch failed, resyncing: {}", error);
+            self.shared.write().synced = false;
+
+            loop {
+                if stopped.try_recv() != Ok(None) {
+                    return;
+                }
+                match block_on(sync(&kv, &self.prefix, &self.range_end, &self.shared)) {
+                    Ok(synced) => {
+                        revision = synced;
+                        self.shared.resyncs.fetch_add(1, Ordering::Relaxed);
+                        break;
+                    }
+                    Err(error) => {
+                        warn!("Could not resync cache: {}", error);
+                        thread::sleep(Duration::from_secs(1));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies every change after `revision`, returning why the watch stopped.
+    async fn follow(&self, revision: i64) -> Error {
+        let mut watch_create_request = WatchCreateRequest::new();
+        watch_create_request.key = prefix_key(&self.prefix);
+        watch_create_request.range_end = self.range_end.clone();
+        watch_create_request.start_revision = revision + 1;
+
+        let mut watch = Watch::new(watch_create_request, self.client.clone());
+        loop {
+            match watch.try_next().await {
+                Ok(Some(response)) => self.shared.write().apply(&response),
+                Ok(None) => return Error::Unrecoverable("Cache watch ended".to_string()),
+                Err(error) => return error,
+            }
+        }
+    }
+}
+
+/// Replaces the cached keys with a fresh range of the prefix, returning its revision.
+async fn sync(kv: &Kv, prefix: &[u8], range_end: &[u8], shared: &Shared) -> Result<i64, Error> {
+    let mut range_request = RangeRequest::new();
+    range_request.key = prefix_key(prefix);
+    range_request.range_end = range_end.to_vec();
+    let mut response = kv.range(range_request).await?;
+
+    let header = response.take_header();
+    let revision = header.revision;
+    let entries = response
+        .take_kvs()
+        .into_iter()
+        .map(|kv| (kv.key.clone(), kv))
+        .collect();
+    *shared.write() = State {
+        synced: true,
+        header,
+        entries,
+    };
+    Ok(revision)
+}
+
+/// Whether the range from `key` to `range_end` lies within the one from `start` to `end`, where an
+/// empty range end selects one key and `[0]` selects every key from the start.
+fn contains(start: &[u8], end: &[u8], key: &[u8], range_end: &[u8]) -> bool {
+    if key < start {
+        return false;
+    }
+
+    let unbounded = end == [0];
+    match range_end {
+        [] => unbounded || key < end,
+        [0] => unbounded,
+        range_end => unbounded || range_end <= end,
+    }
+}
+
+fn matches(request: &RangeRequest, kv: &KeyValue) -> bool {
+    (request.min_mod_revision == 0 || kv.mod_revision >= request.min_mod_revision)
+        && (request.max_mod_revision == 0 || kv.mod_revision <= request.max_mod_revision)
+        && (request.min_create_revision == 0 || kv.create_revision >= request.min_create_revision)
+        && (request.max_create_revision == 0 || kv.create_revision <= request.max_create_revision)
+}
+
+/// Sorts as etcd does: keys are already ascending, and any other target defaults to ascending.
+fn sort(request: &RangeRequest, kvs: &mut [KeyValue]) {
+    let order = match (request.sort_order, request.sort_target) {
+        (RangeRequest_SortOrder::NONE, RangeRequest_SortTarget::KEY) => return,
+        (RangeRequest_SortOrder::NONE, _) => RangeRequest_SortOrder::ASCEND,
+        (order, _) => order,
+    };
+
+    match request.sort_target {
+        RangeRequest_SortTarget::KEY => kvs.sort_by(|a, b| a.key.cmp(&b.key)),
+        RangeRequest_SortTarget::VERSION => kvs.sort_by_key(|kv| kv.version),
+        RangeRequest_SortTarget::CREATE => kvs.sort_by_key(|kv| kv.create_revision),
+        RangeRequest_SortTarget::MOD => kvs.sort_by_key(|kv| kv.mod_revision),
+        RangeRequest_SortTarget::VALUE => kvs.sort_by(|a, b| a.value.cmp(&b.value)),
+    }
+    if order == RangeRequest_SortOrder::DESCEND {
+        kvs.reverse();
+    }
+}
@@ -0,0 +1,104 @@
+use super::CliError;
+use std::collections::VecDeque;
+
+/// The arguments after the program name, consumed as each command asks for them.
+///
+/// Options are `--name`, `--name value` or `--name=value`, and may come anywhere before a `--`,
+/// after which every argument is positional. Commands take their options before their
+/// positional arguments, so an option's value is never mistaken for one.
+pub struct Args {
+    options: Vec<String>,
+    // After `--`.
+    trailing: VecDeque<String>,
+}
+
+impl Args {
+    pub fn new<I: IntoIterator<Item = String>>(args: I) -> Args {
+        let mut options = Vec::new();
+        let mut trailing = VecDeque::new();
+        let mut args = args.into_iter();
+        for arg in &mut args {
+            if arg == "--" {
+                break;
+            }
+            options.push(arg);
+        }
+        trailing.extend(args);
+
+        Args { options, trailing }
+    }
+
+    /// Whether `--name` was given.
+    pub fn flag(&mut self, name: &str) -> bool {
+        let option = format!("--{}", name);
+        match self.options.iter().position(|arg| *arg == option) {
+            Some(index) => {
+                self.options.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The value of `--name`, if it was given.
+    pub fn value(&mut self, name: &str) -> Result<Option<String>, CliError> {
+        let option = format!("--{}", name);
+        let prefix = format!("--{}=", name);
+        for index in 0..self.options.len() {
+            if let Some(value) = self.options[index].strip_prefix(&prefix) {
+                let value = value.to_string();
+                self.options.remove(index);
+                return Ok(Some(value));
+            }
+            if self.options[index] == option {
+                if index + 1 == self.options.len() {
+                    return Err(CliError::Usage(format!("{} needs a value", option)));
+                }
+                self.options.remove(index);
+                return Ok(Some(self.options.remove(index)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The value of `--name` parsed as a number, if it was given.
+    pub fn number(&mut self, name: &str) -> Result<Option<i64>, CliError> {
+        match self.value(name)? {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| CliError::Usage(format!("--{} must be a number", name))),
+            None => Ok(None),
+        }
+    }
+
+    /// The next positional argument, if there is one.
+    pub fn optional(&mut self) -> Option<String> {
+        match self.options.iter().position(|arg| !arg.starts_with("--")) {
+            Some(index) => Some(self.options.remove(index)),
+            None => self.trailing.pop_front(),
+        }
+    }
+
+    /// The next positional argument, named `name` in the error if it is missing.
+    pub fn required(&mut self, name: &str) -> Result<String, CliError> {
+        self.optional()
+            .ok_or_else(|| CliError::Usage(format!("Missing <{}>", name)))
+    }
+
+    /// Every argument after `--`.
+    pub fn trailing(&mut self) -> Vec<String> {
+        self.trailing.drain(..).collect()
+    }
+
+    /// Fails on any argument the command did not take.
+    pub fn finish(self) -> Result<(), CliError> {
+        match self.options.iter().chain(self.trailing.iter()).next() {
+            Some(arg) if arg.starts_with("--") => {
+                Err(CliError::Usage(format!("Unknown option {}", arg)))
+            }
+            Some(arg) => Err(CliError::Usage(format!("Unexpected argument {}", arg))),
+            None => Ok(()),
+        }
+    }
+}
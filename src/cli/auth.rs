@@ -0,0 +1,267 @@
+use super::args::Args;
+use super::output::{self, header, Output, Table};
+use super::{key_range, CliError, Context};
+use etcd::auth::{Permission, Permission_Type};
+use etcd::rpc::{
+    AuthRoleAddRequest, AuthRoleDeleteRequest, AuthRoleGetRequest, AuthRoleGrantPermissionRequest,
+    AuthRoleListRequest, AuthRoleRevokePermissionRequest, AuthUserAddRequest,
+    AuthUserChangePasswordRequest, AuthUserDeleteRequest, AuthUserGetRequest,
+    AuthUserGrantRoleRequest, AuthUserListRequest, AuthUserRevokeRoleRequest, ResponseHeader,
+};
+use serde_json::json;
+use std::io::{self, BufRead};
+
+pub async fn user(context: &Context, mut args: Args) -> Result<(), CliError> {
+    match args.required("user command")?.as_str() {
+        "add" => {
+            let mut auth_user_add_request = AuthUserAddRequest::new();
+            auth_user_add_request.password = password(&mut args)?;
+            auth_user_add_request.name = args.required("name")?;
+            args.finish()?;
+
+            let name = auth_user_add_request.name.clone();
+            let response = context.client.user_add(auth_user_add_request).await?;
+            done(
+                context,
+                response.get_header(),
+                format!("User {} created", name),
+            );
+        }
+        "delete" => {
+            let mut auth_user_delete_request = AuthUserDeleteRequest::new();
+            auth_user_delete_request.name = args.required("name")?;
+            args.finish()?;
+
+            let name = auth_user_delete_request.name.clone();
+            let response = context.client.user_delete(auth_user_delete_request).await?;
+            done(
+                context,
+                response.get_header(),
+                format!("User {} deleted", name),
+            );
+        }
+        "get" => {
+            let mut auth_user_get_request = AuthUserGetRequest::new();
+            auth_user_get_request.name = args.required("name")?;
+            args.finish()?;
+
+            let name = auth_user_get_request.name.clone();
+            let response = context.client.user_get(auth_user_get_request).await?;
+            let json = json!({
+                "header": header(response.get_header()),
+                "name": name,
+                "roles": response.roles.to_vec(),
+            });
+            let text = format!("User: {}\nRoles: {}", name, response.roles.join(" "));
+            context.print(Output::new(json, text));
+        }
+        "list" => {
+            args.finish()?;
+            let response = context.client.user_list(AuthUserListRequest::new()).await?;
+            let json = json!({
+                "header": header(response.get_header()),
+                "users": response.users.to_vec(),
+            });
+            context.print(Output::new(json, response.users.join("\n")));
+        }
+        "passwd" => {
+            let mut auth_user_change_password_request = AuthUserChangePasswordRequest::new();
+            auth_user_change_password_request.password = password(&mut args)?;
+            auth_user_change_password_request.name = args.required("name")?;
+            args.finish()?;
+
+            let response = context
+                .client
+                .user_change_password(auth_user_change_password_request)
+                .await?;
+            done(
+                context,
+                response.get_header(),
+                "Password updated".to_string(),
+            );
+        }
+        "grant-role" => {
+            let mut auth_user_grant_role_request = AuthUserGrantRoleRequest::new();
+            auth_user_grant_role_request.user = args.required("name")?;
+            auth_user_grant_role_request.role = args.required("role")?;
+            args.finish()?;
+
+            let text = format!(
+                "Role {} is granted to user {}",
+                auth_user_grant_role_request.role, auth_user_grant_role_request.user
+            );
+            let response = context
+                .client
+                .user_grant_role(auth_user_grant_role_request)
+                .await?;
+            done(context, response.get_header(), text);
+        }
+        "revoke-role" => {
+            let mut auth_user_revoke_role_request = AuthUserRevokeRoleRequest::new();
+            auth_user_revoke_role_request.name = args.required("name")?;
+            auth_user_revoke_role_request.role = args.required("role")?;
+            args.finish()?;
+
+            let text = format!(
+                "Role {} is revoked from user {}",
+                auth_user_revoke_role_request.role, auth_user_revoke_role_request.name
+            );
+            let response = context
+                .client
+                .user_revoke_role(auth_user_revoke_role_request)
+                .await?;
+            done(context, response.get_header(), text);
+        }
+        command => return Err(CliError::Usage(format!("Unknown user command {}", command))),
+    }
+    Ok(())
+}
+
+pub async fn role(context: &Context, mut args: Args) -> Result<(), CliError> {
+    match args.required("role command")?.as_str() {
+        "add" => {
+            let mut auth_role_add_request = AuthRoleAddRequest::new();
+            auth_role_add_request.name = args.required("name")?;
+            args.finish()?;
+
+            let name = auth_role_add_request.name.clone();
+            let response = context.client.role_add(auth_role_add_request).await?;
+            done(
+                context,
+                response.get_header(),
+                format!("Role {} created", name),
+            );
+        }
+        "delete" => {
+            let mut auth_role_delete_request = AuthRoleDeleteRequest::new();
+            auth_role_delete_request.role = args.required("name")?;
+            args.finish()?;
+
+            let name = auth_role_delete_request.role.clone();
+            let response = context.client.role_delete(auth_role_delete_request).await?;
+            done(
+                context,
+                response.get_header(),
+                format!("Role {} deleted", name),
+            );
+        }
+        "get" => {
+            let mut auth_role_get_request = AuthRoleGetRequest::new();
+            auth_role_get_request.role = args.required("name")?;
+            args.finish()?;
+
+            let name = auth_role_get_request.role.clone();
+            let response = context.client.role_get(auth_role_get_request).await?;
+            let permissions: Vec<_> = response
+                .perm
+                .iter()
+                .map(|permission| {
+                    json!({
+                        "type": permission_type(permission.permType),
+                        "key": output::bytes(&permission.key),
+                        "range_end": output::bytes(&permission.range_end),
+                    })
+                })
+                .collect();
+            let json = json!({
+                "header": header(response.get_header()),
+                "name": name,
+                "permissions": permissions,
+            });
+
+            let mut table = Table::new(vec!["TYPE", "KEY", "RANGE END"]);
+            for permission in response.perm.iter() {
+                table.row(vec![
+                    permission_type(permission.permType).to_string(),
+                    output::text(&permission.key),
+                    output::text(&permission.range_end),
+                ]);
+            }
+            let text = format!("Role {}\n{}", name, table.render());
+            context.print(Output::new(json, text));
+        }
+        "list" => {
+            args.finish()?;
+            let response = context.client.role_list(AuthRoleListRequest::new()).await?;
+            let json = json!({
+                "header": header(response.get_header()),
+                "roles": response.roles.to_vec(),
+            });
+            context.print(Output::new(json, response.roles.join("\n")));
+        }
+        "grant-permission" => {
+            let name = args.required("name")?;
+            let permission_type = match args.required("permission type")?.as_str() {
+                "read" => Permission_Type::READ,
+                "write" => Permission_Type::WRITE,
+                "readwrite" => Permission_Type::READWRITE,
+                permission_type => {
+                    return Err(CliError::Usage(format!(
+                        "Unknown permission type {}",
+                        permission_type
+                    )))
+                }
+            };
+            let (key, range_end) = key_range(&mut args)?;
+            args.finish()?;
+
+            let mut permission = Permission::new();
+            permission.permType = permission_type;
+            permission.key = key;
+            permission.range_end = range_end;
+            let mut auth_role_grant_permission_request = AuthRoleGrantPermissionRequest::new();
+            auth_role_grant_permission_request.set_perm(permission);
+            auth_role_grant_permission_request.name = name.clone();
+            let response = context
+                .client
+                .role_grant_permission(auth_role_grant_permission_request)
+                .await?;
+            let text = format!("Role {} updated", name);
+            done(context, response.get_header(), text);
+        }
+        "revoke-permission" => {
+            let name = args.required("name")?;
+            let (key, range_end) = key_range(&mut args)?;
+            args.finish()?;
+
+            let mut auth_role_revoke_permission_request = AuthRoleRevokePermissionRequest::new();
+            auth_role_revoke_permission_request.role = name.clone();
+            auth_role_revoke_permission_request.key = key;
+            auth_role_revoke_permission_request.range_end = range_end;
+            let response = context
+                .client
+                .role_revoke_permission(auth_role_revoke_permission_request)
+                .await?;
+            let text = format!("Permission of role {} is revoked", name);
+            done(context, response.get_header(), text);
+        }
+        command => return Err(CliError::Usage(format!("Unknown role command {}", command))),
+    }
+    Ok(())
+}
+
+/// Takes `--password`, or reads a line from stdin without its line ending.
+fn password(args: &mut Args) -> Result<String, CliError> {
+    if let Some(password) = args.value("password")? {
+        return Ok(password);
+    }
+    let mut password = String::new();
+    io::stdin().lock().read_line(&mut password)?;
+    Ok(password.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
+
+fn permission_type(permission_type: Permission_Type) -> &'static str {
+    match permission_type {
+        Permission_Type::READ => "read",
+        Permission_Type::WRITE => "write",
+        Permission_Type::READWRITE => "readwrite",
+    }
+}
+
+/// Prints the outcome of a change that returns nothing but a header.
+fn done(context: &Context, response_header: &ResponseHeader, text: String) {
+    context.print(Output::new(
+        json!({ "header": header(response_header) }),
+        text,
+    ));
+}
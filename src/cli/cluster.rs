@@ -0,0 +1,197 @@
+use super::args::Args;
+use super::output::{header, hex, Output, Table};
+use super::{parse_id, CliError, Context};
+use etcd::rpc::{
+    Member, MemberAddRequest, MemberListRequest, MemberRemoveRequest, RangeRequest,
+    SnapshotRequest, StatusRequest,
+};
+use futures::TryStreamExt;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::Write;
+use std::time::Instant;
+
+pub async fn member(context: &Context, mut args: Args) -> Result<(), CliError> {
+    match args.required("member command")?.as_str() {
+        "list" => {
+            args.finish()?;
+            let member_list_response = context.client.member_list(MemberListRequest::new()).await?;
+            let members = &member_list_response.members;
+            let json = json!({
+                "header": header(member_list_response.get_header()),
+                "members": members.iter().map(member_json).collect::<Vec<_>>(),
+            });
+            context.print(Output::new(json, member_table(members).render()));
+        }
+        "add" => {
+            let peer_urls = args
+                .value("peer-urls")?
+                .ok_or_else(|| CliError::Usage("Missing --peer-urls".to_string()))?;
+            args.finish()?;
+
+            let mut member_add_request = MemberAddRequest::new();
+            member_add_request.peerURLs = peer_urls.split(',').map(str::to_string).collect();
+            let member_add_response = context.client.member_add(member_add_request).await?;
+            let member = member_add_response.get_member();
+            let json = json!({
+                "header": header(member_add_response.get_header()),
+                "member": member_json(member),
+                "members": member_add_response.members.iter().map(member_json).collect::<Vec<_>>(),
+            });
+            let text = format!("Member {} added to cluster", hex(member.ID));
+            context.print(Output::new(json, text));
+        }
+        "remove" => {
+            let mut member_remove_request = MemberRemoveRequest::new();
+            member_remove_request.ID = parse_id(&args.required("id")?)?;
+            args.finish()?;
+
+            let id = member_remove_request.ID;
+            let member_remove_response =
+                context.client.member_remove(member_remove_request).await?;
+            let json = json!({ "header": header(member_remove_response.get_header()) });
+            context.print(Output::new(
+                json,
+                format!("Member {} removed from cluster", hex(id)),
+            ));
+        }
+        command => {
+            return Err(CliError::Usage(format!(
+                "Unknown member command {}",
+                command
+            )))
+        }
+    }
+    Ok(())
+}
+
+pub async fn endpoint(context: &Context, mut args: Args) -> Result<(), CliError> {
+    match args.required("endpoint command")?.as_str() {
+        "status" => {
+            args.finish()?;
+            let status_response = context.client.status(StatusRequest::new()).await?;
+            let status_header = status_response.get_header();
+            let is_leader = status_header.member_id == status_response.leader;
+            let json = json!({
+                "endpoint": context.endpoint,
+                "header": header(status_header),
+                "version": status_response.version,
+                "db_size": status_response.dbSize,
+                "leader": status_response.leader,
+                "raft_index": status_response.raftIndex,
+                "raft_term": status_response.raftTerm,
+                "errors": status_response.errors.to_vec(),
+            });
+            let mut table = Table::new(vec![
+                "ENDPOINT",
+                "ID",
+                "VERSION",
+                "DB SIZE",
+                "IS LEADER",
+                "RAFT TERM",
+                "RAFT INDEX",
+            ]);
+            table.row(vec![
+                context.endpoint.clone(),
+                hex(status_header.member_id),
+                status_response.version.clone(),
+                status_response.dbSize.to_string(),
+                is_leader.to_string(),
+                status_response.raftTerm.to_string(),
+                status_response.raftIndex.to_string(),
+            ]);
+            context.print(Output::new(json, table.render()));
+        }
+        "health" => {
+            args.finish()?;
+            // Like etcdctl, a read of any key proves the cluster can serve requests.
+            let mut range_request = RangeRequest::new();
+            range_request.key = b"health".to_vec();
+            let started = Instant::now();
+            let result = context.client.range(range_request).await;
+            let took = started.elapsed();
+
+            let json = json!({
+                "endpoint": context.endpoint,
+                "health": result.is_ok(),
+                "took": format!("{:?}", took),
+                "error": result.as_ref().err().map(|error| error.to_string()),
+            });
+            let text = match &result {
+                Ok(_) => format!(
+                    "{} is healthy: successfully committed proposal: took = {:?}",
+                    context.endpoint, took
+                ),
+                Err(error) => format!("{} is unhealthy: {}", context.endpoint, error),
+            };
+            context.print(Output::new(json, text));
+            result?;
+        }
+        command => {
+            return Err(CliError::Usage(format!(
+                "Unknown endpoint command {}",
+                command
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Saves a snapshot of the backend to a file, which only appears once the whole snapshot has
+/// arrived.
+pub async fn snapshot(context: &Context, mut args: Args) -> Result<(), CliError> {
+    match args.required("snapshot command")?.as_str() {
+        "save" => {
+            let path = args.required("file")?;
+            args.finish()?;
+
+            let partial = format!("{}.part", path);
+            let mut file = File::create(&partial)?;
+            let mut snapshot = context.client.snapshot(SnapshotRequest::new());
+            let mut size = 0;
+            while let Some(snapshot_response) = snapshot.try_next().await? {
+                file.write_all(&snapshot_response.blob)?;
+                size += snapshot_response.blob.len();
+            }
+            file.sync_all()?;
+            fs::rename(&partial, &path)?;
+
+            let json = json!({ "path": path, "size": size });
+            context.print(Output::new(json, format!("Snapshot saved at {}", path)));
+            Ok(())
+        }
+        command => Err(CliError::Usage(format!(
+            "Unknown snapshot command {}",
+            command
+        ))),
+    }
+}
+
+fn member_json(member: &Member) -> Value {
+    json!({
+        "id": member.ID,
+        "name": member.name,
+        "peer_urls": member.peerURLs.to_vec(),
+        "client_urls": member.clientURLs.to_vec(),
+    })
+}
+
+fn member_table(members: &[Member]) -> Table {
+    let mut table = Table::new(vec!["ID", "STATUS", "NAME", "PEER ADDRS", "CLIENT ADDRS"]);
+    for member in members {
+        // A member that has not started yet has no name.
+        let status = if member.name.is_empty() {
+            "unstarted"
+        } else {
+            "started"
+        };
+        table.row(vec![
+            hex(member.ID),
+            status.to_string(),
+            member.name.clone(),
+            member.peerURLs.join(","),
+            member.clientURLs.join(","),
+        ]);
+    }
+    table
+}
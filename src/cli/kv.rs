@@ -0,0 +1,332 @@
+use super::args::Args;
+use super::output::{self, header, key_value, key_values, Output};
+use super::{key_range, parse_id, CliError, Context};
+use etcd::kv::Event_EventType;
+use etcd::rpc::{
+    Compare, Compare_CompareResult, Compare_CompareTarget, Compare_oneof_target_union,
+    DeleteRangeRequest, PutRequest, RangeRequest, RequestOp, RequestOp_oneof_request, ResponseOp,
+    ResponseOp_oneof_response, TxnRequest, WatchCreateRequest,
+};
+use etcd::Watch;
+use futures::TryStreamExt;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Read};
+
+pub async fn get(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let mut range_request = RangeRequest::new();
+    range_request.limit = args.number("limit")?.unwrap_or(0);
+    range_request.revision = args.number("rev")?.unwrap_or(0);
+    range_request.keys_only = args.flag("keys-only");
+    range_request.count_only = args.flag("count-only");
+    let (key, range_end) = key_range(&mut args)?;
+    args.finish()?;
+    range_request.key = key;
+    range_request.range_end = range_end;
+
+    let keys_only = range_request.keys_only;
+    let count_only = range_request.count_only;
+    let range_response = context.client.range(range_request).await?;
+    let json = json!({
+        "header": header(range_response.get_header()),
+        "kvs": range_response.kvs.iter().map(key_value).collect::<Vec<_>>(),
+        "more": range_response.more,
+        "count": range_response.count,
+    });
+    let text = if count_only {
+        range_response.count.to_string()
+    } else {
+        key_values(range_response.kvs.iter(), keys_only).render()
+    };
+    context.print(Output::new(json, text));
+    Ok(())
+}
+
+pub async fn put(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let mut put_request = PutRequest::new();
+    if let Some(lease) = args.value("lease")? {
+        put_request.lease = parse_id(&lease)? as i64;
+    }
+    put_request.prev_kv = args.flag("prev-kv");
+    put_request.key = args.required("key")?.into_bytes();
+    put_request.value = match args.optional() {
+        Some(value) => value.into_bytes(),
+        None => {
+            let mut value = Vec::new();
+            io::stdin().read_to_end(&mut value)?;
+            value
+        }
+    };
+    args.finish()?;
+
+    let put_response = context.client.put(put_request).await?;
+    let mut json = json!({ "header": header(put_response.get_header()) });
+    let mut text = "OK\n".to_string();
+    if put_response.has_prev_kv() {
+        json["prev_kv"] = key_value(put_response.get_prev_kv());
+        text += &key_values(Some(put_response.get_prev_kv()), false).render();
+    }
+    context.print(Output::new(json, text));
+    Ok(())
+}
+
+pub async fn del(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.prev_kv = args.flag("prev-kv");
+    let (key, range_end) = key_range(&mut args)?;
+    args.finish()?;
+    delete_range_request.key = key;
+    delete_range_request.range_end = range_end;
+
+    let delete_range_response = context.client.delete_range(delete_range_request).await?;
+    let prev_kvs = &delete_range_response.prev_kvs;
+    let json = json!({
+        "header": header(delete_range_response.get_header()),
+        "deleted": delete_range_response.deleted,
+        "prev_kvs": prev_kvs.iter().map(key_value).collect::<Vec<_>>(),
+    });
+    let mut text = format!("{}\n", delete_range_response.deleted);
+    if !prev_kvs.is_empty() {
+        text += &key_values(prev_kvs.iter(), false).render();
+    }
+    context.print(Output::new(json, text));
+    Ok(())
+}
+
+/// Prints each response until the watch ends, or the process is interrupted.
+pub async fn watch(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.start_revision = args.number("rev")?.unwrap_or(0);
+    watch_create_request.prev_kv = args.flag("prev-kv");
+    let (key, range_end) = key_range(&mut args)?;
+    args.finish()?;
+    watch_create_request.key = key;
+    watch_create_request.range_end = range_end;
+
+    let mut watch = Watch::new(watch_create_request, context.client.clone());
+    while let Some(response) = watch.try_next().await? {
+        let mut table = output::Table::new(vec!["TYPE", "KEY", "VALUE", "MOD REVISION"]);
+        let mut events = Vec::new();
+        for event in response.events.iter() {
+            let kind = match event.field_type {
+                Event_EventType::PUT => "PUT",
+                Event_EventType::DELETE => "DELETE",
+            };
+            let kv = event.get_kv();
+            table.row(vec![
+                kind.to_string(),
+                output::text(&kv.key),
+                output::text(&kv.value),
+                kv.mod_revision.to_string(),
+            ]);
+            let mut json = json!({ "type": kind, "kv": key_value(kv) });
+            if event.has_prev_kv() {
+                json["prev_kv"] = key_value(event.get_prev_kv());
+            }
+            events.push(json);
+        }
+
+        let json = json!({ "header": header(response.get_header()), "events": events });
+        context.print(Output::new(json, table.render()));
+    }
+    Ok(())
+}
+
+/// Runs a transaction read from stdin in etcdctl's format: compares such as
+/// `value("key") = "v"` or `mod("key") > "5"`, then requests to run if they all hold, then
+/// requests to run otherwise, each group ending at a blank line. Reading stops at the third
+/// blank line, as etcdctl's does. Requests are `get`, `put` and `del` with the same arguments as
+/// the commands.
+pub async fn txn(context: &Context, args: Args) -> Result<(), CliError> {
+    args.finish()?;
+    let mut groups = vec![Vec::new()];
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            groups.last_mut().unwrap().push(line);
+        } else if groups.len() == 3 {
+            break;
+        } else {
+            groups.push(Vec::new());
+        }
+    }
+    groups.resize(3, Vec::new());
+
+    let mut txn_request = TxnRequest::new();
+    for line in groups[0].iter() {
+        txn_request.compare.push(parse_compare(line)?);
+    }
+    for line in groups[1].iter() {
+        txn_request.success.push(parse_request(line)?);
+    }
+    for line in groups[2].iter() {
+        txn_request.failure.push(parse_request(line)?);
+    }
+
+    let txn_response = context.client.txn(txn_request).await?;
+    let mut text = if txn_response.succeeded {
+        "SUCCESS\n".to_string()
+    } else {
+        "FAILURE\n".to_string()
+    };
+    let mut responses = Vec::new();
+    for response_op in txn_response.responses.iter() {
+        let (json, response_text) = response(response_op);
+        responses.push(json);
+        text += "\n";
+        text += &response_text;
+    }
+    let json = json!({
+        "header": header(txn_response.get_header()),
+        "succeeded": txn_response.succeeded,
+        "responses": responses,
+    });
+    context.print(Output::new(json, text));
+    Ok(())
+}
+
+fn parse_compare(line: &str) -> Result<Compare, CliError> {
+    let invalid = || CliError::Usage(format!("Invalid compare: {}", line));
+    let open = line.find('(').ok_or_else(invalid)?;
+    let words = split(&line[open + 1..]).ok_or_else(invalid)?;
+    let (key, operator, value) = match &words[..] {
+        [key, operator, value] => (key.strip_suffix(')').ok_or_else(invalid)?, operator, value),
+        _ => return Err(invalid()),
+    };
+
+    let mut compare = Compare::new();
+    compare.key = key.as_bytes().to_vec();
+    compare.result = match operator.as_str() {
+        "=" => Compare_CompareResult::EQUAL,
+        "!=" => Compare_CompareResult::NOT_EQUAL,
+        ">" => Compare_CompareResult::GREATER,
+        "<" => Compare_CompareResult::LESS,
+        _ => return Err(invalid()),
+    };
+    let number = || value.parse::<i64>().map_err(|_| invalid());
+    let (target, union) = match line[..open].trim() {
+        "value" => (
+            Compare_CompareTarget::VALUE,
+            Compare_oneof_target_union::value(value.as_bytes().to_vec()),
+        ),
+        "version" | "ver" => (
+            Compare_CompareTarget::VERSION,
+            Compare_oneof_target_union::version(number()?),
+        ),
+        "create" | "c" => (
+            Compare_CompareTarget::CREATE,
+            Compare_oneof_target_union::create_revision(number()?),
+        ),
+        "mod" | "m" => (
+            Compare_CompareTarget::MOD,
+            Compare_oneof_target_union::mod_revision(number()?),
+        ),
+        "lease" => (
+            Compare_CompareTarget::LEASE,
+            Compare_oneof_target_union::lease(parse_id(value)? as i64),
+        ),
+        _ => return Err(invalid()),
+    };
+    compare.target = target;
+    compare.target_union = Some(union);
+    Ok(compare)
+}
+
+fn parse_request(line: &str) -> Result<RequestOp, CliError> {
+    let words = split(line).ok_or_else(|| CliError::Usage(format!("Invalid request: {}", line)))?;
+    let mut args = Args::new(words);
+    let mut request_op = RequestOp::new();
+    request_op.request = Some(match args.required("request")?.as_str() {
+        "get" => {
+            let mut range_request = RangeRequest::new();
+            range_request.limit = args.number("limit")?.unwrap_or(0);
+            range_request.keys_only = args.flag("keys-only");
+            range_request.count_only = args.flag("count-only");
+            let (key, range_end) = key_range(&mut args)?;
+            range_request.key = key;
+            range_request.range_end = range_end;
+            RequestOp_oneof_request::request_range(range_request)
+        }
+        "put" => {
+            let mut put_request = PutRequest::new();
+            if let Some(lease) = args.value("lease")? {
+                put_request.lease = parse_id(&lease)? as i64;
+            }
+            put_request.prev_kv = args.flag("prev-kv");
+            put_request.key = args.required("key")?.into_bytes();
+            put_request.value = args.required("value")?.into_bytes();
+            RequestOp_oneof_request::request_put(put_request)
+        }
+        "del" => {
+            let mut delete_range_request = DeleteRangeRequest::new();
+            delete_range_request.prev_kv = args.flag("prev-kv");
+            let (key, range_end) = key_range(&mut args)?;
+            delete_range_request.key = key;
+            delete_range_request.range_end = range_end;
+            RequestOp_oneof_request::request_delete_range(delete_range_request)
+        }
+        request => return Err(CliError::Usage(format!("Unknown request {}", request))),
+    });
+    args.finish()?;
+    Ok(request_op)
+}
+
+fn response(response_op: &ResponseOp) -> (Value, String) {
+    match &response_op.response {
+        Some(ResponseOp_oneof_response::response_range(range_response)) => (
+            json!({
+                "kvs": range_response.kvs.iter().map(key_value).collect::<Vec<_>>(),
+                "more": range_response.more,
+                "count": range_response.count,
+            }),
+            key_values(range_response.kvs.iter(), false).render(),
+        ),
+        Some(ResponseOp_oneof_response::response_put(put_response)) => {
+            let mut json = json!({});
+            if put_response.has_prev_kv() {
+                json["prev_kv"] = key_value(put_response.get_prev_kv());
+            }
+            (json, "OK\n".to_string())
+        }
+        Some(ResponseOp_oneof_response::response_delete_range(delete_range_response)) => (
+            json!({
+                "deleted": delete_range_response.deleted,
+                "prev_kvs": delete_range_response
+                    .prev_kvs
+                    .iter()
+                    .map(key_value)
+                    .collect::<Vec<_>>(),
+            }),
+            format!("{}\n", delete_range_response.deleted),
+        ),
+        Some(ResponseOp_oneof_response::response_txn(txn_response)) => (
+            json!({ "succeeded": txn_response.succeeded }),
+            String::new(),
+        ),
+        None => (Value::Null, String::new()),
+    }
+}
+
+/// Splits a line into words on whitespace, where double quotes group words and `\` escapes the
+/// next character. Returns `None` if a quote is left open.
+fn split(line: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            '\\' => word.get_or_insert_with(String::new).push(chars.next()?),
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    words.extend(word);
+    Some(words)
+}
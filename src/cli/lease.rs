@@ -0,0 +1,249 @@
+use super::args::Args;
+use super::output::{self, header, hex, Output, Table};
+use super::{parse_id, sleep, CliError, Context};
+use etcd::rpc::{
+    LeaseGrantRequest, LeaseKeepAliveRequest, LeaseLeasesRequest, LeaseRevokeRequest,
+    LeaseTimeToLiveRequest,
+};
+use etcd::{Error, Lease, Lock};
+use futures::channel::{mpsc, oneshot};
+use futures::future::{select, Either};
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use serde_json::json;
+use std::io::{self, Read};
+use std::process::{Child, Command};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+pub async fn lease(context: &Context, mut args: Args) -> Result<(), CliError> {
+    match args.required("lease command")?.as_str() {
+        "grant" => grant(context, args).await,
+        "revoke" => revoke(context, args).await,
+        "timetolive" => time_to_live(context, args).await,
+        "keep-alive" => keep_alive(context, args).await,
+        "list" => list(context, args).await,
+        command => Err(CliError::Usage(format!(
+            "Unknown lease command {}",
+            command
+        ))),
+    }
+}
+
+async fn grant(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let mut lease_grant_request = LeaseGrantRequest::new();
+    lease_grant_request.TTL = ttl(&args.required("ttl")?)?;
+    args.finish()?;
+
+    let lease_grant_response = context.client.lease_grant(lease_grant_request).await?;
+    if !lease_grant_response.error.is_empty() {
+        return Err(Error::Unrecoverable(lease_grant_response.error).into());
+    }
+    let json = json!({
+        "header": header(lease_grant_response.get_header()),
+        "id": lease_grant_response.ID,
+        "ttl": lease_grant_response.TTL,
+    });
+    let text = format!(
+        "lease {} granted with TTL({}s)",
+        hex(lease_grant_response.ID),
+        lease_grant_response.TTL
+    );
+    context.print(Output::new(json, text));
+    Ok(())
+}
+
+async fn revoke(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let mut lease_revoke_request = LeaseRevokeRequest::new();
+    lease_revoke_request.ID = parse_id(&args.required("id")?)? as i64;
+    args.finish()?;
+
+    let id = lease_revoke_request.ID;
+    let lease_revoke_response = context.client.lease_revoke(lease_revoke_request).await?;
+    let json = json!({ "header": header(lease_revoke_response.get_header()) });
+    context.print(Output::new(json, format!("lease {} revoked", hex(id))));
+    Ok(())
+}
+
+async fn time_to_live(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let mut lease_time_to_live_request = LeaseTimeToLiveRequest::new();
+    lease_time_to_live_request.keys = args.flag("keys");
+    lease_time_to_live_request.ID = parse_id(&args.required("id")?)? as i64;
+    args.finish()?;
+
+    let keys = lease_time_to_live_request.keys;
+    let response = context
+        .client
+        .lease_time_to_live(lease_time_to_live_request)
+        .await?;
+    let json = json!({
+        "header": header(response.get_header()),
+        "id": response.ID,
+        "ttl": response.TTL,
+        "granted_ttl": response.grantedTTL,
+        "keys": response.keys.iter().map(|key| output::bytes(key)).collect::<Vec<_>>(),
+    });
+    let mut text = if response.TTL == -1 {
+        format!("lease {} already expired", hex(response.ID))
+    } else {
+        format!(
+            "lease {} granted with TTL({}s), remaining({}s)",
+            hex(response.ID),
+            response.grantedTTL,
+            response.TTL
+        )
+    };
+    if keys {
+        let keys: Vec<String> = response.keys.iter().map(|key| output::text(key)).collect();
+        text += &format!(", attached keys([{}])", keys.join(" "));
+    }
+    context.print(Output::new(json, text));
+    Ok(())
+}
+
+async fn list(context: &Context, args: Args) -> Result<(), CliError> {
+    args.finish()?;
+    let lease_leases_response = context
+        .client
+        .lease_leases(LeaseLeasesRequest::new())
+        .await?;
+    let ids: Vec<i64> = lease_leases_response
+        .leases
+        .iter()
+        .map(|lease| lease.ID)
+        .collect();
+
+    let json = json!({
+        "header": header(lease_leases_response.get_header()),
+        "leases": ids,
+    });
+    let mut table = Table::new(vec!["ID"]);
+    for id in ids.iter() {
+        table.row(vec![hex(*id)]);
+    }
+    context.print(Output::new(json, table.render()));
+    Ok(())
+}
+
+/// Refreshes a lease every third of its TTL until it expires, or just once with `--once`.
+async fn keep_alive(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let once = args.flag("once");
+    let id = parse_id(&args.required("id")?)? as i64;
+    args.finish()?;
+
+    // Not a `Lease`, which would revoke the lease when dropped.
+    let (requests, receiver) = mpsc::unbounded();
+    let mut responses = context.client.lease_keep_alive(receiver.boxed());
+    loop {
+        let mut lease_keep_alive_request = LeaseKeepAliveRequest::new();
+        lease_keep_alive_request.ID = id;
+        let closed = || Error::Unrecoverable("Lease keep-alive stream closed".to_string());
+        requests
+            .unbounded_send(lease_keep_alive_request)
+            .map_err(|_| closed())?;
+        let response = responses.try_next().await?.ok_or_else(closed)?;
+        if response.TTL <= 0 {
+            return Err(Error::LeaseExpired.into());
+        }
+
+        let json = json!({
+            "header": header(response.get_header()),
+            "id": response.ID,
+            "ttl": response.TTL,
+        });
+        let text = format!("lease {} keepalived with TTL({})", hex(id), response.TTL);
+        context.print(Output::new(json, text));
+        if once {
+            return Ok(());
+        }
+        sleep(refresh_interval(response.TTL)).await;
+    }
+}
+
+/// Takes a lock, then runs the command after `--` while holding it, or holds it until stdin
+/// closes. The lock is released with its lease afterwards, and the command's exit code becomes
+/// ours. If the lease cannot be kept alive the command is killed, as it no longer holds the lock.
+pub async fn lock(context: &Context, mut args: Args) -> Result<(), CliError> {
+    let ttl = args.number("ttl")?.unwrap_or(10);
+    let name = args.required("name")?;
+    let command = args.trailing();
+    args.finish()?;
+    if ttl <= 0 {
+        return Err(CliError::Usage("--ttl must be positive".to_string()));
+    }
+
+    let lease = Rc::new(Lease::new(ttl, context.client.clone()).await?);
+    let mut keep_alive = lease.keep_alive();
+    let lock = Lock::new(name.into_bytes(), lease.clone()).await?;
+    let json = json!({ "key": output::bytes(&lock.key) });
+    context.print(Output::new(json, output::text(&lock.key)));
+
+    let child = match command.split_first() {
+        Some((program, args)) => Some(Arc::new(Mutex::new(
+            Command::new(program).args(args).spawn()?,
+        ))),
+        None => None,
+    };
+    let (sender, receiver) = oneshot::channel();
+    let waited = child.clone();
+    thread::spawn(move || {
+        let result = match waited {
+            Some(child) => wait(&child),
+            None => io::stdin().read_to_end(&mut Vec::new()).map(|_| 0),
+        };
+        let _ = sender.send(result);
+    });
+
+    let mut done = receiver;
+    let code = loop {
+        match select(&mut done, sleep(refresh_interval(ttl)).boxed()).await {
+            Either::Left((result, _)) => {
+                break result.map_err(|_| Error::Unrecoverable("Command lost".to_string()))??
+            }
+            Either::Right(_) => {
+                if let Err(error) = keep_alive.refresh().await {
+                    if let Some(child) = child {
+                        let mut child = child.lock().unwrap_or_else(PoisonError::into_inner);
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    return Err(CliError::LockLost(error));
+                }
+            }
+        }
+    };
+
+    // Revoking the lease deletes the lock's key, so nothing is left to the detached unlock.
+    drop(lock);
+    if let Ok(lease) = Rc::try_unwrap(lease) {
+        lease.revoke().await?;
+    }
+    match code {
+        0 => Ok(()),
+        code => Err(CliError::Exit(code)),
+    }
+}
+
+/// Polls for the command's exit code, leaving `lock` free to kill it in between.
+fn wait(child: &Mutex<Child>) -> io::Result<i32> {
+    loop {
+        let status = child
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_wait()?;
+        if let Some(status) = status {
+            return Ok(status.code().unwrap_or(1));
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn ttl(ttl: &str) -> Result<i64, CliError> {
+    ttl.parse()
+        .map_err(|_| CliError::Usage(format!("Invalid TTL {}", ttl)))
+}
+
+fn refresh_interval(ttl: i64) -> Duration {
+    Duration::from_millis((ttl.max(1) * 1000 / 3) as u64)
+}
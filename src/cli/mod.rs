@@ -0,0 +1,224 @@
+//! The `etcd-rs` command line, a Rust stand-in for the parts of etcdctl we use.
+
+mod args;
+mod auth;
+mod cluster;
+mod kv;
+mod lease;
+mod output;
+
+use self::args::Args;
+use self::output::{Format, Output};
+use etcd::{prefix_range_end, Client, Error};
+use futures::channel::oneshot;
+use std::fmt;
+use std::future::Future;
+use std::thread;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: etcd-rs [options] <command> [arguments]
+
+Commands:
+  get <key> [range_end] [--prefix] [--from-key] [--limit=N] [--rev=N] [--keys-only]
+      [--count-only]
+  put <key> [value] [--lease=ID] [--prev-kv]      Reads the value from stdin if omitted
+  del <key> [range_end] [--prefix] [--from-key] [--prev-kv]
+  watch <key> [range_end] [--prefix] [--from-key] [--rev=N] [--prev-kv]
+  txn                                             Reads compares, then success and failure
+                                                  requests, from stdin, separated by blank
+                                                  lines
+  lease grant <ttl>
+  lease revoke <id>
+  lease timetolive <id> [--keys]
+  lease keep-alive <id> [--once]
+  lease list
+  lock <name> [--ttl=N] [-- command...]           Holds the lock while the command runs, or
+                                                  until stdin closes
+  member list
+  member add --peer-urls=URL[,URL...]
+  member remove <id>
+  endpoint status
+  endpoint health
+  snapshot save <file>
+  user add <name> [--password=P]                  Reads the password from stdin if omitted
+  user delete <name>
+  user get <name>
+  user list
+  user passwd <name> [--password=P]
+  user grant-role <name> <role>
+  user revoke-role <name> <role>
+  role add <name>
+  role delete <name>
+  role get <name>
+  role list
+  role grant-permission <name> <read|write|readwrite> <key> [range_end] [--prefix]
+      [--from-key]
+  role revoke-permission <name> <key> [range_end] [--prefix] [--from-key]
+
+Options:
+  --endpoint=URL                  http[s]://host:port or unix://path, default
+                                  http://127.0.0.1:2379
+  --cacert=FILE                   Verifies the server with this CA certificate
+  --cert=FILE --key=FILE          Identifies the client with this certificate and key
+  --insecure-skip-tls-verify      Does not verify the server's certificate
+  -w, --write-out=table|json      Prints results as tables or JSON, default table
+
+IDs of leases and members are hexadecimal. Keys and values are base64 in JSON.
+";
+
+/// Why a command failed, and so how the program exits.
+pub enum CliError {
+    /// The arguments were wrong, exiting with 2 after the usage.
+    Usage(String),
+    Etcd(Error),
+    /// A command run under `lock` exited with this code.
+    Exit(i32),
+    /// The lease behind `lock` could not be kept alive, so its command was killed.
+    LockLost(Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Etcd(error) => write!(f, "{}", error),
+            CliError::Exit(code) => write!(f, "Command exited with {}", code),
+            CliError::LockLost(error) => write!(f, "Lost the lock, killed the command: {}", error),
+        }
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(error: Error) -> CliError {
+        CliError::Etcd(error)
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(error: std::io::Error) -> CliError {
+        CliError::Etcd(Error::Io(error))
+    }
+}
+
+/// Everything a command needs besides its own arguments.
+pub struct Context {
+    pub client: Client,
+    pub endpoint: String,
+    pub format: Format,
+}
+
+impl Context {
+    pub fn print(&self, output: Output) {
+        self.format.print(output)
+    }
+}
+
+/// Runs the command in `args`, which exclude the program name.
+pub fn run<I: IntoIterator<Item = String>>(args: I) -> Result<(), CliError> {
+    // `-w` is the only short option.
+    let mut args = Args::new(args.into_iter().map(|arg| match arg.as_str() {
+        "-w" => "--write-out".to_string(),
+        _ => arg,
+    }));
+    if args.flag("help") {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+
+    let endpoint = args
+        .value("endpoint")?
+        .unwrap_or_else(|| "http://127.0.0.1:2379".to_string());
+    let format = match args.value("write-out")? {
+        Some(format) => Format::parse(&format)
+            .ok_or_else(|| CliError::Usage(format!("Unknown output format {}", format)))?,
+        None => Format::Table,
+    };
+    let mut builder = Client::builder(endpoint.clone());
+    if let Some(ca_certificate) = args.value("cacert")? {
+        builder = builder.ca_certificate_file(ca_certificate)?;
+    }
+    match (args.value("cert")?, args.value("key")?) {
+        (Some(certificate), Some(key)) => builder = builder.identity_files(certificate, key)?,
+        (None, None) => {}
+        _ => return Err(CliError::Usage("--cert and --key go together".to_string())),
+    }
+    builder = builder.insecure_skip_verify(args.flag("insecure-skip-tls-verify"));
+
+    let command = args.required("command")?;
+    block_on(async move {
+        let context = Context {
+            client: builder.connect().await?,
+            endpoint,
+            format,
+        };
+        match command.as_str() {
+            "get" => kv::get(&context, args).await,
+            "put" => kv::put(&context, args).await,
+            "del" => kv::del(&context, args).await,
+            "watch" => kv::watch(&context, args).await,
+            "txn" => kv::txn(&context, args).await,
+            "lease" => lease::lease(&context, args).await,
+            "lock" => lease::lock(&context, args).await,
+            "member" => cluster::member(&context, args).await,
+            "endpoint" => cluster::endpoint(&context, args).await,
+            "snapshot" => cluster::snapshot(&context, args).await,
+            "user" => auth::user(&context, args).await,
+            "role" => auth::role(&context, args).await,
+            command => Err(CliError::Usage(format!("Unknown command {}", command))),
+        }
+    })
+}
+
+/// Runs the command on a tokio runtime, which tonic's transport needs.
+#[cfg(feature = "tonic")]
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Could not start a tokio runtime")
+        .block_on(future)
+}
+
+#[cfg(not(feature = "tonic"))]
+fn block_on<F: Future>(future: F) -> F::Output {
+    futures::executor::block_on(future)
+}
+
+/// Resolves after `duration`, whichever executor polls it.
+pub async fn sleep(duration: Duration) {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(duration);
+        let _ = sender.send(());
+    });
+    let _ = receiver.await;
+}
+
+/// Takes a key and an optional range end, or the range `--prefix` or `--from-key` selects.
+pub fn key_range(args: &mut Args) -> Result<(Vec<u8>, Vec<u8>), CliError> {
+    let prefix = args.flag("prefix");
+    let from_key = args.flag("from-key");
+    let mut key = args.required("key")?.into_bytes();
+    let range_end = match (args.optional(), prefix, from_key) {
+        (None, false, false) => Vec::new(),
+        (None, true, false) => prefix_range_end(&key),
+        (None, false, true) => vec![0],
+        (Some(range_end), false, false) => range_end.into_bytes(),
+        _ => {
+            return Err(CliError::Usage(
+                "Give at most one of a range end, --prefix and --from-key".to_string(),
+            ))
+        }
+    };
+    // etcd rejects an empty key, so like etcdctl, an empty prefix or start means every key.
+    if key.is_empty() && (prefix || from_key) {
+        key = vec![0];
+    }
+    Ok((key, range_end))
+}
+
+/// Parses a lease or member ID, which etcdctl prints in hexadecimal.
+pub fn parse_id(id: &str) -> Result<u64, CliError> {
+    u64::from_str_radix(id, 16).map_err(|_| CliError::Usage(format!("Invalid ID {}", id)))
+}
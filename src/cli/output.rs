@@ -0,0 +1,162 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use etcd::kv::KeyValue;
+use etcd::rpc::ResponseHeader;
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// How results are printed, chosen with `--write-out`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+/// A command's result, as JSON and as text.
+pub struct Output {
+    json: Value,
+    text: String,
+}
+
+/// Columns of text, drawn with borders like etcdctl's table output.
+pub struct Table {
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Format {
+    pub fn parse(format: &str) -> Option<Format> {
+        match format {
+            "table" => Some(Format::Table),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// Prints `output` on a line of its own, so streamed results are JSON Lines.
+    pub fn print(self, output: Output) {
+        match self {
+            Format::Table => print!("{}", output.text),
+            Format::Json => println!("{}", output.json),
+        }
+    }
+}
+
+impl Output {
+    pub fn new<T: Into<String>>(json: Value, text: T) -> Output {
+        let mut text = text.into();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        Output { json, text }
+    }
+}
+
+impl Table {
+    pub fn new(header: Vec<&'static str>) -> Table {
+        Table {
+            header,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn render(&self) -> String {
+        let mut widths: Vec<usize> = self
+            .header
+            .iter()
+            .map(|cell| cell.chars().count())
+            .collect();
+        for row in self.rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let border: String = widths
+            .iter()
+            .map(|width| format!("+{}", "-".repeat(width + 2)))
+            .collect::<String>()
+            + "+\n";
+        let line = |cells: Vec<&str>| {
+            let mut line = String::new();
+            for (cell, width) in cells.iter().zip(widths.iter()) {
+                let padding = width - cell.chars().count();
+                let _ = write!(line, "| {}{} ", cell, " ".repeat(padding));
+            }
+            line + "|\n"
+        };
+
+        let mut table = border.clone();
+        table += &line(self.header.clone());
+        table += &border;
+        for row in self.rows.iter() {
+            table += &line(row.iter().map(String::as_str).collect());
+        }
+        if !self.rows.is_empty() {
+            table += &border;
+        }
+        table
+    }
+}
+
+/// Bytes in JSON, as base64 like etcdctl.
+pub fn bytes(bytes: &[u8]) -> Value {
+    Value::String(STANDARD.encode(bytes))
+}
+
+/// Bytes in a table, with anything that is not UTF-8 replaced.
+pub fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// An ID in a table, in hexadecimal like etcdctl.
+pub fn hex<I: Into<i128>>(id: I) -> String {
+    format!("{:x}", id.into())
+}
+
+pub fn header(header: &ResponseHeader) -> Value {
+    json!({
+        "cluster_id": header.cluster_id,
+        "member_id": header.member_id,
+        "revision": header.revision,
+        "raft_term": header.raft_term,
+    })
+}
+
+pub fn key_value(kv: &KeyValue) -> Value {
+    json!({
+        "key": bytes(&kv.key),
+        "value": bytes(&kv.value),
+        "create_revision": kv.create_revision,
+        "mod_revision": kv.mod_revision,
+        "version": kv.version,
+        "lease": kv.lease,
+    })
+}
+
+/// A table of key-values, without their values if `keys_only`.
+pub fn key_values<'a, I: IntoIterator<Item = &'a KeyValue>>(kvs: I, keys_only: bool) -> Table {
+    let mut table = if keys_only {
+        Table::new(vec!["KEY", "VERSION", "MOD REVISION", "LEASE"])
+    } else {
+        Table::new(vec!["KEY", "VALUE", "VERSION", "MOD REVISION", "LEASE"])
+    };
+    for kv in kvs {
+        let mut row = vec![text(&kv.key)];
+        if !keys_only {
+            row.push(text(&kv.value));
+        }
+        row.push(kv.version.to_string());
+        row.push(kv.mod_revision.to_string());
+        row.push(match kv.lease {
+            0 => String::new(),
+            lease => hex(lease),
+        });
+        table.row(row);
+    }
+    table
+}
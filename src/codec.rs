@@ -0,0 +1,62 @@
+//! Encodings between typed values and the bytes stored in etcd, for `TypedKv`.
+//!
+//! `Json` and `Protobuf` are always available. `Bincode` needs the `bincode` feature. Any other
+//! encoding can implement `Codec`.
+
+use crate::errors::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Encodes and decodes values of type `T`.
+pub trait Codec<T> {
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error>;
+
+    /// Decodes a stored value, failing with `Error::Decode` if the bytes do not hold a `T`.
+    fn decode(&self, bytes: &[u8]) -> Result<T, Error>;
+}
+
+/// JSON, via `serde_json`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+
+/// bincode's default encoding, via `bincode` 1.
+#[cfg(feature = "bincode")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bincode;
+
+/// The protobuf wire format of any `protobuf::Message`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Protobuf;
+
+impl<T: Serialize + DeserializeOwned> Codec<T> for Json {
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        serde_json::to_vec(value).map_err(|error| Error::Encode(error.to_string()))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<T, Error> {
+        serde_json::from_slice(bytes).map_err(|error| Error::Decode(error.to_string()))
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<T: Serialize + DeserializeOwned> Codec<T> for Bincode {
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        bincode::serialize(value).map_err(|error| Error::Encode(error.to_string()))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<T, Error> {
+        bincode::deserialize(bytes).map_err(|error| Error::Decode(error.to_string()))
+    }
+}
+
+impl<T: protobuf::Message> Codec<T> for Protobuf {
+    fn encode(&self, value: &T) -> Result<Vec<u8>, Error> {
+        value
+            .write_to_bytes()
+            .map_err(|error| Error::Encode(error.to_string()))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<T, Error> {
+        protobuf::parse_from_bytes(bytes).map_err(|error| Error::Decode(error.to_string()))
+    }
+}
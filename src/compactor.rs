@@ -0,0 +1,194 @@
+use crate::errors::Error;
+use crate::kv_client::{compacted, Kv};
+use crate::rpc::{CompactionRequest, RangeRequest};
+use crate::transport::Client;
+use futures::executor::block_on;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Compacts etcd's history, either to a given revision or by a `Retention` policy.
+///
+/// etcd has no record of when each revision was made, so retaining a duration relies on the
+/// revisions this compactor has sampled: each call to `retain` samples the current revision, and
+/// compacts up to the newest sample at least that old.
+///
+/// ```no_run
+/// # fn example(client: etcd::Client) -> Result<(), etcd::Error> {
+/// use etcd::{Compactor, Retention};
+/// use std::time::Duration;
+///
+/// let compaction = Compactor::new(client).spawn(
+///     Retention::Duration(Duration::from_secs(60 * 60)),
+///     Duration::from_secs(5 * 60),
+/// )?;
+/// // ...
+/// println!("Compacted up to {}", compaction.compact_revision());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Compactor {
+    kv: Kv,
+    physical: bool,
+    compact_revision: i64,
+    // Oldest first.
+    samples: VecDeque<(Instant, i64)>,
+}
+
+/// How much history `Compactor::retain` keeps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Retention {
+    /// The last this many revisions.
+    Revisions(i64),
+    /// Every revision made within this long.
+    Duration(Duration),
+}
+
+/// A compactor applying a `Retention` on a background thread, until this is dropped or stopped.
+pub struct PeriodicCompaction {
+    compact_revision: Arc<AtomicI64>,
+    // Dropping the sender stops the thread.
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl Compactor {
+    pub fn new<C: Into<Client>>(client: C) -> Compactor {
+        Compactor {
+            kv: Kv::new(client),
+            physical: false,
+            compact_revision: 0,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Waits for each compaction to be applied to etcd's backend before it completes.
+    pub fn physical(mut self, physical: bool) -> Compactor {
+        self.physical = physical;
+        self
+    }
+
+    /// The highest revision this compactor knows to have been compacted, or 0.
+    pub fn compact_revision(&self) -> i64 {
+        self.compact_revision
+    }
+
+    /// Compacts away every revision before `revision`, returning the new compaction revision.
+    ///
+    /// Fails with `Error::Compacted` if history has already been compacted beyond `revision`.
+    pub async fn compact(&mut self, revision: i64) -> Result<i64, Error> {
+        let mut compaction_request = CompactionRequest::new();
+        compaction_request.revision = revision;
+        compaction_request.physical = self.physical;
+        match self.kv.compact(compaction_request).await {
+            Ok(_) => {
+                self.compact_revision = self.compact_revision.max(revision);
+                Ok(revision)
+            }
+            // etcd refuses to compact to the compaction revision itself, which a watch from it
+            // would not report, so learn the compaction revision from the start of history.
+            Err(error) => match compacted(&self.kv.client, error, 1).await {
+                Error::Compacted { compact_revision } => {
+                    self.compact_revision = self.compact_revision.max(compact_revision);
+                    Err(Error::Compacted { compact_revision })
+                }
+                error => Err(error),
+            },
+        }
+    }
+
+    /// Samples the current revision and compacts as far as `retention` allows, returning the new
+    /// compaction revision, or `None` if there was nothing to compact.
+    pub async fn retain(&mut self, retention: Retention) -> Result<Option<i64>, Error> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = vec![0];
+        range_request.count_only = true;
+        let revision = self.kv.range(range_request).await?.get_header().revision;
+        let now = Instant::now();
+        self.samples.push_back((now, revision));
+
+        let target = match retention {
+            Retention::Revisions(revisions) => revision - revisions,
+            Retention::Duration(duration) => {
+                // Samples before the newest one old enough are no longer needed.
+                let old = self
+                    .samples
+                    .iter()
+                    .take_while(|(sampled, _)| now.duration_since(*sampled) >= duration)
+                    .count();
+                if old == 0 {
+                    return Ok(None);
+                }
+                self.samples.drain(..old - 1);
+                self.samples[0].1
+            }
+        };
+        if target <= self.compact_revision {
+            return Ok(None);
+        }
+
+        match self.compact(target).await {
+            Ok(compact_revision) => Ok(Some(compact_revision)),
+            // Someone else compacted further already.
+            Err(Error::Compacted { .. }) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Applies `retention` every `interval` on a background thread.
+    pub fn spawn(
+        self,
+        retention: Retention,
+        interval: Duration,
+    ) -> Result<PeriodicCompaction, Error> {
+        let compact_revision = Arc::new(AtomicI64::new(self.compact_revision));
+        let (stop, stopped) = mpsc::channel();
+        let shared = compact_revision.clone();
+        let thread = thread::Builder::new()
+            .name("etcd-compactor".to_string())
+            .spawn(move || self.run(retention, interval, shared, stopped))?;
+
+        Ok(PeriodicCompaction {
+            compact_revision,
+            stop,
+            thread,
+        })
+    }
+
+    fn run(
+        mut self,
+        retention: Retention,
+        interval: Duration,
+        compact_revision: Arc<AtomicI64>,
+        stopped: Receiver<()>,
+    ) {
+        loop {
+            match block_on(self.retain(retention)) {
+                Ok(Some(revision)) => debug!("Compacted etcd up to revision {}", revision),
+                Ok(None) => {}
+                Err(error) => warn!("Could not compact etcd: {}", error),
+            }
+            compact_revision.store(self.compact_revision, Ordering::Relaxed);
+
+            if stopped.recv_timeout(interval) != Err(RecvTimeoutError::Timeout) {
+                return;
+            }
+        }
+    }
+}
+
+impl PeriodicCompaction {
+    /// The highest revision compacted so far, or 0.
+    pub fn compact_revision(&self) -> i64 {
+        self.compact_revision.load(Ordering::Relaxed)
+    }
+
+    /// Stops compacting, waiting for any compaction in progress to finish.
+    pub fn stop(self) {
+        drop(self.stop);
+        let _ = self.thread.join();
+    }
+}
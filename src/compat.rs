@@ -0,0 +1,33 @@
+//! Adapters for callers still on futures 0.1 while they move to async/await.
+//!
+//! ```no_run
+//! # use std::rc::Rc;
+//! # use std::sync::Arc;
+//! # fn example(client: Arc<grpc::Client>) -> impl futures01::Future<Item = (), Error = etcd::Error> {
+//! use futures01::Future;
+//!
+//! etcd::compat::future01(etcd::Lease::new(60, client.clone()))
+//!     .and_then(|lease| etcd::compat::future01(etcd::Lock::new(b"name".to_vec(), Rc::new(lease))))
+//!     .map(|_lock| ())
+//! # }
+//! ```
+
+use futures::compat::Compat;
+use futures::{TryFuture, TryStream};
+use std::pin::Pin;
+
+/// Adapts a `std::future::Future` into a futures 0.1 `Future`.
+pub fn future01<F>(future: F) -> Compat<Pin<Box<F>>>
+where
+    F: TryFuture,
+{
+    Compat::new(Box::pin(future))
+}
+
+/// Adapts a futures 0.3 `Stream` into a futures 0.1 `Stream`.
+pub fn stream01<S>(stream: S) -> Compat<Pin<Box<S>>>
+where
+    S: TryStream,
+{
+    Compat::new(Box::pin(stream))
+}
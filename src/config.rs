@@ -0,0 +1,123 @@
+use crate::errors::Error;
+use crate::kv_client::Kv;
+use crate::transport::Client;
+use crate::watch::{prefix_snapshots, range_prefix, Snapshot};
+use futures::stream::{BoxStream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+type Validator<T> = Arc<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+/// Loads a `T` from every key under a prefix, and reloads it whenever any of them change.
+///
+/// Each key's path below the prefix, split on `/`, names a field, so `app/db/port` under `app/`
+/// sets `db.port`. Values are parsed as JSON where they can be, so numbers, booleans and quoted
+/// strings keep their types, and anything else is taken as a string.
+///
+/// ```no_run
+/// # #[derive(serde::Deserialize)]
+/// # struct Config { replicas: u32 }
+/// # async fn example(client: etcd::Client) -> Result<(), etcd::Error> {
+/// use futures::TryStreamExt;
+///
+/// let mut configs = etcd::ConfigWatcher::<Config>::new(client, b"app/".to_vec())
+///     .validate(|config| match config.replicas {
+///         0 => Err("replicas must be positive".to_string()),
+///         _ => Ok(()),
+///     })
+///     .watch();
+/// while let Some(config) = configs.try_next().await? {
+///     // ...
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ConfigWatcher<T> {
+    kv: Kv,
+    prefix: Vec<u8>,
+    validators: Vec<Validator<T>>,
+}
+
+impl<T: DeserializeOwned + Send + 'static> ConfigWatcher<T> {
+    pub fn new<C: Into<Client>>(client: C, prefix: Vec<u8>) -> ConfigWatcher<T> {
+        ConfigWatcher {
+            kv: Kv::new(client),
+            prefix,
+            validators: Vec::new(),
+        }
+    }
+
+    /// Rejects any configuration `validator` returns an error for, including the first.
+    pub fn validate<F>(mut self, validator: F) -> ConfigWatcher<T>
+    where
+        F: Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Reads the configuration once.
+    pub async fn load(&self) -> Result<T, Error> {
+        let (keys, _) = range_prefix(&self.kv.client, &self.prefix).await?;
+        self.assemble(&keys)
+    }
+
+    /// Streams the current configuration, then a new one after every revision that changes a key
+    /// under the prefix.
+    ///
+    /// A configuration that fails to decode or validate is yielded as an error, and the stream
+    /// carries on with the next change. A failed watch ends the stream after its error.
+    pub fn watch(self) -> BoxStream<'static, Result<T, Error>> {
+        prefix_snapshots(self.kv.client.clone(), self.prefix.clone())
+            .map(move |keys| self.assemble(&keys?))
+            .boxed()
+    }
+
+    /// Builds the tree of fields from `keys`, decodes it and runs the validators.
+    fn assemble(&self, keys: &Snapshot) -> Result<T, Error> {
+        let mut tree = Map::new();
+        for (key, value) in keys.iter() {
+            let path = String::from_utf8_lossy(&key[self.prefix.len()..]).into_owned();
+            insert(&mut tree, &path, leaf(value))?;
+        }
+
+        let config: T = serde_json::from_value(Value::Object(tree))
+            .map_err(|error| Error::Decode(error.to_string()))?;
+        for validator in self.validators.iter() {
+            validator(&config).map_err(Error::InvalidConfig)?;
+        }
+        Ok(config)
+    }
+}
+
+/// Sets the field at `path`, creating the objects above it.
+fn insert(tree: &mut Map<String, Value>, path: &str, value: Value) -> Result<(), Error> {
+    let conflict = || Error::Decode(format!("{} is both a value and a prefix", path));
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    // The prefix itself names no field.
+    let field = match segments.pop() {
+        Some(field) => field,
+        None => return Ok(()),
+    };
+
+    let mut object = tree;
+    for segment in segments {
+        let child = object
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        object = match child {
+            Value::Object(child) => child,
+            _ => return Err(conflict()),
+        };
+    }
+    match object.insert(field.to_string(), value) {
+        Some(Value::Object(_)) => Err(conflict()),
+        _ => Ok(()),
+    }
+}
+
+fn leaf(value: &[u8]) -> Value {
+    serde_json::from_slice(value)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(value).into_owned()))
+}
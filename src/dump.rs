@@ -0,0 +1,211 @@
+//! Exports key ranges to JSON Lines and imports them back, for cloning an environment or
+//! migrating it between clusters.
+//!
+//! Each line holds one `Record`, with the key and value in base64:
+//!
+//! ```text
+//! {"key":"YXBwL3JlcGxpY2Fz","value":"Mw==","lease":0,"create_revision":2,"mod_revision":5,"version":2}
+//! ```
+
+use crate::errors::Error;
+use crate::kv::KeyValue;
+use crate::kv_client::{compare_create_revision, put_op, Kv};
+use crate::rpc::{Compare_CompareResult, RequestOp, RequestOp_oneof_request, TxnRequest};
+use crate::transport::Client;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/// Keys ranged per request while exporting.
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+/// One exported key-value.
+///
+/// Revisions and versions record where the key was exported from, and cannot be restored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(with = "base64_bytes")]
+    pub key: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub value: Vec<u8>,
+    pub lease: i64,
+    pub create_revision: i64,
+    pub mod_revision: i64,
+    pub version: i64,
+}
+
+/// How `import` writes records.
+#[derive(Clone, Copy, Debug)]
+pub struct ImportOptions {
+    batch_size: usize,
+    preserve_leases: bool,
+    skip_existing: bool,
+}
+
+/// Counts of the records `import` wrote and skipped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+impl From<KeyValue> for Record {
+    fn from(kv: KeyValue) -> Record {
+        Record {
+            key: kv.key,
+            value: kv.value,
+            lease: kv.lease,
+            create_revision: kv.create_revision,
+            mod_revision: kv.mod_revision,
+            version: kv.version,
+        }
+    }
+}
+
+impl ImportOptions {
+    /// Writes 100 records per transaction, dropping leases and overwriting existing keys.
+    pub fn new() -> ImportOptions {
+        ImportOptions {
+            batch_size: 100,
+            preserve_leases: false,
+            skip_existing: false,
+        }
+    }
+
+    /// Writes `batch_size` records per transaction, which etcd limits to 128 by default.
+    pub fn batch_size(mut self, batch_size: usize) -> ImportOptions {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Attaches each key to the lease ID it was exported with, which must exist.
+    pub fn preserve_leases(mut self, preserve_leases: bool) -> ImportOptions {
+        self.preserve_leases = preserve_leases;
+        self
+    }
+
+    /// Leaves keys that already exist alone, rather than overwriting them.
+    pub fn skip_existing(mut self, skip_existing: bool) -> ImportOptions {
+        self.skip_existing = skip_existing;
+        self
+    }
+}
+
+impl Default for ImportOptions {
+    fn default() -> ImportOptions {
+        ImportOptions::new()
+    }
+}
+
+/// Writes every key under `prefix` to `writer`, one `Record` per line, returning how many there
+/// were.
+///
+/// The keys are ranged a page at a time, all at one revision.
+pub async fn export<C: Into<Client>, W: Write>(
+    client: C,
+    prefix: Vec<u8>,
+    mut writer: W,
+) -> Result<usize, Error> {
+    let mut kvs = Kv::new(client).scan(prefix, EXPORT_PAGE_SIZE);
+    let mut exported = 0;
+    while let Some(kv) = kvs.try_next().await? {
+        serde_json::to_writer(&mut writer, &Record::from(kv))
+            .map_err(|error| Error::Encode(error.to_string()))?;
+        writer.write_all(b"\n")?;
+        exported += 1;
+    }
+    writer.flush()?;
+
+    Ok(exported)
+}
+
+/// Writes every `Record` in `reader` back to etcd, in transactions of `options.batch_size`.
+///
+/// Blank lines are ignored. A line that does not hold a record fails the import, leaving the
+/// batches before it written.
+pub async fn import<C: Into<Client>, R: BufRead>(
+    client: C,
+    reader: R,
+    options: ImportOptions,
+) -> Result<ImportSummary, Error> {
+    let kv = Kv::new(client);
+    let mut summary = ImportSummary::default();
+    let mut batch = Vec::with_capacity(options.batch_size);
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Record = serde_json::from_str(&line)
+            .map_err(|error| Error::Decode(format!("line {}: {}", number + 1, error)))?;
+
+        batch.push(record);
+        if batch.len() == options.batch_size {
+            write_batch(&kv, batch.drain(..), &options, &mut summary).await?;
+        }
+    }
+    if !batch.is_empty() {
+        write_batch(&kv, batch.drain(..), &options, &mut summary).await?;
+    }
+
+    Ok(summary)
+}
+
+async fn write_batch<I: Iterator<Item = Record>>(
+    kv: &Kv,
+    records: I,
+    options: &ImportOptions,
+    summary: &mut ImportSummary,
+) -> Result<(), Error> {
+    let mut txn_request = TxnRequest::new();
+    for record in records {
+        let lease = if options.preserve_leases {
+            record.lease
+        } else {
+            0
+        };
+        let put = put_op(record.key.clone(), record.value, lease);
+        if options.skip_existing {
+            // A transaction of its own for each key, so one existing key skips only itself.
+            let mut guarded = TxnRequest::new();
+            guarded.compare.push(compare_create_revision(
+                record.key,
+                Compare_CompareResult::EQUAL,
+                0,
+            ));
+            guarded.success.push(put);
+            let mut request_op = RequestOp::new();
+            request_op.request = Some(RequestOp_oneof_request::request_txn(guarded));
+            txn_request.success.push(request_op);
+        } else {
+            txn_request.success.push(put);
+        }
+    }
+
+    let txn_response = kv.txn(txn_request).await?;
+    for response_op in txn_response.responses.iter() {
+        if response_op.has_response_txn() && !response_op.get_response_txn().succeeded {
+            summary.skipped += 1;
+        } else {
+            summary.imported += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Serializes bytes as a base64 string.
+mod base64_bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(D::Error::custom)
+    }
+}
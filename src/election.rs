@@ -0,0 +1,319 @@
+use crate::errors::Error;
+use crate::kv::{Event_EventType, KeyValue};
+use crate::kv_client::{
+    compare_create_revision, delete_op, prefix_range_end, put_op, range_op, Kv,
+};
+use crate::lease::Lease;
+use crate::rpc::{
+    Compare_CompareResult, RangeRequest, RangeRequest_SortOrder, RangeRequest_SortTarget,
+    ResponseOp_oneof_response, TxnRequest, WatchCreateRequest,
+};
+use crate::transport::Client;
+use crate::watch::{wait_delete, wait_prefix_put, Watch};
+use futures::stream::{self, Stream, TryStreamExt};
+use futures::TryFutureExt;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Client side leader election, compatible with etcd's `concurrency.Election`.
+///
+/// Each candidate writes a key named after its lease under the election prefix; the
+/// candidate whose key has the lowest create revision is the leader.
+pub struct Election {
+    prefix: Vec<u8>,
+    lease: Rc<Lease>,
+    leadership: Rc<RefCell<Option<Leadership>>>,
+}
+
+#[derive(Clone)]
+struct Leadership {
+    key: Vec<u8>,
+    revision: i64,
+}
+
+enum Observation {
+    Leaderless,
+    Following {
+        watch: Option<Watch>,
+        pending: VecDeque<KeyValue>,
+    },
+}
+
+impl Election {
+    pub fn new(name: Vec<u8>, lease: Rc<Lease>) -> Election {
+        let mut prefix = name;
+        prefix.push(b'/');
+
+        Election {
+            prefix,
+            lease,
+            leadership: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// The key this candidate campaigned with, once it is the leader.
+    pub fn key(&self) -> Option<Vec<u8>> {
+        self.leadership
+            .borrow()
+            .as_ref()
+            .map(|leadership| leadership.key.clone())
+    }
+
+    /// Resolves once this candidate has been elected leader with the given value.
+    pub async fn campaign(&self, value: Vec<u8>) -> Result<(), Error> {
+        trace!("Campaigning...");
+
+        let kv = Kv::new(self.lease.client.clone());
+        let result = campaign(
+            kv.clone(),
+            self.prefix.clone(),
+            self.leadership.clone(),
+            self.lease.lease_id,
+            value,
+        )
+        .await;
+        if result.is_err() {
+            let _ = resign(kv, self.leadership.clone()).await;
+        }
+        result
+    }
+
+    /// Updates the leader's value without starting a new election.
+    pub async fn proclaim(&self, value: Vec<u8>) -> Result<(), Error> {
+        let current = self.leadership.borrow().clone();
+        match current {
+            Some(Leadership { key, revision }) => {
+                proclaim(
+                    Kv::new(self.lease.client.clone()),
+                    self.leadership.clone(),
+                    key,
+                    revision,
+                    value,
+                    self.lease.lease_id,
+                )
+                .await
+            }
+            None => Err(Error::ElectionNotLeader),
+        }
+    }
+
+    /// Gives up leadership so another candidate can be elected.
+    pub async fn resign(&self) -> Result<(), Error> {
+        trace!("Resigning...");
+
+        resign(Kv::new(self.lease.client.clone()), self.leadership.clone()).await
+    }
+
+    /// The current leader's key and value.
+    pub async fn leader(&self) -> Result<KeyValue, Error> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = self.prefix.clone();
+        range_request.range_end = prefix_range_end(&self.prefix);
+        range_request.sort_target = RangeRequest_SortTarget::CREATE;
+        range_request.sort_order = RangeRequest_SortOrder::ASCEND;
+        range_request.limit = 1;
+
+        let response = Kv::new(self.lease.client.clone())
+            .range(range_request)
+            .await?;
+        response
+            .kvs
+            .into_iter()
+            .next()
+            .ok_or(Error::ElectionNoLeader)
+    }
+
+    /// Streams the leader's key and value every time leadership or the leader's value changes.
+    pub fn observe(&self) -> impl Stream<Item = Result<KeyValue, Error>> {
+        let client = self.lease.client.clone();
+        let prefix = self.prefix.clone();
+
+        stream::try_unfold(Observation::Leaderless, move |observation| {
+            next_leader(client.clone(), prefix.clone(), observation).map_ok(Some)
+        })
+    }
+}
+
+async fn campaign(
+    kv: Kv,
+    prefix: Vec<u8>,
+    leadership: Rc<RefCell<Option<Leadership>>>,
+    lease_id: i64,
+    value: Vec<u8>,
+) -> Result<(), Error> {
+    let key = [prefix.clone(), format!("{:x}", lease_id).into_bytes()].concat();
+
+    let mut txn_request = TxnRequest::new();
+    txn_request.compare.push(compare_create_revision(
+        key.clone(),
+        Compare_CompareResult::EQUAL,
+        0,
+    ));
+    txn_request
+        .success
+        .push(put_op(key.clone(), value.clone(), lease_id));
+    txn_request.failure.push(range_op(key.clone()));
+
+    let response = kv.txn(txn_request).await?;
+    let mut revision = response.get_header().revision;
+    let mut update = None;
+    if !response.succeeded {
+        let existing = response
+            .responses
+            .into_iter()
+            .next()
+            .and_then(|op| match op.response {
+                Some(ResponseOp_oneof_response::response_range(range)) => {
+                    range.kvs.into_iter().next()
+                }
+                _ => None,
+            })
+            .ok_or_else(|| {
+                Error::Unrecoverable("Campaign key disappeared during campaign".to_string())
+            })?;
+        revision = existing.create_revision;
+        if existing.value != value {
+            update = Some(value);
+        }
+    }
+
+    *leadership.borrow_mut() = Some(Leadership {
+        key: key.clone(),
+        revision,
+    });
+
+    if let Some(value) = update {
+        proclaim(kv.clone(), leadership, key, revision, value, lease_id).await?;
+    }
+    wait_deletes(kv, prefix, revision - 1).await
+}
+
+async fn proclaim(
+    kv: Kv,
+    leadership: Rc<RefCell<Option<Leadership>>>,
+    key: Vec<u8>,
+    revision: i64,
+    value: Vec<u8>,
+    lease_id: i64,
+) -> Result<(), Error> {
+    let mut txn_request = TxnRequest::new();
+    txn_request.compare.push(compare_create_revision(
+        key.clone(),
+        Compare_CompareResult::EQUAL,
+        revision,
+    ));
+    txn_request.success.push(put_op(key, value, lease_id));
+
+    let response = kv.txn(txn_request).await?;
+    if response.succeeded {
+        Ok(())
+    } else {
+        *leadership.borrow_mut() = None;
+        Err(Error::ElectionNotLeader)
+    }
+}
+
+async fn resign(kv: Kv, leadership: Rc<RefCell<Option<Leadership>>>) -> Result<(), Error> {
+    let current = leadership.borrow_mut().take();
+    if let Some(Leadership { key, revision }) = current {
+        let mut txn_request = TxnRequest::new();
+        txn_request.compare.push(compare_create_revision(
+            key.clone(),
+            Compare_CompareResult::EQUAL,
+            revision,
+        ));
+        txn_request.success.push(delete_op(key));
+        kv.txn(txn_request).await?;
+    }
+    Ok(())
+}
+
+/// Waits until every key under `prefix` created at or before `max_create_revision` is deleted.
+async fn wait_deletes(kv: Kv, prefix: Vec<u8>, max_create_revision: i64) -> Result<(), Error> {
+    loop {
+        let mut range_request = RangeRequest::new();
+        range_request.key = prefix.clone();
+        range_request.range_end = prefix_range_end(&prefix);
+        range_request.sort_target = RangeRequest_SortTarget::CREATE;
+        range_request.sort_order = RangeRequest_SortOrder::DESCEND;
+        range_request.max_create_revision = max_create_revision;
+        range_request.limit = 1;
+
+        let response = kv.range(range_request).await?;
+        let revision = response.get_header().revision;
+        match response.kvs.into_iter().next() {
+            Some(last) => wait_delete(kv.client.clone(), last.key, revision).await?,
+            None => return Ok(()),
+        }
+    }
+}
+
+async fn next_leader(
+    client: Client,
+    prefix: Vec<u8>,
+    mut observation: Observation,
+) -> Result<(KeyValue, Observation), Error> {
+    loop {
+        observation = match observation {
+            Observation::Leaderless => {
+                let mut range_request = RangeRequest::new();
+                range_request.key = prefix.clone();
+                range_request.range_end = prefix_range_end(&prefix);
+                range_request.sort_target = RangeRequest_SortTarget::CREATE;
+                range_request.sort_order = RangeRequest_SortOrder::ASCEND;
+                range_request.limit = 1;
+
+                let response = Kv::new(client.clone()).range(range_request).await?;
+                let revision = response.get_header().revision;
+                let (leader, revision) = match response.kvs.into_iter().next() {
+                    Some(leader) => (leader, revision),
+                    None => {
+                        let leader =
+                            wait_prefix_put(client.clone(), prefix.clone(), revision).await?;
+                        let revision = leader.mod_revision;
+                        (leader, revision)
+                    }
+                };
+
+                let mut watch_create_request = WatchCreateRequest::new();
+                watch_create_request.key = leader.key.clone();
+                watch_create_request.start_revision = revision + 1;
+                let watch = Watch::new(watch_create_request, client);
+
+                return Ok((
+                    leader,
+                    Observation::Following {
+                        watch: Some(watch),
+                        pending: VecDeque::new(),
+                    },
+                ));
+            }
+            Observation::Following { watch, mut pending } => {
+                if let Some(leader) = pending.pop_front() {
+                    return Ok((leader, Observation::Following { watch, pending }));
+                }
+
+                match watch {
+                    Some(mut watch) => {
+                        let response = watch.try_next().await?.ok_or_else(|| {
+                            Error::Unrecoverable("Lost watcher observing leader".to_string())
+                        })?;
+
+                        let mut watch = Some(watch);
+                        for event in response.events.into_iter() {
+                            if event.field_type == Event_EventType::DELETE {
+                                watch = None;
+                                break;
+                            }
+                            pending.push_back(event.kv.unwrap_or_default());
+                        }
+
+                        Observation::Following { watch, pending }
+                    }
+                    None => Observation::Leaderless,
+                }
+            }
+        };
+    }
+}
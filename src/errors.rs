@@ -1,13 +1,34 @@
-use std::convert::From;
-
-use grpc;
+// failure's derive expands to impls nested inside a const block.
+#![allow(non_local_definitions)]
 
 #[derive(Fail, Debug)]
 pub enum Error {
     #[fail(display = "GRPC error: {}", _0)]
     Grpc(grpc::Error),
+    #[fail(display = "IO error: {}", _0)]
+    Io(std::io::Error),
+    #[fail(display = "TLS error: {}", _0)]
+    Tls(String),
+    #[fail(display = "Could not encode value: {}", _0)]
+    Encode(String),
+    #[fail(display = "Could not decode value: {}", _0)]
+    Decode(String),
+    #[fail(display = "Invalid configuration: {}", _0)]
+    InvalidConfig(String),
     #[fail(display = "Unrecoverable error: {}", _0)]
     Unrecoverable(String),
+    #[fail(display = "Revision has been compacted at {}", compact_revision)]
+    Compacted { compact_revision: i64 },
+    #[fail(display = "Election has no leader")]
+    ElectionNoLeader,
+    #[fail(display = "Election is not led by this candidate")]
+    ElectionNotLeader,
+    #[fail(display = "Lease has expired")]
+    LeaseExpired,
+    #[fail(display = "Key already exists")]
+    KeyExists,
+    #[fail(display = "Too many clients entered the barrier")]
+    TooManyClients,
 }
 
 impl From<grpc::Error> for Error {
@@ -15,3 +36,9 @@ impl From<grpc::Error> for Error {
         Error::Grpc(rpc_error)
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(io_error: std::io::Error) -> Error {
+        Error::Io(io_error)
+    }
+}
@@ -0,0 +1,289 @@
+use crate::errors::Error;
+use crate::kv::{Event, KeyValue};
+use crate::rpc::{
+    CompactionRequest, CompactionResponse, Compare, Compare_CompareResult, Compare_CompareTarget,
+    Compare_oneof_target_union, DeleteRangeRequest, DeleteRangeResponse, PutRequest, PutResponse,
+    RangeRequest, RangeResponse, RequestOp, RequestOp_oneof_request, TxnRequest, TxnResponse,
+    WatchCreateRequest,
+};
+use crate::transport::Client;
+use crate::watch::Watch;
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use futures::{Future, TryFutureExt};
+
+#[derive(Clone)]
+pub struct Kv {
+    pub client: Client,
+}
+
+impl Kv {
+    pub fn new<C: Into<Client>>(client: C) -> Kv {
+        Kv {
+            client: client.into(),
+        }
+    }
+
+    pub fn get(&self, key: Vec<u8>) -> impl Future<Output = Result<Option<KeyValue>, Error>> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = key;
+        self.range(range_request)
+            .map_ok(|response| response.kvs.into_iter().next())
+    }
+
+    /// Reads `key` as it was at `revision`, failing with `Error::Compacted` if that revision has
+    /// been compacted away.
+    pub async fn get_at(&self, key: Vec<u8>, revision: i64) -> Result<Option<KeyValue>, Error> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = key;
+        range_request.revision = revision;
+        match self.range(range_request).await {
+            Ok(range_response) => Ok(range_response.kvs.into_iter().next()),
+            Err(error) => Err(compacted(&self.client, error, revision).await),
+        }
+    }
+
+    /// Every change to `key` from `from_revision` through `to_revision`, oldest first.
+    ///
+    /// The changes are replayed by a watch from `from_revision`, so this fails with
+    /// `Error::Compacted` if either revision has been compacted away. The compaction revision
+    /// itself is kept, so history can start there.
+    pub async fn history(
+        &self,
+        key: Vec<u8>,
+        from_revision: i64,
+        to_revision: i64,
+    ) -> Result<Vec<Event>, Error> {
+        let from_revision = from_revision.max(1);
+        if from_revision > to_revision {
+            return Ok(Vec::new());
+        }
+
+        // A watch never says when it has replayed a revision, so stop at the first event known
+        // to come at or after the last change: the key's last put if it exists at `to_revision`,
+        // and otherwise its next put if it exists now. Reading the key at `to_revision` also
+        // rejects a revision that has not happened yet.
+        let mut watch_create_request = WatchCreateRequest::new();
+        watch_create_request.key = key.clone();
+        let last_revision = match self.get_at(key.clone(), to_revision).await? {
+            Some(kv) if kv.mod_revision < from_revision => return Ok(Vec::new()),
+            Some(kv) => kv.mod_revision,
+            None if self.get(key.clone()).await?.is_some() => to_revision + 1,
+            None if to_revision == 1 => return Ok(Vec::new()),
+            None => {
+                // No read tells when a key that is gone for good was deleted, but every revision
+                // after the first changed some key, so watch them all up to `to_revision`.
+                watch_create_request.key = vec![0];
+                watch_create_request.range_end = vec![0];
+                to_revision
+            }
+        };
+        watch_create_request.start_revision = from_revision;
+
+        let mut history = Vec::new();
+        let mut watch = Watch::new(watch_create_request, self.client.clone());
+        while let Some(response) = watch.try_next().await? {
+            let mut done = false;
+            for event in response.events.into_iter() {
+                let revision = event.get_kv().mod_revision;
+                done |= revision >= last_revision;
+                if revision <= to_revision && event.get_kv().key == key {
+                    history.push(event);
+                }
+            }
+            if done {
+                watch.cancel();
+                return Ok(history);
+            }
+        }
+
+        Err(Error::Unrecoverable(
+            "Lost watcher reading history".to_string(),
+        ))
+    }
+
+    pub fn range(
+        &self,
+        range_request: RangeRequest,
+    ) -> impl Future<Output = Result<RangeResponse, Error>> {
+        self.client.range(range_request)
+    }
+
+    /// Streams every key under `prefix` in key order, ranging `page_size` keys at a time.
+    ///
+    /// Every page is read at the revision of the first, so the keys are a consistent snapshot
+    /// even if the prefix changes during the scan. A `page_size` of 0 ranges every key at once.
+    pub fn scan(
+        &self,
+        prefix: Vec<u8>,
+        page_size: i64,
+    ) -> BoxStream<'static, Result<KeyValue, Error>> {
+        self.scan_at(prefix, page_size, 0)
+    }
+
+    /// Like `scan`, but reads every page at `revision`, or at the first page's if it is 0.
+    pub fn scan_at(
+        &self,
+        prefix: Vec<u8>,
+        page_size: i64,
+        revision: i64,
+    ) -> BoxStream<'static, Result<KeyValue, Error>> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = prefix_key(&prefix);
+        range_request.range_end = prefix_range_end(&prefix);
+        range_request.limit = page_size;
+        range_request.revision = revision;
+
+        let client = self.client.clone();
+        stream::try_unfold(Some(range_request), move |next| {
+            let client = client.clone();
+            async move {
+                let mut range_request = match next {
+                    Some(range_request) => range_request,
+                    None => return Ok::<_, Error>(None),
+                };
+                let range_response = client.range(range_request.clone()).await?;
+
+                let next = match range_response.kvs.last() {
+                    Some(last) if range_response.more => {
+                        range_request.key = [&last.key[..], &[0]].concat();
+                        // Later responses carry the current revision, not the pinned one.
+                        if range_request.revision == 0 {
+                            range_request.revision = range_response.get_header().revision;
+                        }
+                        Some(range_request)
+                    }
+                    _ => None,
+                };
+                let page = range_response.kvs.into_iter().map(Ok);
+                Ok(Some((stream::iter(page), next)))
+            }
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    pub fn put(&self, put_request: PutRequest) -> impl Future<Output = Result<PutResponse, Error>> {
+        self.client.put(put_request)
+    }
+
+    pub fn delete_range(
+        &self,
+        delete_range_request: DeleteRangeRequest,
+    ) -> impl Future<Output = Result<DeleteRangeResponse, Error>> {
+        self.client.delete_range(delete_range_request)
+    }
+
+    pub fn txn(&self, txn_request: TxnRequest) -> impl Future<Output = Result<TxnResponse, Error>> {
+        self.client.txn(txn_request)
+    }
+
+    pub fn compact(
+        &self,
+        compaction_request: CompactionRequest,
+    ) -> impl Future<Output = Result<CompactionResponse, Error>> {
+        self.client.compact(compaction_request)
+    }
+}
+
+/// Turns etcd's error for a read at a compacted revision into `Error::Compacted`, which needs a
+/// watch from the same revision to learn where the compaction happened.
+pub(crate) async fn compacted(client: &Client, error: Error, revision: i64) -> Error {
+    let is_compacted = match &error {
+        Error::Grpc(grpc::Error::GrpcMessage(message)) => message
+            .grpc_message
+            .contains("required revision has been compacted"),
+        _ => false,
+    };
+    if !is_compacted {
+        return error;
+    }
+
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = vec![0];
+    watch_create_request.start_revision = revision;
+    match Watch::new(watch_create_request, client.clone())
+        .try_next()
+        .await
+    {
+        Err(compacted @ Error::Compacted { .. }) => compacted,
+        _ => error,
+    }
+}
+
+/// Returns the range end that selects every key starting with `prefix`.
+pub fn prefix_range_end(prefix: &[u8]) -> Vec<u8> {
+    let mut range_end = prefix.to_vec();
+    while let Some(last) = range_end.pop() {
+        if last < 0xff {
+            range_end.push(last + 1);
+            return range_end;
+        }
+    }
+
+    // The prefix was empty or all 0xff, so select every key.
+    vec![0]
+}
+
+/// Returns the key that starts the range of every key under `prefix`. etcd rejects an empty key,
+/// so an empty prefix starts from the zero byte instead.
+pub(crate) fn prefix_key(prefix: &[u8]) -> Vec<u8> {
+    if prefix.is_empty() {
+        vec![0]
+    } else {
+        prefix.to_vec()
+    }
+}
+
+pub(crate) fn compare_create_revision(
+    key: Vec<u8>,
+    result: Compare_CompareResult,
+    revision: i64,
+) -> Compare {
+    let mut compare = Compare::new();
+    compare.key = key;
+    compare.result = result;
+    compare.target = Compare_CompareTarget::CREATE;
+    compare.target_union = Some(Compare_oneof_target_union::create_revision(revision));
+    compare
+}
+
+pub(crate) fn compare_mod_revision(
+    key: Vec<u8>,
+    result: Compare_CompareResult,
+    revision: i64,
+) -> Compare {
+    let mut compare = Compare::new();
+    compare.key = key;
+    compare.result = result;
+    compare.target = Compare_CompareTarget::MOD;
+    compare.target_union = Some(Compare_oneof_target_union::mod_revision(revision));
+    compare
+}
+
+pub(crate) fn put_op(key: Vec<u8>, value: Vec<u8>, lease: i64) -> RequestOp {
+    let mut put_request = PutRequest::new();
+    put_request.key = key;
+    put_request.value = value;
+    put_request.lease = lease;
+    let mut request_op = RequestOp::new();
+    request_op.request = Some(RequestOp_oneof_request::request_put(put_request));
+    request_op
+}
+
+pub(crate) fn range_op(key: Vec<u8>) -> RequestOp {
+    let mut range_request = RangeRequest::new();
+    range_request.key = key;
+    let mut request_op = RequestOp::new();
+    request_op.request = Some(RequestOp_oneof_request::request_range(range_request));
+    request_op
+}
+
+pub(crate) fn delete_op(key: Vec<u8>) -> RequestOp {
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = key;
+    let mut request_op = RequestOp::new();
+    request_op.request = Some(RequestOp_oneof_request::request_delete_range(
+        delete_range_request,
+    ));
+    request_op
+}
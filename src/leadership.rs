@@ -0,0 +1,105 @@
+use crate::errors::Error;
+use crate::kv::KeyValue;
+use crate::lease::Lease;
+use crate::transport::Client;
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderKey, LeaderRequest, ProclaimRequest, ResignRequest,
+    ResignResponse,
+};
+use futures::{Future, FutureExt, Stream, TryFutureExt, TryStreamExt};
+use std::rc::Rc;
+
+/// Leadership of an election held through etcd's server-side election service, as used by
+/// `etcdctl elect`.
+pub struct Leadership {
+    pub key: Vec<u8>,
+    leader: Option<LeaderKey>,
+    lease: Rc<Lease>,
+}
+
+impl Leadership {
+    pub async fn new(name: Vec<u8>, value: Vec<u8>, lease: Rc<Lease>) -> Result<Leadership, Error> {
+        let mut response = Leadership::campaign(name, value, lease.lease_id, &lease.client).await?;
+        let leader = response.take_leader();
+        Ok(Leadership {
+            key: leader.key.clone(),
+            leader: Some(leader),
+            lease,
+        })
+    }
+
+    pub fn proclaim(&self, value: Vec<u8>) -> impl Future<Output = Result<(), Error>> {
+        let mut proclaim_request = ProclaimRequest::new();
+        proclaim_request.set_leader(self.leader.clone().unwrap_or_default());
+        proclaim_request.value = value;
+        self.lease.client.proclaim(proclaim_request).map_ok(|_| ())
+    }
+
+    pub fn resign(mut self) -> impl Future<Output = Result<(), Error>> {
+        Leadership::resign_leader(self.leader.take().unwrap_or_default(), &self.lease.client)
+            .map_ok(|_| ())
+    }
+
+    /// The current leader's key and value for the named election.
+    pub fn leader<C: Into<Client>>(
+        name: Vec<u8>,
+        client: C,
+    ) -> impl Future<Output = Result<KeyValue, Error>> {
+        let mut leader_request = LeaderRequest::new();
+        leader_request.name = name;
+        client
+            .into()
+            .leader(leader_request)
+            .map_ok(|mut response| response.take_kv())
+    }
+
+    /// Streams every proclamation made by the named election's leaders.
+    pub fn observe<C: Into<Client>>(
+        name: Vec<u8>,
+        client: C,
+    ) -> impl Stream<Item = Result<KeyValue, Error>> {
+        let mut leader_request = LeaderRequest::new();
+        leader_request.name = name;
+        client
+            .into()
+            .observe(leader_request)
+            .map_ok(|mut response| response.take_kv())
+    }
+
+    fn campaign(
+        name: Vec<u8>,
+        value: Vec<u8>,
+        lease_id: i64,
+        client: &Client,
+    ) -> impl Future<Output = Result<CampaignResponse, Error>> {
+        let mut campaign_request = CampaignRequest::new();
+        campaign_request.name = name;
+        campaign_request.value = value;
+        campaign_request.lease = lease_id;
+        client.campaign(campaign_request)
+    }
+
+    fn resign_leader(
+        leader: LeaderKey,
+        client: &Client,
+    ) -> impl Future<Output = Result<ResignResponse, Error>> {
+        let mut resign_request = ResignRequest::new();
+        resign_request.set_leader(leader);
+        client.resign(resign_request)
+    }
+}
+
+impl Drop for Leadership {
+    fn drop(&mut self) {
+        if let Some(leader) = self.leader.take() {
+            trace!("Resigning leadership");
+
+            let resign = Leadership::resign_leader(leader, &self.lease.client).map(|result| {
+                if let Err(error) = result {
+                    error!("Could not resign leadership: {}", error);
+                }
+            });
+            self.lease.client.detach(resign.boxed());
+        }
+    }
+}
@@ -1,71 +1,110 @@
 use crate::errors::Error;
-use crate::rpc::{LeaseGrantRequest, LeaseGrantResponse, LeaseRevokeRequest, LeaseRevokeResponse};
-use crate::rpc_grpc::{Lease as LeaseTrait, LeaseClient};
-use futures::Future;
-use grpc::ClientStub;
-use grpc::RequestOptions;
-use std::sync::Arc;
+use crate::rpc::{
+    LeaseGrantRequest, LeaseGrantResponse, LeaseKeepAliveRequest, LeaseKeepAliveResponse,
+    LeaseRevokeRequest, LeaseRevokeResponse,
+};
+use crate::transport::Client;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use futures::{Future, FutureExt};
 
 pub struct Lease {
-    pub client: Arc<grpc::Client>,
+    pub client: Client,
     pub lease_id: i64,
 }
 
+/// A stream to refresh a lease over, one refresh per call to `refresh`.
+pub struct KeepAlive {
+    lease_id: i64,
+    requests: UnboundedSender<LeaseKeepAliveRequest>,
+    responses: BoxStream<'static, Result<LeaseKeepAliveResponse, Error>>,
+}
+
 impl Lease {
-    pub fn new(ttl: i64, client: Arc<grpc::Client>) -> impl Future<Item = Lease, Error = Error> {
+    pub async fn new<C: Into<Client>>(ttl: i64, client: C) -> Result<Lease, Error> {
         trace!("Initializing lease...");
 
-        let lease_client = LeaseClient::with_client(client.clone());
-        Self::get_lease(0, ttl, lease_client).and_then(move |response| {
-            if response.error.len() > 0 {
-                Err(Error::Unrecoverable(response.error.clone()))
-            } else {
-                Ok(Lease {
-                    client: client.clone(),
-                    lease_id: response.ID,
-                })
-            }
+        let client = client.into();
+        let response = Self::get_lease(0, ttl, &client).await?;
+        if !response.error.is_empty() {
+            return Err(Error::Unrecoverable(response.error));
+        }
+
+        Ok(Lease {
+            client,
+            lease_id: response.ID,
         })
     }
 
+    /// Revokes the lease now, deleting every key attached to it, rather than when it is dropped.
+    pub async fn revoke(mut self) -> Result<(), Error> {
+        let revoked = Self::revoke_lease(self.lease_id, &self.client).await;
+        // etcd never grants lease 0, so the drop has nothing left to revoke.
+        self.lease_id = 0;
+        revoked.map(|_| ())
+    }
+
+    pub fn keep_alive(&self) -> KeepAlive {
+        let (requests, receiver) = unbounded();
+        KeepAlive {
+            lease_id: self.lease_id,
+            requests,
+            responses: self.client.lease_keep_alive(receiver.boxed()),
+        }
+    }
+
     fn get_lease(
         lease_id: i64,
         ttl: i64,
-        lease_client: LeaseClient,
-    ) -> impl Future<Item = LeaseGrantResponse, Error = Error> {
-        let request_options = RequestOptions::new();
+        client: &Client,
+    ) -> impl Future<Output = Result<LeaseGrantResponse, Error>> {
         let mut lease_grant_request = LeaseGrantRequest::new();
         lease_grant_request.ID = lease_id;
         lease_grant_request.TTL = ttl;
-        lease_client
-            .lease_grant(request_options, lease_grant_request)
-            .drop_metadata()
-            .from_err()
+        client.lease_grant(lease_grant_request)
     }
 
     fn revoke_lease(
         lease_id: i64,
-        lease_client: LeaseClient,
-    ) -> impl Future<Item = LeaseRevokeResponse, Error = Error> {
-        let request_options = RequestOptions::new();
+        client: &Client,
+    ) -> impl Future<Output = Result<LeaseRevokeResponse, Error>> {
         let mut lease_revoke_request = LeaseRevokeRequest::new();
         lease_revoke_request.ID = lease_id;
-        lease_client
-            .lease_revoke(request_options, lease_revoke_request)
-            .drop_metadata()
-            .from_err()
+        client.lease_revoke(lease_revoke_request)
+    }
+}
+
+impl KeepAlive {
+    /// Refreshes the lease, returning its new TTL, or `Error::LeaseExpired` if it has already
+    /// expired.
+    pub async fn refresh(&mut self) -> Result<i64, Error> {
+        let mut lease_keep_alive_request = LeaseKeepAliveRequest::new();
+        lease_keep_alive_request.ID = self.lease_id;
+        let closed = || Error::Unrecoverable("Lease keep-alive stream closed".to_string());
+        self.requests
+            .unbounded_send(lease_keep_alive_request)
+            .map_err(|_| closed())?;
+
+        match self.responses.try_next().await? {
+            Some(response) if response.TTL > 0 => Ok(response.TTL),
+            Some(_) => Err(Error::LeaseExpired),
+            None => Err(closed()),
+        }
     }
 }
 
 impl Drop for Lease {
     fn drop(&mut self) {
         trace!("Dropping lease...");
+        if self.lease_id == 0 {
+            return;
+        }
 
-        let lease_client = LeaseClient::with_client(self.client.clone());
-        let _ = Self::revoke_lease(self.lease_id, lease_client).map_err(|error| {
-            error!("Could not revoke lease: {}", error);
-
-            error
+        let revoke = Self::revoke_lease(self.lease_id, &self.client).map(|result| {
+            if let Err(error) = result {
+                error!("Could not revoke lease: {}", error);
+            }
         });
+        self.client.detach(revoke.boxed());
     }
 }
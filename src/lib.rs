@@ -4,17 +4,63 @@ extern crate failure;
 extern crate log;
 extern crate grpc;
 
-mod auth;
+#[allow(warnings, clippy::all)]
+pub mod auth;
+mod barrier;
+pub mod blocking;
+mod cached_kv;
+pub mod codec;
+mod compactor;
+pub mod compat;
+mod config;
+pub mod dump;
+mod election;
 mod errors;
+#[allow(warnings, clippy::all)]
 mod etcdserver;
-mod kv;
+#[allow(warnings, clippy::all)]
+pub mod kv;
+mod kv_client;
+mod leadership;
 mod lease;
 mod lock;
-mod rpc;
+mod mirror;
+mod namespace;
+mod queue;
+mod registry;
+#[allow(warnings, clippy::all)]
+pub mod rpc;
+#[allow(warnings, clippy::all)]
 mod rpc_grpc;
+pub mod stm;
+#[cfg(feature = "test-server")]
+pub mod testing;
+pub mod transport;
+mod typed_kv;
+#[allow(warnings, clippy::all)]
+mod v3election;
+#[allow(warnings, clippy::all)]
+mod v3election_grpc;
+#[allow(warnings, clippy::all)]
 mod v3lock;
+#[allow(warnings, clippy::all)]
 mod v3lock_grpc;
+mod watch;
 
+pub use self::barrier::{Barrier, DoubleBarrier};
+pub use self::cached_kv::{CacheMetrics, CachedKv};
+pub use self::compactor::{Compactor, PeriodicCompaction, Retention};
+pub use self::config::ConfigWatcher;
+pub use self::election::Election;
 pub use self::errors::Error;
-pub use self::lease::Lease;
+pub use self::kv_client::{prefix_range_end, Kv};
+pub use self::leadership::Leadership;
+pub use self::lease::{KeepAlive, Lease};
 pub use self::lock::Lock;
+pub use self::mirror::{Mirror, MirrorProgress, MirrorTask};
+pub use self::namespace::Namespace;
+pub use self::queue::{PriorityQueue, Queue};
+pub use self::registry::{Registration, ServiceInstance, ServiceRegistry};
+pub use self::transport::{Client, ClientBuilder, Transport};
+pub use self::typed_kv::{TypedEvent, TypedKv};
+pub use self::watch::Watch;
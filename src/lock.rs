@@ -1,10 +1,8 @@
 use crate::errors::Error;
 use crate::lease::Lease;
+use crate::transport::Client;
 use crate::v3lock::{LockRequest as GrpcLockRequest, LockResponse, UnlockRequest, UnlockResponse};
-use crate::v3lock_grpc::{Lock as LockTrait, LockClient};
-use futures::Future;
-use grpc::ClientStub;
-use grpc::RequestOptions;
+use futures::{Future, FutureExt};
 use std::rc::Rc;
 
 pub struct Lock {
@@ -13,40 +11,32 @@ pub struct Lock {
 }
 
 impl Lock {
-    pub fn new(name: Vec<u8>, lease: Rc<Lease>) -> impl Future<Item = Lock, Error = Error> {
-        let lock_client = LockClient::with_client(lease.client.clone());
-        Lock::get_lock(name, lock_client).and_then(move |response| {
-            Ok(Lock {
-                key: response.key,
-                lease: lease.clone(),
-            })
+    pub async fn new(name: Vec<u8>, lease: Rc<Lease>) -> Result<Lock, Error> {
+        let response = Lock::get_lock(name, lease.lease_id, &lease.client).await?;
+        Ok(Lock {
+            key: response.key,
+            lease,
         })
     }
 
     fn unlock(
         key: Vec<u8>,
-        lock_client: LockClient,
-    ) -> impl Future<Item = UnlockResponse, Error = Error> {
-        let request_options = RequestOptions::new();
+        client: &Client,
+    ) -> impl Future<Output = Result<UnlockResponse, Error>> {
         let mut unlock_request = UnlockRequest::new();
         unlock_request.key = key;
-        lock_client
-            .unlock(request_options, unlock_request)
-            .drop_metadata()
-            .from_err()
+        client.unlock(unlock_request)
     }
 
     fn get_lock(
         name: Vec<u8>,
-        lock_client: LockClient,
-    ) -> impl Future<Item = LockResponse, Error = Error> {
-        let request_options = RequestOptions::new();
+        lease_id: i64,
+        client: &Client,
+    ) -> impl Future<Output = Result<LockResponse, Error>> {
         let mut lock_request = GrpcLockRequest::new();
         lock_request.name = name;
-        lock_client
-            .lock(request_options, lock_request)
-            .drop_metadata()
-            .from_err()
+        lock_request.lease = lease_id;
+        client.lock(lock_request)
     }
 }
 
@@ -54,11 +44,11 @@ impl Drop for Lock {
     fn drop(&mut self) {
         trace!("Revoking lock");
 
-        let lock_client = LockClient::with_client(self.lease.client.clone());
-        let _ = Lock::unlock(self.key.clone(), lock_client).map_err(|error| {
-            error!("Could not revoke lock: {}", error);
-
-            error
+        let unlock = Lock::unlock(self.key.clone(), &self.lease.client).map(|result| {
+            if let Err(error) = result {
+                error!("Could not revoke lock: {}", error);
+            }
         });
+        self.lease.client.detach(unlock.boxed());
     }
 }
@@ -1,3 +1,20 @@
+mod cli;
+
+use cli::CliError;
+use std::env;
+use std::process;
+
 fn main() {
-    println!("Hello, world!");
+    match cli::run(env::args().skip(1)) {
+        Ok(()) => {}
+        Err(CliError::Usage(message)) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            process::exit(2);
+        }
+        Err(CliError::Exit(code)) => process::exit(code),
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            process::exit(1);
+        }
+    }
 }
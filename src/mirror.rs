@@ -0,0 +1,239 @@
+use crate::errors::Error;
+use crate::kv::{Event, Event_EventType};
+use crate::kv_client::{delete_op, prefix_key, prefix_range_end, put_op, Kv};
+use crate::rpc::{RangeRequest, TxnRequest, WatchCreateRequest};
+use crate::transport::Client;
+use crate::watch::Watch;
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::future::{select, Either};
+use futures::{FutureExt, TryStreamExt};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Keys ranged per request during the initial sync.
+const SYNC_PAGE_SIZE: i64 = 1000;
+
+/// Ops written per destination transaction, within etcd's default `--max-txn-ops` of 128.
+const SYNC_BATCH_SIZE: usize = 100;
+
+/// Copies every key under a prefix from one cluster to another, then keeps copying changes to
+/// them, like `etcdctl make-mirror`.
+///
+/// The initial sync ranges the prefix at a single revision, a page at a time. A watch from the
+/// next revision then replicates each later revision in a transaction of its own. Leases are not
+/// mirrored, and keys that only exist in the destination are left alone.
+///
+/// ```no_run
+/// # fn example(source: etcd::Client, destination: etcd::Client) -> Result<(), etcd::Error> {
+/// let mirror = etcd::Mirror::new(source, destination, b"app/".to_vec())
+///     .with_destination_prefix(b"mirrored/app/".to_vec())
+///     .spawn()?;
+/// // ...
+/// println!("{} revisions behind", mirror.progress().lag());
+/// mirror.stop()
+/// # }
+/// ```
+pub struct Mirror {
+    source: Client,
+    destination: Client,
+    prefix: Vec<u8>,
+    destination_prefix: Vec<u8>,
+    progress: Arc<Mutex<MirrorProgress>>,
+}
+
+/// How far a mirror has got, in source revisions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MirrorProgress {
+    /// The latest source revision the mirror has heard of.
+    pub source_revision: i64,
+    /// The source revision the destination reflects the prefix at.
+    pub mirrored_revision: i64,
+}
+
+/// A mirror running on a background thread, until this is dropped or stopped.
+pub struct MirrorTask {
+    progress: Arc<Mutex<MirrorProgress>>,
+    // Dropping the sender stops the thread.
+    stop: oneshot::Sender<()>,
+    thread: JoinHandle<Result<(), Error>>,
+}
+
+impl Mirror {
+    pub fn new<S: Into<Client>, D: Into<Client>>(
+        source: S,
+        destination: D,
+        prefix: Vec<u8>,
+    ) -> Mirror {
+        Mirror {
+            source: source.into(),
+            destination: destination.into(),
+            destination_prefix: prefix.clone(),
+            prefix,
+            progress: Arc::new(Mutex::new(MirrorProgress::default())),
+        }
+    }
+
+    /// Writes keys under `destination_prefix` in place of the source prefix.
+    pub fn with_destination_prefix(mut self, destination_prefix: Vec<u8>) -> Mirror {
+        self.destination_prefix = destination_prefix;
+        self
+    }
+
+    pub fn progress(&self) -> MirrorProgress {
+        *lock(&self.progress)
+    }
+
+    /// Copies every key under the prefix once, returning the source revision it was read at.
+    pub async fn sync(&self) -> Result<i64, Error> {
+        let source = Kv::new(self.source.clone());
+        let mut range_request = RangeRequest::new();
+        range_request.key = prefix_key(&self.prefix);
+        range_request.range_end = prefix_range_end(&self.prefix);
+        range_request.count_only = true;
+        let revision = source.range(range_request).await?.get_header().revision;
+        lock(&self.progress).source_revision = revision;
+
+        let mut kvs = source.scan_at(self.prefix.clone(), SYNC_PAGE_SIZE, revision);
+        let mut txn_request = TxnRequest::new();
+        while let Some(kv) = kvs.try_next().await? {
+            let key = self.rewrite(&kv.key);
+            txn_request.success.push(put_op(key, kv.value, 0));
+            if txn_request.success.len() == SYNC_BATCH_SIZE {
+                self.destination.txn(txn_request).await?;
+                txn_request = TxnRequest::new();
+            }
+        }
+        if !txn_request.success.is_empty() {
+            self.destination.txn(txn_request).await?;
+        }
+
+        lock(&self.progress).mirrored_revision = revision;
+        Ok(revision)
+    }
+
+    /// Syncs, then replicates changes on a background thread.
+    ///
+    /// A failed watch or write is retried from the last mirrored revision. Mirroring stops if
+    /// that revision has been compacted, or if the initial sync fails.
+    pub fn spawn(self) -> Result<MirrorTask, Error> {
+        let progress = self.progress.clone();
+        let (stop, stopped) = oneshot::channel();
+        let thread = thread::Builder::new()
+            .name("etcd-mirror".to_string())
+            .spawn(move || self.run(stopped))?;
+
+        Ok(MirrorTask {
+            progress,
+            stop,
+            thread,
+        })
+    }
+
+    fn run(self, mut stopped: oneshot::Receiver<()>) -> Result<(), Error> {
+        let mut revision = match block_on(select(&mut stopped, self.sync().boxed())) {
+            Either::Left(_) => return Ok(()),
+            Either::Right((synced, _)) => synced?,
+        };
+
+        loop {
+            let error = match block_on(select(&mut stopped, self.replicate(revision).boxed())) {
+                Either::Left(_) => return Ok(()),
+                Either::Right((error, _)) => error,
+            };
+            if let Error::Compacted { .. } = error {
+                // The changes after `revision` are gone, so the destination cannot catch up.
+                return Err(error);
+            }
+            warn!("Mirror failed, resuming: {}", error);
+
+            thread::sleep(Duration::from_secs(1));
+            if stopped.try_recv() != Ok(None) {
+                return Ok(());
+            }
+            revision = self.progress().mirrored_revision;
+        }
+    }
+
+    /// Applies every change after `revision`, returning why it stopped.
+    async fn replicate(&self, revision: i64) -> Error {
+        let mut watch_create_request = WatchCreateRequest::new();
+        watch_create_request.key = prefix_key(&self.prefix);
+        watch_create_request.range_end = prefix_range_end(&self.prefix);
+        watch_create_request.start_revision = revision + 1;
+
+        let mut watch = Watch::new(watch_create_request, self.source.clone());
+        loop {
+            let response = match watch.try_next().await {
+                Ok(Some(response)) => response,
+                Ok(None) => return Error::Unrecoverable("Mirror watch ended".to_string()),
+                Err(error) => return error,
+            };
+            {
+                let mut progress = lock(&self.progress);
+                progress.source_revision =
+                    progress.source_revision.max(response.get_header().revision);
+            }
+
+            let revisions = response
+                .events
+                .chunk_by(|a, b| a.get_kv().mod_revision == b.get_kv().mod_revision);
+            for events in revisions {
+                if let Err(error) = self.apply(events).await {
+                    return error;
+                }
+                lock(&self.progress).mirrored_revision = events[0].get_kv().mod_revision;
+            }
+        }
+    }
+
+    /// Writes one source revision's events to the destination, in transactions small enough for
+    /// etcd to accept. A revision that fails part way is rewritten in full on the next attempt.
+    async fn apply(&self, events: &[Event]) -> Result<(), Error> {
+        for batch in events.chunks(SYNC_BATCH_SIZE) {
+            let mut txn_request = TxnRequest::new();
+            for event in batch {
+                let kv = event.get_kv();
+                let key = self.rewrite(&kv.key);
+                txn_request.success.push(match event.field_type {
+                    Event_EventType::PUT => put_op(key, kv.value.clone(), 0),
+                    Event_EventType::DELETE => delete_op(key),
+                });
+            }
+            self.destination.txn(txn_request).await?;
+        }
+        Ok(())
+    }
+
+    fn rewrite(&self, key: &[u8]) -> Vec<u8> {
+        [&self.destination_prefix[..], &key[self.prefix.len()..]].concat()
+    }
+}
+
+impl MirrorProgress {
+    /// How many source revisions the destination is behind by.
+    pub fn lag(&self) -> i64 {
+        (self.source_revision - self.mirrored_revision).max(0)
+    }
+}
+
+impl MirrorTask {
+    pub fn progress(&self) -> MirrorProgress {
+        *lock(&self.progress)
+    }
+
+    /// Stops mirroring, returning the error that stopped it already, if any.
+    pub fn stop(self) -> Result<(), Error> {
+        drop(self.stop);
+        match self.thread.join() {
+            Ok(result) => result,
+            Err(_) => Err(Error::Unrecoverable("Mirror thread panicked".to_string())),
+        }
+    }
+}
+
+/// Locks the shared progress, which is plain data that a panic cannot leave half written.
+fn lock(progress: &Mutex<MirrorProgress>) -> MutexGuard<'_, MirrorProgress> {
+    progress.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,527 @@
+use crate::errors::Error;
+use crate::kv::KeyValue;
+use crate::kv_client::prefix_range_end;
+use crate::rpc::{
+    AuthRoleAddRequest, AuthRoleAddResponse, AuthRoleDeleteRequest, AuthRoleDeleteResponse,
+    AuthRoleGetRequest, AuthRoleGetResponse, AuthRoleGrantPermissionRequest,
+    AuthRoleGrantPermissionResponse, AuthRoleListRequest, AuthRoleListResponse,
+    AuthRoleRevokePermissionRequest, AuthRoleRevokePermissionResponse, AuthUserAddRequest,
+    AuthUserAddResponse, AuthUserChangePasswordRequest, AuthUserChangePasswordResponse,
+    AuthUserDeleteRequest, AuthUserDeleteResponse, AuthUserGetRequest, AuthUserGetResponse,
+    AuthUserGrantRoleRequest, AuthUserGrantRoleResponse, AuthUserListRequest, AuthUserListResponse,
+    AuthUserRevokeRoleRequest, AuthUserRevokeRoleResponse, CompactionRequest, CompactionResponse,
+    DeleteRangeRequest, DeleteRangeResponse, LeaseGrantRequest, LeaseGrantResponse,
+    LeaseKeepAliveRequest, LeaseKeepAliveResponse, LeaseLeasesRequest, LeaseLeasesResponse,
+    LeaseRevokeRequest, LeaseRevokeResponse, LeaseTimeToLiveRequest, LeaseTimeToLiveResponse,
+    MemberAddRequest, MemberAddResponse, MemberListRequest, MemberListResponse,
+    MemberRemoveRequest, MemberRemoveResponse, PutRequest, PutResponse, RangeRequest,
+    RangeResponse, RequestOp_oneof_request, ResponseOp_oneof_response, SnapshotRequest,
+    SnapshotResponse, StatusRequest, StatusResponse, TxnRequest, TxnResponse, WatchRequest,
+    WatchResponse,
+};
+use crate::transport::{Client, Transport};
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderKey, LeaderRequest, LeaderResponse, ProclaimRequest,
+    ProclaimResponse, ResignRequest, ResignResponse,
+};
+use crate::v3lock::{LockRequest, LockResponse, UnlockRequest, UnlockResponse};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, TryFutureExt, TryStreamExt};
+use std::sync::Arc;
+
+/// A view of etcd with every key under `prefix`, like the `namespace` package of etcd's Go
+/// client.
+///
+/// Keys and range ends in requests, including those in txns, watches, locks and elections, have
+/// the prefix prepended, and keys in results have it stripped. A range end of `[0]`, meaning every
+/// key from the start of the range, stops at the end of the namespace.
+///
+/// ```no_run
+/// # async fn example(client: etcd::Client) -> Result<(), etcd::Error> {
+/// let kv = etcd::Kv::new(etcd::Namespace::new(client, "/team-x/"));
+/// // Reads `/team-x/config`, returning it with the key `config`.
+/// let config = kv.get(b"config".to_vec()).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Namespace {
+    client: Client,
+    prefix: Arc<[u8]>,
+}
+
+impl Namespace {
+    pub fn new<C: Into<Client>, P: Into<Vec<u8>>>(client: C, prefix: P) -> Namespace {
+        Namespace {
+            client: client.into(),
+            prefix: prefix.into().into(),
+        }
+    }
+
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+}
+
+impl From<Namespace> for Client {
+    fn from(namespace: Namespace) -> Client {
+        Client::new(namespace)
+    }
+}
+
+impl Transport for Namespace {
+    fn range(&self, mut request: RangeRequest) -> BoxFuture<'static, Result<RangeResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_interval(&prefix, &mut request.key, &mut request.range_end);
+        self.client
+            .range(request)
+            .map_ok(move |mut response| {
+                strip_range(&prefix, &mut response);
+                response
+            })
+            .boxed()
+    }
+
+    fn put(&self, mut request: PutRequest) -> BoxFuture<'static, Result<PutResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_key(&prefix, request.mut_key());
+        self.client
+            .put(request)
+            .map_ok(move |mut response| {
+                strip_put(&prefix, &mut response);
+                response
+            })
+            .boxed()
+    }
+
+    fn delete_range(
+        &self,
+        mut request: DeleteRangeRequest,
+    ) -> BoxFuture<'static, Result<DeleteRangeResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_interval(&prefix, &mut request.key, &mut request.range_end);
+        self.client
+            .delete_range(request)
+            .map_ok(move |mut response| {
+                strip_delete_range(&prefix, &mut response);
+                response
+            })
+            .boxed()
+    }
+
+    fn txn(&self, mut request: TxnRequest) -> BoxFuture<'static, Result<TxnResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_txn(&prefix, &mut request);
+        self.client
+            .txn(request)
+            .map_ok(move |mut response| {
+                strip_txn(&prefix, &mut response);
+                response
+            })
+            .boxed()
+    }
+
+    fn compact(
+        &self,
+        request: CompactionRequest,
+    ) -> BoxFuture<'static, Result<CompactionResponse, Error>> {
+        self.client.compact(request)
+    }
+
+    fn watch(
+        &self,
+        requests: BoxStream<'static, WatchRequest>,
+    ) -> BoxStream<'static, Result<WatchResponse, Error>> {
+        let prefix = self.prefix.clone();
+        let requests = requests
+            .map(move |mut request| {
+                if request.has_create_request() {
+                    let create = request.mut_create_request();
+                    prefix_interval(&prefix, &mut create.key, &mut create.range_end);
+                }
+                request
+            })
+            .boxed();
+
+        let prefix = self.prefix.clone();
+        self.client
+            .watch(requests)
+            .map_ok(move |mut response| {
+                for event in response.mut_events().iter_mut() {
+                    if event.has_kv() {
+                        strip_key_value(&prefix, event.mut_kv());
+                    }
+                    if event.has_prev_kv() {
+                        strip_key_value(&prefix, event.mut_prev_kv());
+                    }
+                }
+                response
+            })
+            .boxed()
+    }
+
+    fn lease_grant(
+        &self,
+        request: LeaseGrantRequest,
+    ) -> BoxFuture<'static, Result<LeaseGrantResponse, Error>> {
+        self.client.lease_grant(request)
+    }
+
+    fn lease_revoke(
+        &self,
+        request: LeaseRevokeRequest,
+    ) -> BoxFuture<'static, Result<LeaseRevokeResponse, Error>> {
+        self.client.lease_revoke(request)
+    }
+
+    fn lease_keep_alive(
+        &self,
+        requests: BoxStream<'static, LeaseKeepAliveRequest>,
+    ) -> BoxStream<'static, Result<LeaseKeepAliveResponse, Error>> {
+        self.client.lease_keep_alive(requests)
+    }
+
+    /// Leases are shared across namespaces, so only the attached keys inside this one are listed.
+    fn lease_time_to_live(
+        &self,
+        request: LeaseTimeToLiveRequest,
+    ) -> BoxFuture<'static, Result<LeaseTimeToLiveResponse, Error>> {
+        let prefix = self.prefix.clone();
+        self.client
+            .lease_time_to_live(request)
+            .map_ok(move |mut response| {
+                let keys = response
+                    .take_keys()
+                    .into_iter()
+                    .filter(|key| key.starts_with(&prefix))
+                    .map(|key| key[prefix.len()..].to_vec())
+                    .collect();
+                response.set_keys(keys);
+                response
+            })
+            .boxed()
+    }
+
+    fn lease_leases(
+        &self,
+        request: LeaseLeasesRequest,
+    ) -> BoxFuture<'static, Result<LeaseLeasesResponse, Error>> {
+        self.client.lease_leases(request)
+    }
+
+    // Cluster, maintenance and auth calls have no keys to scope, except for role permissions,
+    // which are cluster-wide like roles themselves.
+    fn member_add(
+        &self,
+        request: MemberAddRequest,
+    ) -> BoxFuture<'static, Result<MemberAddResponse, Error>> {
+        self.client.member_add(request)
+    }
+
+    fn member_remove(
+        &self,
+        request: MemberRemoveRequest,
+    ) -> BoxFuture<'static, Result<MemberRemoveResponse, Error>> {
+        self.client.member_remove(request)
+    }
+
+    fn member_list(
+        &self,
+        request: MemberListRequest,
+    ) -> BoxFuture<'static, Result<MemberListResponse, Error>> {
+        self.client.member_list(request)
+    }
+
+    fn status(&self, request: StatusRequest) -> BoxFuture<'static, Result<StatusResponse, Error>> {
+        self.client.status(request)
+    }
+
+    fn snapshot(
+        &self,
+        request: SnapshotRequest,
+    ) -> BoxStream<'static, Result<SnapshotResponse, Error>> {
+        self.client.snapshot(request)
+    }
+
+    fn user_add(
+        &self,
+        request: AuthUserAddRequest,
+    ) -> BoxFuture<'static, Result<AuthUserAddResponse, Error>> {
+        self.client.user_add(request)
+    }
+
+    fn user_get(
+        &self,
+        request: AuthUserGetRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGetResponse, Error>> {
+        self.client.user_get(request)
+    }
+
+    fn user_list(
+        &self,
+        request: AuthUserListRequest,
+    ) -> BoxFuture<'static, Result<AuthUserListResponse, Error>> {
+        self.client.user_list(request)
+    }
+
+    fn user_delete(
+        &self,
+        request: AuthUserDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthUserDeleteResponse, Error>> {
+        self.client.user_delete(request)
+    }
+
+    fn user_change_password(
+        &self,
+        request: AuthUserChangePasswordRequest,
+    ) -> BoxFuture<'static, Result<AuthUserChangePasswordResponse, Error>> {
+        self.client.user_change_password(request)
+    }
+
+    fn user_grant_role(
+        &self,
+        request: AuthUserGrantRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGrantRoleResponse, Error>> {
+        self.client.user_grant_role(request)
+    }
+
+    fn user_revoke_role(
+        &self,
+        request: AuthUserRevokeRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserRevokeRoleResponse, Error>> {
+        self.client.user_revoke_role(request)
+    }
+
+    fn role_add(
+        &self,
+        request: AuthRoleAddRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleAddResponse, Error>> {
+        self.client.role_add(request)
+    }
+
+    fn role_get(
+        &self,
+        request: AuthRoleGetRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGetResponse, Error>> {
+        self.client.role_get(request)
+    }
+
+    fn role_list(
+        &self,
+        request: AuthRoleListRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleListResponse, Error>> {
+        self.client.role_list(request)
+    }
+
+    fn role_delete(
+        &self,
+        request: AuthRoleDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleDeleteResponse, Error>> {
+        self.client.role_delete(request)
+    }
+
+    fn role_grant_permission(
+        &self,
+        request: AuthRoleGrantPermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGrantPermissionResponse, Error>> {
+        self.client.role_grant_permission(request)
+    }
+
+    fn role_revoke_permission(
+        &self,
+        request: AuthRoleRevokePermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleRevokePermissionResponse, Error>> {
+        self.client.role_revoke_permission(request)
+    }
+
+    fn lock(&self, mut request: LockRequest) -> BoxFuture<'static, Result<LockResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_key(&prefix, request.mut_name());
+        self.client
+            .lock(request)
+            .map_ok(move |mut response| {
+                strip_key(&prefix, response.mut_key());
+                response
+            })
+            .boxed()
+    }
+
+    fn unlock(
+        &self,
+        mut request: UnlockRequest,
+    ) -> BoxFuture<'static, Result<UnlockResponse, Error>> {
+        prefix_key(&self.prefix, request.mut_key());
+        self.client.unlock(request)
+    }
+
+    fn campaign(
+        &self,
+        mut request: CampaignRequest,
+    ) -> BoxFuture<'static, Result<CampaignResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_key(&prefix, request.mut_name());
+        self.client
+            .campaign(request)
+            .map_ok(move |mut response| {
+                if response.has_leader() {
+                    strip_leader_key(&prefix, response.mut_leader());
+                }
+                response
+            })
+            .boxed()
+    }
+
+    fn proclaim(
+        &self,
+        mut request: ProclaimRequest,
+    ) -> BoxFuture<'static, Result<ProclaimResponse, Error>> {
+        if request.has_leader() {
+            prefix_leader_key(&self.prefix, request.mut_leader());
+        }
+        self.client.proclaim(request)
+    }
+
+    fn leader(
+        &self,
+        mut request: LeaderRequest,
+    ) -> BoxFuture<'static, Result<LeaderResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_key(&prefix, request.mut_name());
+        self.client
+            .leader(request)
+            .map_ok(move |mut response| {
+                strip_leader(&prefix, &mut response);
+                response
+            })
+            .boxed()
+    }
+
+    fn observe(
+        &self,
+        mut request: LeaderRequest,
+    ) -> BoxStream<'static, Result<LeaderResponse, Error>> {
+        let prefix = self.prefix.clone();
+        prefix_key(&prefix, request.mut_name());
+        self.client
+            .observe(request)
+            .map_ok(move |mut response| {
+                strip_leader(&prefix, &mut response);
+                response
+            })
+            .boxed()
+    }
+
+    fn resign(
+        &self,
+        mut request: ResignRequest,
+    ) -> BoxFuture<'static, Result<ResignResponse, Error>> {
+        if request.has_leader() {
+            prefix_leader_key(&self.prefix, request.mut_leader());
+        }
+        self.client.resign(request)
+    }
+
+    fn detach(&self, task: BoxFuture<'static, ()>) {
+        self.client.detach(task)
+    }
+}
+
+fn prefix_key(prefix: &[u8], key: &mut Vec<u8>) {
+    key.splice(0..0, prefix.iter().cloned());
+}
+
+/// Prefixes a key and range end, keeping an empty range end for a single key.
+fn prefix_interval(prefix: &[u8], key: &mut Vec<u8>, range_end: &mut Vec<u8>) {
+    // The zero key from an empty prefix starts at the namespace itself.
+    if key.as_slice() == [0] && range_end.as_slice() == [0] {
+        key.clear();
+    }
+    prefix_key(prefix, key);
+    if range_end.as_slice() == [0] {
+        *range_end = prefix_range_end(prefix);
+    } else if !range_end.is_empty() {
+        prefix_key(prefix, range_end);
+    }
+}
+
+fn prefix_txn(prefix: &[u8], request: &mut TxnRequest) {
+    for compare in request.mut_compare().iter_mut() {
+        prefix_interval(prefix, &mut compare.key, &mut compare.range_end);
+    }
+
+    let ops = request.success.iter_mut().chain(request.failure.iter_mut());
+    for op in ops {
+        match &mut op.request {
+            Some(RequestOp_oneof_request::request_range(range)) => {
+                prefix_interval(prefix, &mut range.key, &mut range.range_end);
+            }
+            Some(RequestOp_oneof_request::request_put(put)) => {
+                prefix_key(prefix, put.mut_key());
+            }
+            Some(RequestOp_oneof_request::request_delete_range(delete)) => {
+                prefix_interval(prefix, &mut delete.key, &mut delete.range_end);
+            }
+            Some(RequestOp_oneof_request::request_txn(txn)) => prefix_txn(prefix, txn),
+            None => {}
+        }
+    }
+}
+
+fn prefix_leader_key(prefix: &[u8], leader: &mut LeaderKey) {
+    prefix_key(prefix, leader.mut_name());
+    prefix_key(prefix, leader.mut_key());
+}
+
+fn strip_key(prefix: &[u8], key: &mut Vec<u8>) {
+    if key.starts_with(prefix) {
+        key.drain(..prefix.len());
+    }
+}
+
+fn strip_key_value(prefix: &[u8], key_value: &mut KeyValue) {
+    strip_key(prefix, key_value.mut_key());
+}
+
+fn strip_range(prefix: &[u8], response: &mut RangeResponse) {
+    for key_value in response.mut_kvs().iter_mut() {
+        strip_key_value(prefix, key_value);
+    }
+}
+
+fn strip_put(prefix: &[u8], response: &mut PutResponse) {
+    if response.has_prev_kv() {
+        strip_key_value(prefix, response.mut_prev_kv());
+    }
+}
+
+fn strip_delete_range(prefix: &[u8], response: &mut DeleteRangeResponse) {
+    for key_value in response.mut_prev_kvs().iter_mut() {
+        strip_key_value(prefix, key_value);
+    }
+}
+
+fn strip_txn(prefix: &[u8], response: &mut TxnResponse) {
+    for op in response.mut_responses().iter_mut() {
+        match &mut op.response {
+            Some(ResponseOp_oneof_response::response_range(range)) => strip_range(prefix, range),
+            Some(ResponseOp_oneof_response::response_put(put)) => strip_put(prefix, put),
+            Some(ResponseOp_oneof_response::response_delete_range(delete)) => {
+                strip_delete_range(prefix, delete)
+            }
+            Some(ResponseOp_oneof_response::response_txn(txn)) => strip_txn(prefix, txn),
+            None => {}
+        }
+    }
+}
+
+fn strip_leader_key(prefix: &[u8], leader: &mut LeaderKey) {
+    strip_key(prefix, leader.mut_name());
+    strip_key(prefix, leader.mut_key());
+}
+
+fn strip_leader(prefix: &[u8], response: &mut LeaderResponse) {
+    if response.has_kv() {
+        strip_key_value(prefix, response.mut_kv());
+    }
+}
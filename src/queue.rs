@@ -0,0 +1,185 @@
+use crate::errors::Error;
+use crate::kv::KeyValue;
+use crate::kv_client::{compare_mod_revision, delete_op, prefix_range_end, put_op, Kv};
+use crate::rpc::{
+    Compare_CompareResult, RangeRequest, RangeRequest_SortOrder, RangeRequest_SortTarget,
+    TxnRequest,
+};
+use crate::transport::Client;
+use crate::watch::wait_prefix_put;
+
+/// A distributed FIFO queue, compatible with etcd's `recipes.Queue` key layout.
+pub struct Queue {
+    name: Vec<u8>,
+    kv: Kv,
+}
+
+/// A distributed queue that dequeues lower priorities first and is FIFO within a priority.
+pub struct PriorityQueue {
+    name: Vec<u8>,
+    kv: Kv,
+}
+
+impl Queue {
+    pub fn new<C: Into<Client>>(name: Vec<u8>, client: C) -> Queue {
+        Queue {
+            name,
+            kv: Kv::new(client),
+        }
+    }
+
+    pub async fn enqueue(&self, value: Vec<u8>) -> Result<(), Error> {
+        put_sequential(self.kv.clone(), self.name.clone(), value).await?;
+        Ok(())
+    }
+
+    /// Removes and returns the oldest value, waiting for one to be enqueued if the queue is empty.
+    pub async fn dequeue(&self) -> Result<Vec<u8>, Error> {
+        dequeue(
+            self.kv.clone(),
+            [self.name.clone(), b"/".to_vec()].concat(),
+            RangeRequest_SortTarget::MOD,
+        )
+        .await
+    }
+}
+
+impl PriorityQueue {
+    pub fn new<C: Into<Client>>(name: Vec<u8>, client: C) -> PriorityQueue {
+        PriorityQueue {
+            name,
+            kv: Kv::new(client),
+        }
+    }
+
+    pub async fn enqueue(&self, value: Vec<u8>, priority: u16) -> Result<(), Error> {
+        let prefix = [self.name.clone(), format!("/{:05}", priority).into_bytes()].concat();
+        put_sequential(self.kv.clone(), prefix, value).await?;
+        Ok(())
+    }
+
+    /// Removes and returns the value with the lowest priority, waiting for one to be enqueued if
+    /// the queue is empty.
+    pub async fn dequeue(&self) -> Result<Vec<u8>, Error> {
+        dequeue(
+            self.kv.clone(),
+            [self.name.clone(), b"/".to_vec()].concat(),
+            RangeRequest_SortTarget::KEY,
+        )
+        .await
+    }
+}
+
+/// Puts `value` at `prefix/<sequence>`, with a sequence number after every one already there.
+///
+/// Returns the new key and the revision it was created at.
+pub(crate) async fn put_sequential(
+    kv: Kv,
+    prefix: Vec<u8>,
+    value: Vec<u8>,
+) -> Result<(Vec<u8>, i64), Error> {
+    loop {
+        let mut range_request = RangeRequest::new();
+        range_request.key = [prefix.clone(), b"/".to_vec()].concat();
+        range_request.range_end = prefix_range_end(&range_request.key);
+        range_request.sort_target = RangeRequest_SortTarget::KEY;
+        range_request.sort_order = RangeRequest_SortOrder::DESCEND;
+        range_request.limit = 1;
+
+        let response = kv.range(range_request).await?;
+        let sequence = match response.kvs.first() {
+            Some(last) => {
+                parse_sequence(&last.key).ok_or_else(|| {
+                    Error::Unrecoverable(format!(
+                        "Could not parse sequence from key {:?}",
+                        String::from_utf8_lossy(&last.key)
+                    ))
+                })? + 1
+            }
+            None => 0,
+        };
+        let key = [prefix.clone(), format!("/{:016}", sequence).into_bytes()].concat();
+
+        // Bumping the base key guards against another client allocating, and then
+        // deleting, a later sequence number between our range and our txn.
+        let base_key = [b"__".to_vec(), prefix.clone()].concat();
+        let mut txn_request = TxnRequest::new();
+        txn_request.compare.push(compare_mod_revision(
+            base_key.clone(),
+            Compare_CompareResult::LESS,
+            response.get_header().revision + 1,
+        ));
+        txn_request.success.push(put_op(base_key, Vec::new(), 0));
+        txn_request
+            .success
+            .push(put_op(key.clone(), value.clone(), 0));
+
+        let response = kv.txn(txn_request).await?;
+        if response.succeeded {
+            return Ok((key, response.get_header().revision));
+        }
+    }
+}
+
+fn parse_sequence(key: &[u8]) -> Option<u64> {
+    let suffix = key.rsplit(|byte| *byte == b'/').next()?;
+    std::str::from_utf8(suffix).ok()?.parse().ok()
+}
+
+async fn dequeue(
+    kv: Kv,
+    prefix: Vec<u8>,
+    sort_target: RangeRequest_SortTarget,
+) -> Result<Vec<u8>, Error> {
+    loop {
+        let mut range_request = RangeRequest::new();
+        range_request.key = prefix.clone();
+        range_request.range_end = prefix_range_end(&prefix);
+        range_request.sort_target = sort_target;
+        range_request.sort_order = RangeRequest_SortOrder::ASCEND;
+        range_request.limit = 1;
+
+        let response = kv.range(range_request).await?;
+        let revision = response.get_header().revision;
+        let more = response.more;
+        if let Some(claimed) = claim_first_key(kv.clone(), response.kvs.into_vec()).await? {
+            return Ok(claimed.value);
+        }
+        // Someone else claimed the first key but there are more to try.
+        if more {
+            continue;
+        }
+
+        let put = wait_prefix_put(kv.client.clone(), prefix.clone(), revision).await?;
+        if delete_revision_key(kv.clone(), put.key.clone(), put.mod_revision).await? {
+            return Ok(put.value);
+        }
+    }
+}
+
+/// Deletes the first key that has not been modified since it was read.
+async fn claim_first_key(kv: Kv, candidates: Vec<KeyValue>) -> Result<Option<KeyValue>, Error> {
+    for candidate in candidates {
+        if delete_revision_key(kv.clone(), candidate.key.clone(), candidate.mod_revision).await? {
+            return Ok(Some(candidate));
+        }
+    }
+    Ok(None)
+}
+
+/// Deletes `key` only if it was last modified at `mod_revision`.
+pub(crate) async fn delete_revision_key(
+    kv: Kv,
+    key: Vec<u8>,
+    mod_revision: i64,
+) -> Result<bool, Error> {
+    let mut txn_request = TxnRequest::new();
+    txn_request.compare.push(compare_mod_revision(
+        key.clone(),
+        Compare_CompareResult::EQUAL,
+        mod_revision,
+    ));
+    txn_request.success.push(delete_op(key));
+
+    Ok(kv.txn(txn_request).await?.succeeded)
+}
@@ -0,0 +1,202 @@
+use crate::errors::Error;
+use crate::lease::Lease;
+use crate::rpc::PutRequest;
+use crate::transport::Client;
+use crate::watch::{prefix_snapshots, range_prefix, Snapshot};
+use futures::executor::block_on;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Registers instances of services under `/services/<name>/<instance id>`, and discovers them.
+///
+/// Each instance's key is bound to a lease that a background thread keeps alive, so the instance
+/// disappears if its process does. If the lease is lost anyway, the instance registers again
+/// under a new one.
+///
+/// ```no_run
+/// # async fn example(client: etcd::Client) -> Result<(), etcd::Error> {
+/// use futures::TryStreamExt;
+///
+/// let registry = etcd::ServiceRegistry::new(client);
+/// let _registration = registry
+///     .register("web", "web-1", b"10.0.0.1:8080".to_vec())
+///     .await?;
+///
+/// let mut instances = registry.discover("web");
+/// while let Some(instances) = instances.try_next().await? {
+///     // ...
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ServiceRegistry {
+    client: Client,
+    prefix: Vec<u8>,
+    ttl: i64,
+}
+
+/// An instance of a service, with the endpoint information it registered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServiceInstance {
+    pub id: String,
+    pub endpoint: Vec<u8>,
+}
+
+/// A registered instance, kept alive until this is dropped or deregistered.
+pub struct Registration {
+    key: Vec<u8>,
+    // Dropping the sender stops the keep-alive thread, which revokes the lease.
+    stop: Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+/// Keeps one instance registered.
+struct Registrar {
+    client: Client,
+    key: Vec<u8>,
+    endpoint: Vec<u8>,
+    ttl: i64,
+}
+
+impl ServiceRegistry {
+    pub fn new<C: Into<Client>>(client: C) -> ServiceRegistry {
+        ServiceRegistry {
+            client: client.into(),
+            prefix: b"/services/".to_vec(),
+            ttl: 10,
+        }
+    }
+
+    /// Registers under `prefix` instead of `/services/`.
+    pub fn with_prefix(mut self, prefix: Vec<u8>) -> ServiceRegistry {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Binds registrations to leases of `ttl` seconds, 10 by default, refreshed every third of
+    /// that.
+    pub fn with_ttl(mut self, ttl: i64) -> ServiceRegistry {
+        self.ttl = ttl;
+        self
+    }
+
+    pub async fn register(
+        &self,
+        service: &str,
+        instance_id: &str,
+        endpoint: Vec<u8>,
+    ) -> Result<Registration, Error> {
+        let registrar = Registrar {
+            client: self.client.clone(),
+            key: [
+                self.service_prefix(service),
+                instance_id.as_bytes().to_vec(),
+            ]
+            .concat(),
+            endpoint,
+            ttl: self.ttl,
+        };
+        let lease = registrar.register().await?;
+
+        let key = registrar.key.clone();
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("etcd-registration".to_string())
+            .spawn(move || registrar.run(lease, stopped))?;
+
+        Ok(Registration { key, stop, thread })
+    }
+
+    /// The live instances of `service`, ordered by ID.
+    pub async fn instances(&self, service: &str) -> Result<Vec<ServiceInstance>, Error> {
+        let prefix = self.service_prefix(service);
+        let (keys, _) = range_prefix(&self.client, &prefix).await?;
+        Ok(instances(&prefix, keys))
+    }
+
+    /// Streams the live instances of `service`, then the whole set again whenever it changes.
+    pub fn discover(
+        &self,
+        service: &str,
+    ) -> BoxStream<'static, Result<Vec<ServiceInstance>, Error>> {
+        let prefix = self.service_prefix(service);
+        prefix_snapshots(self.client.clone(), prefix.clone())
+            .map_ok(move |keys| instances(&prefix, keys))
+            .boxed()
+    }
+
+    fn service_prefix(&self, service: &str) -> Vec<u8> {
+        [&self.prefix[..], service.as_bytes(), b"/"].concat()
+    }
+}
+
+impl Registration {
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Stops keeping the instance alive and waits for its lease to be revoked.
+    pub fn deregister(self) {
+        drop(self.stop);
+        let _ = self.thread.join();
+    }
+}
+
+impl Registrar {
+    /// Puts the instance's key on a new lease.
+    async fn register(&self) -> Result<Lease, Error> {
+        let lease = Lease::new(self.ttl, self.client.clone()).await?;
+        let mut put_request = PutRequest::new();
+        put_request.key = self.key.clone();
+        put_request.value = self.endpoint.clone();
+        put_request.lease = lease.lease_id;
+        self.client.put(put_request).await?;
+        Ok(lease)
+    }
+
+    fn run(self, mut lease: Lease, stopped: Receiver<()>) {
+        let interval = Duration::from_millis((self.ttl.max(1) * 1000 / 3) as u64);
+        let mut keep_alive = lease.keep_alive();
+        loop {
+            if stopped.recv_timeout(interval) != Err(RecvTimeoutError::Timeout) {
+                if let Err(error) = block_on(lease.revoke()) {
+                    error!("Could not revoke lease: {}", error);
+                }
+                return;
+            }
+            let error = match block_on(keep_alive.refresh()) {
+                Ok(_) => continue,
+                Err(error) => error,
+            };
+
+            let key = String::from_utf8_lossy(&self.key);
+            warn!("Lost the lease for {}, registering again: {}", key, error);
+            if let Error::LeaseExpired = error {
+                // An expired lease has nothing left to revoke.
+                lease.lease_id = 0;
+            }
+            lease = loop {
+                match block_on(self.register()) {
+                    Ok(lease) => break lease,
+                    Err(error) => warn!("Could not register {}: {}", key, error),
+                }
+                if stopped.recv_timeout(interval) != Err(RecvTimeoutError::Timeout) {
+                    return;
+                }
+            };
+            keep_alive = lease.keep_alive();
+        }
+    }
+}
+
+fn instances(prefix: &[u8], keys: Snapshot) -> Vec<ServiceInstance> {
+    keys.into_iter()
+        .map(|(key, endpoint)| ServiceInstance {
+            id: String::from_utf8_lossy(&key[prefix.len()..]).into_owned(),
+            endpoint,
+        })
+        .collect()
+}
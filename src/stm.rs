@@ -0,0 +1,204 @@
+//! Software transactional memory on top of etcd transactions, compatible with the isolation
+//! levels of etcd's `concurrency.STM`.
+//!
+//! ```no_run
+//! use futures::FutureExt;
+//!
+//! # async fn example(client: etcd::Client) -> Result<(), etcd::Error> {
+//! etcd::stm::run(client, |tx| {
+//!     async move {
+//!         let value = tx.get("a").await?;
+//!         tx.put("b", value);
+//!         Ok(())
+//!     }
+//!     .boxed()
+//! })
+//! .await
+//! # }
+//! ```
+//!
+//! [`blocking::Client::stm`](crate::blocking::Client::stm) runs a synchronous closure instead.
+
+use crate::errors::Error;
+use crate::kv::KeyValue;
+use crate::kv_client::{compare_mod_revision, delete_op, put_op, Kv};
+use crate::rpc::{Compare_CompareResult, RangeRequest, TxnRequest};
+use crate::transport::Client;
+use futures::future::BoxFuture;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Isolation {
+    /// Reads come from a single revision, and the transaction aborts if any key read or written
+    /// changed after it.
+    SerializableSnapshot,
+    /// Reads come from a single revision, and the transaction aborts if any key read changed
+    /// after it.
+    Serializable,
+    /// The transaction aborts if any key read changed before commit.
+    RepeatableReads,
+    /// Writes are committed whether or not the keys read have changed.
+    ReadCommitted,
+}
+
+/// The reads and writes of one attempt at a transaction.
+pub struct Stm {
+    kv: Kv,
+    isolation: Isolation,
+    reads: BTreeMap<Vec<u8>, Read>,
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+struct Read {
+    kv: Option<KeyValue>,
+    revision: i64,
+}
+
+/// Runs `apply` with serializable snapshot isolation until its writes commit without conflict.
+pub async fn run<C, F, T>(client: C, apply: F) -> Result<T, Error>
+where
+    C: Into<Client>,
+    F: for<'a> FnMut(&'a mut Stm) -> BoxFuture<'a, Result<T, Error>>,
+{
+    run_isolated(client, Isolation::SerializableSnapshot, apply).await
+}
+
+/// Runs `apply` with the given isolation until its writes commit without conflict.
+pub async fn run_isolated<C, F, T>(
+    client: C,
+    isolation: Isolation,
+    mut apply: F,
+) -> Result<T, Error>
+where
+    C: Into<Client>,
+    F: for<'a> FnMut(&'a mut Stm) -> BoxFuture<'a, Result<T, Error>>,
+{
+    let mut stm = Stm::new(client, isolation);
+
+    loop {
+        stm.reset();
+
+        let result = apply(&mut stm).await?;
+        if stm.commit().await? {
+            return Ok(result);
+        }
+
+        trace!("STM transaction conflicted, retrying...");
+    }
+}
+
+impl Stm {
+    pub(crate) fn new<C: Into<Client>>(client: C, isolation: Isolation) -> Stm {
+        Stm {
+            kv: Kv::new(client),
+            isolation,
+            reads: BTreeMap::new(),
+            writes: BTreeMap::new(),
+        }
+    }
+
+    /// Forgets the reads and writes of the previous attempt.
+    pub(crate) fn reset(&mut self) {
+        self.reads.clear();
+        self.writes.clear();
+    }
+
+    /// The value of `key`, or an empty value if it does not exist.
+    pub async fn get<K: Into<Vec<u8>>>(&mut self, key: K) -> Result<Vec<u8>, Error> {
+        let key = key.into();
+        if let Some(write) = self.writes.get(&key) {
+            return Ok(write.clone().unwrap_or_default());
+        }
+
+        Ok(self
+            .fetch(key)
+            .await?
+            .map(|kv| kv.value.clone())
+            .unwrap_or_default())
+    }
+
+    /// The revision `key` was last modified at, or 0 if it does not exist.
+    pub async fn rev<K: Into<Vec<u8>>>(&mut self, key: K) -> Result<i64, Error> {
+        Ok(self
+            .fetch(key.into())
+            .await?
+            .map(|kv| kv.mod_revision)
+            .unwrap_or_default())
+    }
+
+    pub fn put<K: Into<Vec<u8>>, V: Into<Vec<u8>>>(&mut self, key: K, value: V) {
+        self.writes.insert(key.into(), Some(value.into()));
+    }
+
+    pub fn delete<K: Into<Vec<u8>>>(&mut self, key: K) {
+        self.writes.insert(key.into(), None);
+    }
+
+    async fn fetch(&mut self, key: Vec<u8>) -> Result<Option<&KeyValue>, Error> {
+        if !self.reads.contains_key(&key) {
+            let mut range_request = RangeRequest::new();
+            range_request.key = key.clone();
+            match self.isolation {
+                Isolation::SerializableSnapshot | Isolation::Serializable => {
+                    // Every read after the first comes from the first read's revision.
+                    if let Some(revision) = self.first_revision() {
+                        range_request.revision = revision;
+                        range_request.serializable = true;
+                    }
+                }
+                Isolation::RepeatableReads | Isolation::ReadCommitted => {
+                    range_request.serializable = true;
+                }
+            }
+
+            let response = self.kv.range(range_request).await?;
+            let read = Read {
+                revision: response.get_header().revision,
+                kv: response.kvs.into_iter().next(),
+            };
+            self.reads.insert(key.clone(), read);
+        }
+
+        Ok(self.reads.get(&key).and_then(|read| read.kv.as_ref()))
+    }
+
+    fn first_revision(&self) -> Option<i64> {
+        self.reads.values().map(|read| read.revision).min()
+    }
+
+    pub(crate) async fn commit(&mut self) -> Result<bool, Error> {
+        let mut txn_request = TxnRequest::new();
+
+        if self.isolation != Isolation::ReadCommitted {
+            for (key, read) in self.reads.iter() {
+                let mod_revision = read.kv.as_ref().map(|kv| kv.mod_revision).unwrap_or(0);
+                txn_request.compare.push(compare_mod_revision(
+                    key.clone(),
+                    Compare_CompareResult::EQUAL,
+                    mod_revision,
+                ));
+            }
+        }
+
+        if self.isolation == Isolation::SerializableSnapshot {
+            if let Some(revision) = self.first_revision() {
+                for key in self.writes.keys() {
+                    txn_request.compare.push(compare_mod_revision(
+                        key.clone(),
+                        Compare_CompareResult::LESS,
+                        revision + 1,
+                    ));
+                }
+            }
+        }
+
+        for (key, value) in self.writes.iter() {
+            txn_request.success.push(match value {
+                Some(value) => put_op(key.clone(), value.clone(), 0),
+                None => delete_op(key.clone()),
+            });
+        }
+
+        Ok(self.kv.txn(txn_request).await?.succeeded)
+    }
+}
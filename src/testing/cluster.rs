@@ -0,0 +1,350 @@
+use super::store::{status, Result};
+use crate::auth::Permission;
+use crate::rpc::{
+    AuthRoleAddResponse, AuthRoleDeleteResponse, AuthRoleGetResponse,
+    AuthRoleGrantPermissionRequest, AuthRoleGrantPermissionResponse, AuthRoleListResponse,
+    AuthRoleRevokePermissionRequest, AuthRoleRevokePermissionResponse, AuthUserAddRequest,
+    AuthUserAddResponse, AuthUserChangePasswordResponse, AuthUserDeleteResponse,
+    AuthUserGetResponse, AuthUserGrantRoleRequest, AuthUserGrantRoleResponse, AuthUserListResponse,
+    AuthUserRevokeRoleRequest, AuthUserRevokeRoleResponse, Member, MemberAddRequest,
+    MemberAddResponse, MemberListResponse, MemberRemoveResponse, MemberUpdateRequest,
+    MemberUpdateResponse, ResponseHeader,
+};
+use grpc::GrpcStatus;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The ID of the fake's own member, and so of the leader, as a fresh single-node etcd reports.
+pub(crate) const MEMBER_ID: u64 = 0x8e9e_05c5_2164_694d;
+pub(crate) const CLUSTER_ID: u64 = 0xcdf8_1819_4e3a_8c32;
+
+/// The membership, users and roles of a fake etcd server, which live outside its key-value store.
+///
+/// Users and roles are only bookkeeping: auth is never enabled, so they grant nothing.
+pub(crate) struct Cluster {
+    members: Vec<Member>,
+    next_member_id: u64,
+    /// Every user's roles. Passwords are not kept, as nothing checks them.
+    users: BTreeMap<String, BTreeSet<String>>,
+    roles: BTreeMap<String, Vec<Permission>>,
+}
+
+impl Cluster {
+    pub(crate) fn new() -> Cluster {
+        let mut member = Member::new();
+        member.ID = MEMBER_ID;
+        member.name = "default".to_string();
+        member.peerURLs.push("http://localhost:2380".to_string());
+
+        Cluster {
+            members: vec![member],
+            next_member_id: MEMBER_ID + 1,
+            users: BTreeMap::new(),
+            roles: BTreeMap::new(),
+        }
+    }
+
+    /// Advertises `url` as one of the fake's own client URLs.
+    pub(crate) fn serving(&mut self, url: String) {
+        self.members[0].clientURLs.push(url);
+    }
+
+    /// Adds a member that never starts, so it has no name and no client URLs.
+    pub(crate) fn member_add(
+        &mut self,
+        request: &MemberAddRequest,
+        header: ResponseHeader,
+    ) -> Result<MemberAddResponse> {
+        let mut member = Member::new();
+        member.ID = self.next_member_id;
+        member.peerURLs = request.peerURLs.clone();
+        self.next_member_id += 1;
+        self.members.push(member.clone());
+
+        let mut response = MemberAddResponse::new();
+        response.set_header(header);
+        response.set_member(member);
+        response.members = self.members.clone().into();
+        Ok(response)
+    }
+
+    pub(crate) fn member_remove(
+        &mut self,
+        id: u64,
+        header: ResponseHeader,
+    ) -> Result<MemberRemoveResponse> {
+        let index = self.member(id)?;
+        self.members.remove(index);
+
+        let mut response = MemberRemoveResponse::new();
+        response.set_header(header);
+        response.members = self.members.clone().into();
+        Ok(response)
+    }
+
+    pub(crate) fn member_update(
+        &mut self,
+        request: &MemberUpdateRequest,
+        header: ResponseHeader,
+    ) -> Result<MemberUpdateResponse> {
+        let index = self.member(request.ID)?;
+        self.members[index].peerURLs = request.peerURLs.clone();
+
+        let mut response = MemberUpdateResponse::new();
+        response.set_header(header);
+        response.members = self.members.clone().into();
+        Ok(response)
+    }
+
+    pub(crate) fn member_list(&self, header: ResponseHeader) -> Result<MemberListResponse> {
+        let mut response = MemberListResponse::new();
+        response.set_header(header);
+        response.members = self.members.clone().into();
+        Ok(response)
+    }
+
+    pub(crate) fn user_add(
+        &mut self,
+        request: &AuthUserAddRequest,
+        header: ResponseHeader,
+    ) -> Result<AuthUserAddResponse> {
+        if request.name.is_empty() {
+            return Err(status(
+                GrpcStatus::Argument,
+                "etcdserver: user name is empty",
+            ));
+        }
+        if self.users.contains_key(&request.name) {
+            return Err(status(
+                GrpcStatus::FailedPrecondition,
+                "etcdserver: user name already exists",
+            ));
+        }
+        self.users.insert(request.name.clone(), BTreeSet::new());
+
+        let mut response = AuthUserAddResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    pub(crate) fn user_get(
+        &self,
+        name: &str,
+        header: ResponseHeader,
+    ) -> Result<AuthUserGetResponse> {
+        let roles = self.users.get(name).ok_or_else(user_not_found)?;
+
+        let mut response = AuthUserGetResponse::new();
+        response.set_header(header);
+        response.roles = roles.iter().cloned().collect::<Vec<_>>().into();
+        Ok(response)
+    }
+
+    pub(crate) fn user_list(&self, header: ResponseHeader) -> Result<AuthUserListResponse> {
+        let mut response = AuthUserListResponse::new();
+        response.set_header(header);
+        response.users = self.users.keys().cloned().collect::<Vec<_>>().into();
+        Ok(response)
+    }
+
+    pub(crate) fn user_delete(
+        &mut self,
+        name: &str,
+        header: ResponseHeader,
+    ) -> Result<AuthUserDeleteResponse> {
+        self.users.remove(name).ok_or_else(user_not_found)?;
+
+        let mut response = AuthUserDeleteResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    pub(crate) fn user_change_password(
+        &mut self,
+        name: &str,
+        header: ResponseHeader,
+    ) -> Result<AuthUserChangePasswordResponse> {
+        if !self.users.contains_key(name) {
+            return Err(user_not_found());
+        }
+
+        let mut response = AuthUserChangePasswordResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    pub(crate) fn user_grant_role(
+        &mut self,
+        request: &AuthUserGrantRoleRequest,
+        header: ResponseHeader,
+    ) -> Result<AuthUserGrantRoleResponse> {
+        if !self.roles.contains_key(&request.role) {
+            return Err(role_not_found());
+        }
+        let roles = self
+            .users
+            .get_mut(&request.user)
+            .ok_or_else(user_not_found)?;
+        roles.insert(request.role.clone());
+
+        let mut response = AuthUserGrantRoleResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    pub(crate) fn user_revoke_role(
+        &mut self,
+        request: &AuthUserRevokeRoleRequest,
+        header: ResponseHeader,
+    ) -> Result<AuthUserRevokeRoleResponse> {
+        let roles = self
+            .users
+            .get_mut(&request.name)
+            .ok_or_else(user_not_found)?;
+        if !roles.remove(&request.role) {
+            return Err(status(
+                GrpcStatus::FailedPrecondition,
+                "etcdserver: role is not granted to the user",
+            ));
+        }
+
+        let mut response = AuthUserRevokeRoleResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    pub(crate) fn role_add(
+        &mut self,
+        name: &str,
+        header: ResponseHeader,
+    ) -> Result<AuthRoleAddResponse> {
+        if name.is_empty() {
+            return Err(status(
+                GrpcStatus::Argument,
+                "etcdserver: role name is empty",
+            ));
+        }
+        if self.roles.contains_key(name) {
+            return Err(status(
+                GrpcStatus::FailedPrecondition,
+                "etcdserver: role name already exists",
+            ));
+        }
+        self.roles.insert(name.to_string(), Vec::new());
+
+        let mut response = AuthRoleAddResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    pub(crate) fn role_get(
+        &self,
+        name: &str,
+        header: ResponseHeader,
+    ) -> Result<AuthRoleGetResponse> {
+        let permissions = self.roles.get(name).ok_or_else(role_not_found)?;
+
+        let mut response = AuthRoleGetResponse::new();
+        response.set_header(header);
+        response.perm = permissions.clone().into();
+        Ok(response)
+    }
+
+    pub(crate) fn role_list(&self, header: ResponseHeader) -> Result<AuthRoleListResponse> {
+        let mut response = AuthRoleListResponse::new();
+        response.set_header(header);
+        response.roles = self.roles.keys().cloned().collect::<Vec<_>>().into();
+        Ok(response)
+    }
+
+    /// Deletes a role, revoking it from every user that had it, as etcd does.
+    pub(crate) fn role_delete(
+        &mut self,
+        name: &str,
+        header: ResponseHeader,
+    ) -> Result<AuthRoleDeleteResponse> {
+        self.roles.remove(name).ok_or_else(role_not_found)?;
+        for roles in self.users.values_mut() {
+            roles.remove(name);
+        }
+
+        let mut response = AuthRoleDeleteResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    /// Grants a permission, replacing the type of any already granted on the same range. The
+    /// permissions are kept in key order.
+    pub(crate) fn role_grant_permission(
+        &mut self,
+        request: &AuthRoleGrantPermissionRequest,
+        header: ResponseHeader,
+    ) -> Result<AuthRoleGrantPermissionResponse> {
+        let permissions = self
+            .roles
+            .get_mut(&request.name)
+            .ok_or_else(role_not_found)?;
+        let permission = request.get_perm();
+        match permissions.binary_search_by(|granted| range(granted).cmp(&range(permission))) {
+            Ok(index) => permissions[index].permType = permission.permType,
+            Err(index) => permissions.insert(index, permission.clone()),
+        }
+
+        let mut response = AuthRoleGrantPermissionResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    pub(crate) fn role_revoke_permission(
+        &mut self,
+        request: &AuthRoleRevokePermissionRequest,
+        header: ResponseHeader,
+    ) -> Result<AuthRoleRevokePermissionResponse> {
+        let permissions = self
+            .roles
+            .get_mut(&request.role)
+            .ok_or_else(role_not_found)?;
+        let index = permissions
+            .iter()
+            .position(|granted| {
+                granted.key == request.key && granted.range_end == request.range_end
+            })
+            .ok_or_else(|| {
+                status(
+                    GrpcStatus::FailedPrecondition,
+                    "etcdserver: permission is not granted to the role",
+                )
+            })?;
+        permissions.remove(index);
+
+        let mut response = AuthRoleRevokePermissionResponse::new();
+        response.set_header(header);
+        Ok(response)
+    }
+
+    /// The index of the member with `id`.
+    fn member(&self, id: u64) -> Result<usize> {
+        self.members
+            .iter()
+            .position(|member| member.ID == id)
+            .ok_or_else(|| status(GrpcStatus::NotFound, "etcdserver: member not found"))
+    }
+}
+
+/// The range a permission covers, which orders a role's permissions.
+fn range(permission: &Permission) -> (&[u8], &[u8]) {
+    (&permission.key, &permission.range_end)
+}
+
+fn user_not_found() -> grpc::Error {
+    status(
+        GrpcStatus::FailedPrecondition,
+        "etcdserver: user name not found",
+    )
+}
+
+fn role_not_found() -> grpc::Error {
+    status(
+        GrpcStatus::FailedPrecondition,
+        "etcdserver: role name not found",
+    )
+}
@@ -0,0 +1,177 @@
+use grpc::GrpcStatus;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// An RPC served by `FakeEtcd`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Method {
+    Range,
+    Put,
+    DeleteRange,
+    Txn,
+    Compact,
+    Watch,
+    LeaseGrant,
+    LeaseRevoke,
+    LeaseKeepAlive,
+    LeaseTimeToLive,
+    LeaseLeases,
+    Lock,
+    Unlock,
+    Campaign,
+    Proclaim,
+    Leader,
+    Observe,
+    Resign,
+    MemberAdd,
+    MemberRemove,
+    MemberUpdate,
+    MemberList,
+    Status,
+    Snapshot,
+    UserAdd,
+    UserGet,
+    UserList,
+    UserDelete,
+    UserChangePassword,
+    UserGrantRole,
+    UserRevokeRole,
+    RoleAdd,
+    RoleGet,
+    RoleList,
+    RoleDelete,
+    RoleGrantPermission,
+    RoleRevokePermission,
+}
+
+impl Method {
+    pub(crate) const ALL: [Method; 37] = [
+        Method::Range,
+        Method::Put,
+        Method::DeleteRange,
+        Method::Txn,
+        Method::Compact,
+        Method::Watch,
+        Method::LeaseGrant,
+        Method::LeaseRevoke,
+        Method::LeaseKeepAlive,
+        Method::LeaseTimeToLive,
+        Method::LeaseLeases,
+        Method::Lock,
+        Method::Unlock,
+        Method::Campaign,
+        Method::Proclaim,
+        Method::Leader,
+        Method::Observe,
+        Method::Resign,
+        Method::MemberAdd,
+        Method::MemberRemove,
+        Method::MemberUpdate,
+        Method::MemberList,
+        Method::Status,
+        Method::Snapshot,
+        Method::UserAdd,
+        Method::UserGet,
+        Method::UserList,
+        Method::UserDelete,
+        Method::UserChangePassword,
+        Method::UserGrantRole,
+        Method::UserRevokeRole,
+        Method::RoleAdd,
+        Method::RoleGet,
+        Method::RoleList,
+        Method::RoleDelete,
+        Method::RoleGrantPermission,
+        Method::RoleRevokePermission,
+    ];
+
+    /// The method's gRPC path.
+    pub fn path(self) -> &'static str {
+        match self {
+            Method::Range => "/etcdserverpb.KV/Range",
+            Method::Put => "/etcdserverpb.KV/Put",
+            Method::DeleteRange => "/etcdserverpb.KV/DeleteRange",
+            Method::Txn => "/etcdserverpb.KV/Txn",
+            Method::Compact => "/etcdserverpb.KV/Compact",
+            Method::Watch => "/etcdserverpb.Watch/Watch",
+            Method::LeaseGrant => "/etcdserverpb.Lease/LeaseGrant",
+            Method::LeaseRevoke => "/etcdserverpb.Lease/LeaseRevoke",
+            Method::LeaseKeepAlive => "/etcdserverpb.Lease/LeaseKeepAlive",
+            Method::LeaseTimeToLive => "/etcdserverpb.Lease/LeaseTimeToLive",
+            Method::LeaseLeases => "/etcdserverpb.Lease/LeaseLeases",
+            Method::Lock => "/v3lockpb.Lock/Lock",
+            Method::Unlock => "/v3lockpb.Lock/Unlock",
+            Method::Campaign => "/v3electionpb.Election/Campaign",
+            Method::Proclaim => "/v3electionpb.Election/Proclaim",
+            Method::Leader => "/v3electionpb.Election/Leader",
+            Method::Observe => "/v3electionpb.Election/Observe",
+            Method::Resign => "/v3electionpb.Election/Resign",
+            Method::MemberAdd => "/etcdserverpb.Cluster/MemberAdd",
+            Method::MemberRemove => "/etcdserverpb.Cluster/MemberRemove",
+            Method::MemberUpdate => "/etcdserverpb.Cluster/MemberUpdate",
+            Method::MemberList => "/etcdserverpb.Cluster/MemberList",
+            Method::Status => "/etcdserverpb.Maintenance/Status",
+            Method::Snapshot => "/etcdserverpb.Maintenance/Snapshot",
+            Method::UserAdd => "/etcdserverpb.Auth/UserAdd",
+            Method::UserGet => "/etcdserverpb.Auth/UserGet",
+            Method::UserList => "/etcdserverpb.Auth/UserList",
+            Method::UserDelete => "/etcdserverpb.Auth/UserDelete",
+            Method::UserChangePassword => "/etcdserverpb.Auth/UserChangePassword",
+            Method::UserGrantRole => "/etcdserverpb.Auth/UserGrantRole",
+            Method::UserRevokeRole => "/etcdserverpb.Auth/UserRevokeRole",
+            Method::RoleAdd => "/etcdserverpb.Auth/RoleAdd",
+            Method::RoleGet => "/etcdserverpb.Auth/RoleGet",
+            Method::RoleList => "/etcdserverpb.Auth/RoleList",
+            Method::RoleDelete => "/etcdserverpb.Auth/RoleDelete",
+            Method::RoleGrantPermission => "/etcdserverpb.Auth/RoleGrantPermission",
+            Method::RoleRevokePermission => "/etcdserverpb.Auth/RoleRevokePermission",
+        }
+    }
+}
+
+/// What to do to a call instead of, or before, serving it.
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// Serve the call after waiting.
+    Delay(Duration),
+    /// Never respond.
+    Drop,
+    /// Fail with a gRPC status code and message without serving the call.
+    Status(i32, String),
+}
+
+impl Fault {
+    pub fn status(status: GrpcStatus, message: &str) -> Fault {
+        Fault::Status(status as i32, message.to_string())
+    }
+}
+
+/// The faults injected into each method, consulted once per call.
+#[derive(Default)]
+pub(crate) struct Faults {
+    once: HashMap<Method, Vec<Fault>>,
+    always: HashMap<Method, Fault>,
+}
+
+impl Faults {
+    pub(crate) fn inject(&mut self, method: Method, fault: Fault) {
+        self.always.insert(method, fault);
+    }
+
+    pub(crate) fn inject_once(&mut self, method: Method, fault: Fault) {
+        self.once.entry(method).or_default().push(fault);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.once.clear();
+        self.always.clear();
+    }
+
+    /// The fault for the next call to `method`. One-off faults go first, in the order injected.
+    pub(crate) fn next(&mut self, method: Method) -> Option<Fault> {
+        match self.once.get_mut(&method) {
+            Some(once) if !once.is_empty() => Some(once.remove(0)),
+            _ => self.always.get(&method).cloned(),
+        }
+    }
+}
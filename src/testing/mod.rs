@@ -0,0 +1,816 @@
+//! An in-process fake etcd server for tests that should not depend on a real etcd binary.
+//!
+//! The fake keeps an MVCC store in memory and implements the `KV`, `Watch`, `Lease`, `Lock` and
+//! `Election` services. Leases expire on a clock that only moves when the test calls `FakeEtcd::advance`.
+//! It is also a one-member `Cluster` that serves `Maintenance` status and snapshots, and keeps
+//! `Auth` users and roles without ever enforcing them. Alarms, defragmentation, hashes, leader
+//! moves and enabling auth fail with `Unimplemented`.
+//!
+//! Faults can be injected into any RPC to delay it, drop it, or fail it with a gRPC status, and
+//! watch streams and leases can be broken on demand, to exercise retry and resume logic.
+//!
+//! `Recorder` wraps a client to capture its traffic with a real etcd to a file, and `Replay` serves
+//! a capture back so the same interaction can run offline.
+//!
+//! ```no_run
+//! # fn example() -> Result<(), etcd::Error> {
+//! use std::time::Duration;
+//!
+//! let etcd = etcd::testing::FakeEtcd::new();
+//! let server = etcd.serve()?;
+//! let kv = etcd::Kv::new(server.client()?);
+//! // ...
+//! etcd.advance(Duration::from_secs(60));
+//! # Ok(())
+//! # }
+//! ```
+
+mod cluster;
+mod faults;
+mod recording;
+mod store;
+
+pub use self::faults::{Fault, Method};
+pub use self::recording::{Recorder, Replay};
+
+use self::cluster::{Cluster, MEMBER_ID};
+use self::faults::Faults;
+use self::store::{status, Store};
+use crate::errors::Error;
+use crate::rpc::{
+    AlarmRequest, AlarmResponse, AuthDisableRequest, AuthDisableResponse, AuthEnableRequest,
+    AuthEnableResponse, AuthRoleAddRequest, AuthRoleAddResponse, AuthRoleDeleteRequest,
+    AuthRoleDeleteResponse, AuthRoleGetRequest, AuthRoleGetResponse,
+    AuthRoleGrantPermissionRequest, AuthRoleGrantPermissionResponse, AuthRoleListRequest,
+    AuthRoleListResponse, AuthRoleRevokePermissionRequest, AuthRoleRevokePermissionResponse,
+    AuthUserAddRequest, AuthUserAddResponse, AuthUserChangePasswordRequest,
+    AuthUserChangePasswordResponse, AuthUserDeleteRequest, AuthUserDeleteResponse,
+    AuthUserGetRequest, AuthUserGetResponse, AuthUserGrantRoleRequest, AuthUserGrantRoleResponse,
+    AuthUserListRequest, AuthUserListResponse, AuthUserRevokeRoleRequest,
+    AuthUserRevokeRoleResponse, AuthenticateRequest, AuthenticateResponse, CompactionRequest,
+    CompactionResponse, DefragmentRequest, DefragmentResponse, DeleteRangeRequest,
+    DeleteRangeResponse, HashKVRequest, HashKVResponse, HashRequest, HashResponse,
+    LeaseGrantRequest, LeaseGrantResponse, LeaseKeepAliveRequest, LeaseKeepAliveResponse,
+    LeaseLeasesRequest, LeaseLeasesResponse, LeaseRevokeRequest, LeaseRevokeResponse,
+    LeaseTimeToLiveRequest, LeaseTimeToLiveResponse, MemberAddRequest, MemberAddResponse,
+    MemberListRequest, MemberListResponse, MemberRemoveRequest, MemberRemoveResponse,
+    MemberUpdateRequest, MemberUpdateResponse, MoveLeaderRequest, MoveLeaderResponse, PutRequest,
+    PutResponse, RangeRequest, RangeResponse, ResponseHeader, SnapshotRequest, SnapshotResponse,
+    StatusRequest, StatusResponse, TxnRequest, TxnResponse, WatchRequest, WatchResponse,
+};
+use crate::rpc_grpc::{
+    self, Auth, AuthServer, ClusterServer, KVServer, Lease, LeaseServer, Maintenance,
+    MaintenanceServer, Watch, WatchServer, KV,
+};
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderRequest, LeaderResponse, ProclaimRequest,
+    ProclaimResponse, ResignRequest, ResignResponse,
+};
+use crate::v3election_grpc::{Election, ElectionServer};
+use crate::v3lock::{LockRequest, LockResponse, UnlockRequest, UnlockResponse};
+use crate::v3lock_grpc::{Lock, LockServer};
+use futures01::sync::{mpsc::unbounded, oneshot};
+use futures01::{future, stream, Future, Stream};
+use grpc::rt::ServerServiceDefinition;
+use grpc::{
+    GrpcMessageError, GrpcStatus, RequestOptions, SingleResponse, StreamingRequest,
+    StreamingResponse,
+};
+use protobuf::Message;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// The etcd version the fake reports in its status.
+const VERSION: &str = "3.4.0";
+
+/// A handle to an in-memory etcd store. Clones share the same store.
+#[derive(Clone)]
+pub struct FakeEtcd {
+    store: Arc<Mutex<Store>>,
+    cluster: Arc<Mutex<Cluster>>,
+    faults: Arc<Mutex<Faults>>,
+    streams: Arc<AtomicUsize>,
+}
+
+/// A gRPC server listening on a local port until dropped.
+pub struct TestServer {
+    port: u16,
+    _server: grpc::Server,
+}
+
+impl FakeEtcd {
+    pub fn new() -> FakeEtcd {
+        FakeEtcd {
+            store: Arc::new(Mutex::new(Store::new())),
+            cluster: Arc::new(Mutex::new(Cluster::new())),
+            faults: Arc::new(Mutex::new(Faults::default())),
+            streams: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// The revision of the last write.
+    pub fn revision(&self) -> i64 {
+        self.store().revision()
+    }
+
+    /// Moves the lease clock forward, expiring every lease that has not been kept alive in time.
+    pub fn advance(&self, duration: Duration) {
+        self.store().advance(duration)
+    }
+
+    /// Serves this store on a random local port, which the fake's member then lists as one of
+    /// its client URLs.
+    pub fn serve(&self) -> Result<TestServer, Error> {
+        let server = TestServer::new(vec![
+            KVServer::new_service_def(self.clone()),
+            WatchServer::new_service_def(self.clone()),
+            LeaseServer::new_service_def(self.clone()),
+            ClusterServer::new_service_def(self.clone()),
+            MaintenanceServer::new_service_def(self.clone()),
+            AuthServer::new_service_def(self.clone()),
+            LockServer::new_service_def(self.clone()),
+            ElectionServer::new_service_def(self.clone()),
+        ])?;
+        self.cluster()
+            .serving(format!("http://127.0.0.1:{}", server.port()));
+        Ok(server)
+    }
+
+    /// Injects `fault` into every call to `method` until the faults are cleared.
+    pub fn inject(&self, method: Method, fault: Fault) {
+        self.faults().inject(method, fault)
+    }
+
+    /// Injects `fault` into the next call to `method` only.
+    pub fn inject_once(&self, method: Method, fault: Fault) {
+        self.faults().inject_once(method, fault)
+    }
+
+    pub fn clear_faults(&self) {
+        self.faults().clear()
+    }
+
+    /// Ends every open watch stream with an `Unavailable` status, as if the connection to the
+    /// server had been lost.
+    pub fn break_watches(&self) {
+        self.store().break_watches()
+    }
+
+    /// Revokes a lease as if it had expired, whatever the clock says.
+    pub fn expire(&self, lease_id: i64) {
+        self.store().expire(lease_id)
+    }
+
+    /// Serves a unary call with `respond`, unless a fault has been injected into `method`.
+    fn faulted<T, F>(&self, method: Method, respond: F) -> SingleResponse<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&FakeEtcd) -> SingleResponse<T> + Send + 'static,
+    {
+        match self.faults().next(method) {
+            None => respond(self),
+            Some(Fault::Delay(delay)) => {
+                let etcd = self.clone();
+                SingleResponse::no_metadata(
+                    sleep(delay).and_then(move |()| respond(&etcd).drop_metadata()),
+                )
+            }
+            Some(Fault::Drop) => SingleResponse::no_metadata(future::empty()),
+            Some(Fault::Status(grpc_status, grpc_message)) => {
+                single(Err(grpc::Error::GrpcMessage(GrpcMessageError {
+                    grpc_status,
+                    grpc_message,
+                })))
+            }
+        }
+    }
+
+    /// Serves a streaming call with `respond`, unless a fault has been injected into `method`.
+    fn faulted_stream<T, F>(&self, method: Method, respond: F) -> StreamingResponse<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&FakeEtcd) -> StreamingResponse<T> + Send + 'static,
+    {
+        match self.faults().next(method) {
+            None => respond(self),
+            Some(Fault::Delay(delay)) => {
+                let etcd = self.clone();
+                StreamingResponse::no_metadata(
+                    sleep(delay)
+                        .map(move |()| respond(&etcd).drop_metadata())
+                        .flatten_stream(),
+                )
+            }
+            Some(Fault::Drop) => StreamingResponse::no_metadata(future::empty().into_stream()),
+            Some(Fault::Status(grpc_status, grpc_message)) => StreamingResponse::no_metadata(
+                stream::once(Err(grpc::Error::GrpcMessage(GrpcMessageError {
+                    grpc_status,
+                    grpc_message,
+                }))),
+            ),
+        }
+    }
+
+    /// Serves a unary call to the cluster with `respond`, which is handed the store's header.
+    fn clustered<T, F>(&self, method: Method, respond: F) -> SingleResponse<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Cluster, ResponseHeader) -> store::Result<T> + Send + 'static,
+    {
+        self.faulted(method, move |etcd| {
+            let header = etcd.store().header();
+            single(respond(&mut etcd.cluster(), header))
+        })
+    }
+
+    /// Every live key-value, encoded one by one. This stands in for the backend database, which
+    /// the fake does not have, so a snapshot of it cannot be restored by etcd.
+    fn backend(&self) -> Vec<Vec<u8>> {
+        let mut range_request = RangeRequest::new();
+        range_request.key = vec![0];
+        range_request.range_end = vec![0];
+        let range_response = self
+            .store()
+            .range(&range_request)
+            .expect("The whole keyspace is always readable");
+        range_response
+            .kvs
+            .iter()
+            .map(|kv| {
+                kv.write_length_delimited_to_bytes()
+                    .expect("A key-value always encodes")
+            })
+            .collect()
+    }
+
+    fn cluster(&self) -> MutexGuard<'_, Cluster> {
+        match self.cluster.lock() {
+            Ok(cluster) => cluster,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn faults(&self) -> MutexGuard<'_, Faults> {
+        match self.faults.lock() {
+            Ok(faults) => faults,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn store(&self) -> MutexGuard<'_, Store> {
+        // A panicking test should not take every other test sharing the store down with it.
+        match self.store.lock() {
+            Ok(store) => store,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl Default for FakeEtcd {
+    fn default() -> FakeEtcd {
+        FakeEtcd::new()
+    }
+}
+
+impl TestServer {
+    /// Serves `services` on a random local port.
+    fn new(services: Vec<ServerServiceDefinition>) -> Result<TestServer, Error> {
+        let mut server_builder = grpc::ServerBuilder::new_plain();
+        server_builder
+            .http
+            .set_addr(("127.0.0.1", 0))
+            .map_err(grpc::Error::from)?;
+        for service in services {
+            server_builder.add_service(service);
+        }
+        let server = server_builder.build()?;
+        let port = server.local_addr().port().map_err(grpc::Error::from)?;
+
+        trace!("Serving test server on port {}", port);
+
+        Ok(TestServer {
+            port,
+            _server: server,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// A new client connected to this server.
+    pub fn client(&self) -> Result<Arc<grpc::Client>, Error> {
+        let client = grpc::Client::new_plain("127.0.0.1", self.port, Default::default())?;
+        Ok(Arc::new(client))
+    }
+}
+
+impl KV for FakeEtcd {
+    fn range(&self, _: RequestOptions, request: RangeRequest) -> SingleResponse<RangeResponse> {
+        self.faulted(Method::Range, move |etcd| {
+            single(etcd.store().range(&request))
+        })
+    }
+
+    fn put(&self, _: RequestOptions, request: PutRequest) -> SingleResponse<PutResponse> {
+        self.faulted(Method::Put, move |etcd| single(etcd.store().put(&request)))
+    }
+
+    fn delete_range(
+        &self,
+        _: RequestOptions,
+        request: DeleteRangeRequest,
+    ) -> SingleResponse<DeleteRangeResponse> {
+        self.faulted(Method::DeleteRange, move |etcd| {
+            single(etcd.store().delete_range(&request))
+        })
+    }
+
+    fn txn(&self, _: RequestOptions, request: TxnRequest) -> SingleResponse<TxnResponse> {
+        self.faulted(Method::Txn, move |etcd| single(etcd.store().txn(&request)))
+    }
+
+    fn compact(
+        &self,
+        _: RequestOptions,
+        request: CompactionRequest,
+    ) -> SingleResponse<CompactionResponse> {
+        self.faulted(Method::Compact, move |etcd| {
+            let mut store = etcd.store();
+            single(store.compact(request.revision).map(|()| {
+                let mut response = CompactionResponse::new();
+                response.set_header(store.header());
+                response
+            }))
+        })
+    }
+}
+
+impl Watch for FakeEtcd {
+    fn watch(
+        &self,
+        _: RequestOptions,
+        requests: StreamingRequest<WatchRequest>,
+    ) -> StreamingResponse<WatchResponse> {
+        self.faulted_stream(Method::Watch, move |etcd| {
+            let stream = etcd.streams.fetch_add(1, Ordering::SeqCst) as u64;
+            let (sender, receiver) = unbounded();
+            let handler = etcd.clone();
+
+            // Requests are handled as the response stream is polled, and only ever yield
+            // responses through the channel shared with the store's watchers.
+            let handled = requests.0.filter_map(move |request| {
+                handler.store().watch_request(stream, request, &sender);
+                None::<WatchResponse>
+            });
+            let responses = receiver
+                .map_err(|()| grpc::Error::Other("watch response stream failed"))
+                .and_then(|response| response);
+
+            StreamingResponse::no_metadata(handled.select(responses))
+        })
+    }
+}
+
+impl Lease for FakeEtcd {
+    fn lease_grant(
+        &self,
+        _: RequestOptions,
+        request: LeaseGrantRequest,
+    ) -> SingleResponse<LeaseGrantResponse> {
+        self.faulted(Method::LeaseGrant, move |etcd| {
+            single(etcd.store().lease_grant(&request))
+        })
+    }
+
+    fn lease_revoke(
+        &self,
+        _: RequestOptions,
+        request: LeaseRevokeRequest,
+    ) -> SingleResponse<LeaseRevokeResponse> {
+        self.faulted(Method::LeaseRevoke, move |etcd| {
+            single(etcd.store().lease_revoke(request.ID))
+        })
+    }
+
+    fn lease_keep_alive(
+        &self,
+        _: RequestOptions,
+        requests: StreamingRequest<LeaseKeepAliveRequest>,
+    ) -> StreamingResponse<LeaseKeepAliveResponse> {
+        self.faulted_stream(Method::LeaseKeepAlive, move |etcd| {
+            let handler = etcd.clone();
+            StreamingResponse::no_metadata(
+                requests
+                    .0
+                    .map(move |request| handler.store().lease_keep_alive(request.ID)),
+            )
+        })
+    }
+
+    fn lease_time_to_live(
+        &self,
+        _: RequestOptions,
+        request: LeaseTimeToLiveRequest,
+    ) -> SingleResponse<LeaseTimeToLiveResponse> {
+        self.faulted(Method::LeaseTimeToLive, move |etcd| {
+            SingleResponse::completed(etcd.store().lease_time_to_live(&request))
+        })
+    }
+
+    fn lease_leases(
+        &self,
+        _: RequestOptions,
+        _: LeaseLeasesRequest,
+    ) -> SingleResponse<LeaseLeasesResponse> {
+        self.faulted(Method::LeaseLeases, move |etcd| {
+            SingleResponse::completed(etcd.store().lease_leases())
+        })
+    }
+}
+
+impl rpc_grpc::Cluster for FakeEtcd {
+    fn member_add(
+        &self,
+        _: RequestOptions,
+        request: MemberAddRequest,
+    ) -> SingleResponse<MemberAddResponse> {
+        self.clustered(Method::MemberAdd, move |cluster, header| {
+            cluster.member_add(&request, header)
+        })
+    }
+
+    fn member_remove(
+        &self,
+        _: RequestOptions,
+        request: MemberRemoveRequest,
+    ) -> SingleResponse<MemberRemoveResponse> {
+        self.clustered(Method::MemberRemove, move |cluster, header| {
+            cluster.member_remove(request.ID, header)
+        })
+    }
+
+    fn member_update(
+        &self,
+        _: RequestOptions,
+        request: MemberUpdateRequest,
+    ) -> SingleResponse<MemberUpdateResponse> {
+        self.clustered(Method::MemberUpdate, move |cluster, header| {
+            cluster.member_update(&request, header)
+        })
+    }
+
+    fn member_list(
+        &self,
+        _: RequestOptions,
+        _: MemberListRequest,
+    ) -> SingleResponse<MemberListResponse> {
+        self.clustered(Method::MemberList, |cluster, header| {
+            cluster.member_list(header)
+        })
+    }
+}
+
+impl Maintenance for FakeEtcd {
+    fn alarm(&self, _: RequestOptions, _: AlarmRequest) -> SingleResponse<AlarmResponse> {
+        unimplemented("Alarm")
+    }
+
+    fn status(&self, _: RequestOptions, _: StatusRequest) -> SingleResponse<StatusResponse> {
+        self.faulted(Method::Status, |etcd| {
+            let db_size: usize = etcd.backend().iter().map(Vec::len).sum();
+            let header = etcd.store().header();
+
+            let mut response = StatusResponse::new();
+            response.version = VERSION.to_string();
+            response.dbSize = db_size as i64;
+            response.dbSizeInUse = db_size as i64;
+            response.leader = MEMBER_ID;
+            response.raftIndex = header.revision as u64;
+            response.raftAppliedIndex = header.revision as u64;
+            response.raftTerm = header.raft_term;
+            response.set_header(header);
+            SingleResponse::completed(response)
+        })
+    }
+
+    fn defragment(
+        &self,
+        _: RequestOptions,
+        _: DefragmentRequest,
+    ) -> SingleResponse<DefragmentResponse> {
+        unimplemented("Defragment")
+    }
+
+    fn hash(&self, _: RequestOptions, _: HashRequest) -> SingleResponse<HashResponse> {
+        unimplemented("Hash")
+    }
+
+    fn hash_kv(&self, _: RequestOptions, _: HashKVRequest) -> SingleResponse<HashKVResponse> {
+        unimplemented("HashKV")
+    }
+
+    /// Streams the backend one key-value at a time.
+    fn snapshot(
+        &self,
+        _: RequestOptions,
+        _: SnapshotRequest,
+    ) -> StreamingResponse<SnapshotResponse> {
+        self.faulted_stream(Method::Snapshot, |etcd| {
+            let backend = etcd.backend();
+            let header = etcd.store().header();
+            let mut remaining_bytes: usize = backend.iter().map(Vec::len).sum();
+            let responses: Vec<_> = backend
+                .into_iter()
+                .map(|blob| {
+                    remaining_bytes -= blob.len();
+                    let mut response = SnapshotResponse::new();
+                    response.set_header(header.clone());
+                    response.remaining_bytes = remaining_bytes as u64;
+                    response.blob = blob;
+                    response
+                })
+                .collect();
+            StreamingResponse::iter(responses.into_iter())
+        })
+    }
+
+    fn move_leader(
+        &self,
+        _: RequestOptions,
+        _: MoveLeaderRequest,
+    ) -> SingleResponse<MoveLeaderResponse> {
+        unimplemented("MoveLeader")
+    }
+}
+
+impl Auth for FakeEtcd {
+    fn auth_enable(
+        &self,
+        _: RequestOptions,
+        _: AuthEnableRequest,
+    ) -> SingleResponse<AuthEnableResponse> {
+        unimplemented("AuthEnable")
+    }
+
+    fn auth_disable(
+        &self,
+        _: RequestOptions,
+        _: AuthDisableRequest,
+    ) -> SingleResponse<AuthDisableResponse> {
+        unimplemented("AuthDisable")
+    }
+
+    fn authenticate(
+        &self,
+        _: RequestOptions,
+        _: AuthenticateRequest,
+    ) -> SingleResponse<AuthenticateResponse> {
+        unimplemented("Authenticate")
+    }
+
+    fn user_add(
+        &self,
+        _: RequestOptions,
+        request: AuthUserAddRequest,
+    ) -> SingleResponse<AuthUserAddResponse> {
+        self.clustered(Method::UserAdd, move |cluster, header| {
+            cluster.user_add(&request, header)
+        })
+    }
+
+    fn user_get(
+        &self,
+        _: RequestOptions,
+        request: AuthUserGetRequest,
+    ) -> SingleResponse<AuthUserGetResponse> {
+        self.clustered(Method::UserGet, move |cluster, header| {
+            cluster.user_get(&request.name, header)
+        })
+    }
+
+    fn user_list(
+        &self,
+        _: RequestOptions,
+        _: AuthUserListRequest,
+    ) -> SingleResponse<AuthUserListResponse> {
+        self.clustered(Method::UserList, |cluster, header| {
+            cluster.user_list(header)
+        })
+    }
+
+    fn user_delete(
+        &self,
+        _: RequestOptions,
+        request: AuthUserDeleteRequest,
+    ) -> SingleResponse<AuthUserDeleteResponse> {
+        self.clustered(Method::UserDelete, move |cluster, header| {
+            cluster.user_delete(&request.name, header)
+        })
+    }
+
+    fn user_change_password(
+        &self,
+        _: RequestOptions,
+        request: AuthUserChangePasswordRequest,
+    ) -> SingleResponse<AuthUserChangePasswordResponse> {
+        self.clustered(Method::UserChangePassword, move |cluster, header| {
+            cluster.user_change_password(&request.name, header)
+        })
+    }
+
+    fn user_grant_role(
+        &self,
+        _: RequestOptions,
+        request: AuthUserGrantRoleRequest,
+    ) -> SingleResponse<AuthUserGrantRoleResponse> {
+        self.clustered(Method::UserGrantRole, move |cluster, header| {
+            cluster.user_grant_role(&request, header)
+        })
+    }
+
+    fn user_revoke_role(
+        &self,
+        _: RequestOptions,
+        request: AuthUserRevokeRoleRequest,
+    ) -> SingleResponse<AuthUserRevokeRoleResponse> {
+        self.clustered(Method::UserRevokeRole, move |cluster, header| {
+            cluster.user_revoke_role(&request, header)
+        })
+    }
+
+    fn role_add(
+        &self,
+        _: RequestOptions,
+        request: AuthRoleAddRequest,
+    ) -> SingleResponse<AuthRoleAddResponse> {
+        self.clustered(Method::RoleAdd, move |cluster, header| {
+            cluster.role_add(&request.name, header)
+        })
+    }
+
+    fn role_get(
+        &self,
+        _: RequestOptions,
+        request: AuthRoleGetRequest,
+    ) -> SingleResponse<AuthRoleGetResponse> {
+        self.clustered(Method::RoleGet, move |cluster, header| {
+            cluster.role_get(&request.role, header)
+        })
+    }
+
+    fn role_list(
+        &self,
+        _: RequestOptions,
+        _: AuthRoleListRequest,
+    ) -> SingleResponse<AuthRoleListResponse> {
+        self.clustered(Method::RoleList, |cluster, header| {
+            cluster.role_list(header)
+        })
+    }
+
+    fn role_delete(
+        &self,
+        _: RequestOptions,
+        request: AuthRoleDeleteRequest,
+    ) -> SingleResponse<AuthRoleDeleteResponse> {
+        self.clustered(Method::RoleDelete, move |cluster, header| {
+            cluster.role_delete(&request.role, header)
+        })
+    }
+
+    fn role_grant_permission(
+        &self,
+        _: RequestOptions,
+        request: AuthRoleGrantPermissionRequest,
+    ) -> SingleResponse<AuthRoleGrantPermissionResponse> {
+        self.clustered(Method::RoleGrantPermission, move |cluster, header| {
+            cluster.role_grant_permission(&request, header)
+        })
+    }
+
+    fn role_revoke_permission(
+        &self,
+        _: RequestOptions,
+        request: AuthRoleRevokePermissionRequest,
+    ) -> SingleResponse<AuthRoleRevokePermissionResponse> {
+        self.clustered(Method::RoleRevokePermission, move |cluster, header| {
+            cluster.role_revoke_permission(&request, header)
+        })
+    }
+}
+
+impl Lock for FakeEtcd {
+    fn lock(&self, _: RequestOptions, request: LockRequest) -> SingleResponse<LockResponse> {
+        self.faulted(Method::Lock, move |etcd| {
+            let (sender, receiver) = oneshot::channel();
+            etcd.store().lock(&request, sender);
+            SingleResponse::no_metadata(
+                receiver
+                    .map_err(grpc::Error::from)
+                    .and_then(|response| response),
+            )
+        })
+    }
+
+    fn unlock(&self, _: RequestOptions, request: UnlockRequest) -> SingleResponse<UnlockResponse> {
+        self.faulted(Method::Unlock, move |etcd| {
+            let mut delete_range_request = DeleteRangeRequest::new();
+            delete_range_request.key = request.key;
+            single(
+                etcd.store()
+                    .delete_range(&delete_range_request)
+                    .map(|response| {
+                        let mut unlock_response = UnlockResponse::new();
+                        unlock_response.header = response.header;
+                        unlock_response
+                    }),
+            )
+        })
+    }
+}
+
+impl Election for FakeEtcd {
+    fn campaign(
+        &self,
+        _: RequestOptions,
+        request: CampaignRequest,
+    ) -> SingleResponse<CampaignResponse> {
+        self.faulted(Method::Campaign, move |etcd| {
+            let (sender, receiver) = oneshot::channel();
+            etcd.store().campaign(&request, sender);
+            SingleResponse::no_metadata(
+                receiver
+                    .map_err(grpc::Error::from)
+                    .and_then(|response| response),
+            )
+        })
+    }
+
+    fn proclaim(
+        &self,
+        _: RequestOptions,
+        request: ProclaimRequest,
+    ) -> SingleResponse<ProclaimResponse> {
+        self.faulted(Method::Proclaim, move |etcd| {
+            single(etcd.store().proclaim(&request))
+        })
+    }
+
+    fn leader(&self, _: RequestOptions, request: LeaderRequest) -> SingleResponse<LeaderResponse> {
+        self.faulted(Method::Leader, move |etcd| {
+            single(etcd.store().leader(&request.name))
+        })
+    }
+
+    fn observe(
+        &self,
+        _: RequestOptions,
+        request: LeaderRequest,
+    ) -> StreamingResponse<LeaderResponse> {
+        self.faulted_stream(Method::Observe, move |etcd| {
+            let (sender, receiver) = unbounded();
+            etcd.store().observe(&request.name, sender);
+            StreamingResponse::no_metadata(
+                receiver
+                    .map_err(|()| grpc::Error::Other("observe response stream failed"))
+                    .and_then(|response| response),
+            )
+        })
+    }
+
+    fn resign(&self, _: RequestOptions, request: ResignRequest) -> SingleResponse<ResignResponse> {
+        self.faulted(Method::Resign, move |etcd| {
+            single(etcd.store().resign(request.get_leader()))
+        })
+    }
+}
+
+/// Serves a unary call's result. Errors follow the headers, as their status would otherwise be
+/// lost to a reset stream.
+fn single<T: Send + 'static>(result: Result<T, grpc::Error>) -> SingleResponse<T> {
+    match result {
+        Ok(response) => SingleResponse::completed(response),
+        Err(error) => SingleResponse::no_metadata(future::err(error)),
+    }
+}
+
+/// Fails a call the fake does not serve, as a server that does not know the method would.
+fn unimplemented<T: Send + 'static>(method: &str) -> SingleResponse<T> {
+    single(Err(status(
+        GrpcStatus::Unimplemented,
+        &format!("the fake etcd does not serve {}", method),
+    )))
+}
+
+/// Resolves once `delay` has passed, on a thread of its own.
+fn sleep(delay: Duration) -> impl Future<Item = (), Error = grpc::Error> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(delay);
+        let _ = sender.send(());
+    });
+    receiver.map_err(grpc::Error::from)
+}
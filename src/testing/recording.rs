@@ -0,0 +1,775 @@
+use super::faults::Method;
+use super::store::status;
+use super::{single, TestServer};
+use crate::errors::Error;
+use crate::rpc::{
+    AuthRoleAddRequest, AuthRoleAddResponse, AuthRoleDeleteRequest, AuthRoleDeleteResponse,
+    AuthRoleGetRequest, AuthRoleGetResponse, AuthRoleGrantPermissionRequest,
+    AuthRoleGrantPermissionResponse, AuthRoleListRequest, AuthRoleListResponse,
+    AuthRoleRevokePermissionRequest, AuthRoleRevokePermissionResponse, AuthUserAddRequest,
+    AuthUserAddResponse, AuthUserChangePasswordRequest, AuthUserChangePasswordResponse,
+    AuthUserDeleteRequest, AuthUserDeleteResponse, AuthUserGetRequest, AuthUserGetResponse,
+    AuthUserGrantRoleRequest, AuthUserGrantRoleResponse, AuthUserListRequest, AuthUserListResponse,
+    AuthUserRevokeRoleRequest, AuthUserRevokeRoleResponse, CompactionRequest, CompactionResponse,
+    DeleteRangeRequest, DeleteRangeResponse, LeaseGrantRequest, LeaseGrantResponse,
+    LeaseKeepAliveRequest, LeaseKeepAliveResponse, LeaseLeasesRequest, LeaseLeasesResponse,
+    LeaseRevokeRequest, LeaseRevokeResponse, LeaseTimeToLiveRequest, LeaseTimeToLiveResponse,
+    MemberAddRequest, MemberAddResponse, MemberListRequest, MemberListResponse,
+    MemberRemoveRequest, MemberRemoveResponse, PutRequest, PutResponse, RangeRequest,
+    RangeResponse, SnapshotRequest, SnapshotResponse, StatusRequest, StatusResponse, TxnRequest,
+    TxnResponse, WatchRequest, WatchResponse,
+};
+use crate::rpc_grpc::{KVServer, Lease, LeaseServer, Watch, WatchServer, KV};
+use crate::transport::{Client, Transport};
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderRequest, LeaderResponse, ProclaimRequest,
+    ProclaimResponse, ResignRequest, ResignResponse,
+};
+use crate::v3lock::{LockRequest, LockResponse, UnlockRequest, UnlockResponse};
+use crate::v3lock_grpc::{Lock, LockServer};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::task::Poll;
+use futures::{FutureExt, StreamExt};
+use futures01::future::{self, Loop};
+use futures01::{stream, Future, Stream};
+use grpc::{
+    GrpcMessageError, GrpcStatus, GrpcStream, RequestOptions, SingleResponse, StreamingRequest,
+    StreamingResponse,
+};
+use protobuf::{CodedInputStream, CodedOutputStream, Message, ProtobufError, ProtobufResult};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A client that records every request and response of its `KV`, `Watch`, `Lease` and `Lock`
+/// calls to a file, for `Replay` to serve back later. Calls to the other services pass through
+/// unrecorded.
+///
+/// Each message is written as soon as it is seen, so a recording survives the process being
+/// killed.
+///
+/// ```no_run
+/// # fn example(client: etcd::Client) -> Result<(), etcd::Error> {
+/// let recorder = etcd::testing::Recorder::new(client, "session.recording")?;
+/// let kv = etcd::Kv::new(recorder);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Recorder {
+    client: Client,
+    log: Arc<Mutex<Log>>,
+}
+
+/// A server that answers calls with the responses captured by a `Recorder`.
+///
+/// Unary calls are answered by the first unreplayed recording of the same method with an
+/// identical request. Streaming calls replay the next recorded stream of the same method, sending
+/// each response once the requests recorded before it have arrived.
+#[derive(Clone)]
+pub struct Replay {
+    calls: Arc<Mutex<Vec<Option<Call>>>>,
+}
+
+struct Log {
+    file: File,
+    calls: u64,
+}
+
+struct Call {
+    method: Method,
+    entries: VecDeque<Entry>,
+}
+
+enum Entry {
+    Request(Vec<u8>),
+    Response(Vec<u8>),
+    Error(i32, String),
+    /// The server ended the response stream.
+    End,
+}
+
+impl Recorder {
+    /// Records the calls made through `client` into a new file at `path`.
+    pub fn new<C: Into<Client>, P: AsRef<Path>>(client: C, path: P) -> Result<Recorder, Error> {
+        let file = File::create(path)?;
+        Ok(Recorder {
+            client: client.into(),
+            log: Arc::new(Mutex::new(Log { file, calls: 0 })),
+        })
+    }
+
+    fn start(&self) -> u64 {
+        let mut log = self
+            .log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        log.calls += 1;
+        log.calls
+    }
+
+    fn record(&self, call: u64, method: Method, entry: Entry) {
+        let mut log = self
+            .log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(error) = log.write(call, method, &entry) {
+            error!("Could not record {}: {}", method.path(), error);
+        }
+    }
+
+    fn unary<Req, Resp>(
+        &self,
+        method: Method,
+        request: &Req,
+        response: BoxFuture<'static, Result<Resp, Error>>,
+    ) -> BoxFuture<'static, Result<Resp, Error>>
+    where
+        Req: Message,
+        Resp: Message,
+    {
+        let call = self.start();
+        self.record(call, method, Entry::Request(encode(request)));
+
+        let recorder = self.clone();
+        response
+            .inspect(move |result| recorder.record(call, method, Entry::from_result(result)))
+            .boxed()
+    }
+
+    fn streaming<Req, Resp, F>(
+        &self,
+        method: Method,
+        requests: BoxStream<'static, Req>,
+        forward: F,
+    ) -> BoxStream<'static, Result<Resp, Error>>
+    where
+        Req: Message,
+        Resp: Message,
+        F: FnOnce(BoxStream<'static, Req>) -> BoxStream<'static, Result<Resp, Error>>,
+    {
+        let call = self.start();
+
+        let recorder = self.clone();
+        let requests = requests
+            .inspect(move |request| recorder.record(call, method, Entry::Request(encode(request))))
+            .boxed();
+
+        let recorder = self.clone();
+        let end = self.clone();
+        forward(requests)
+            .inspect(move |result| recorder.record(call, method, Entry::from_result(result)))
+            .chain(futures::stream::poll_fn(move |_| {
+                end.record(call, method, Entry::End);
+                Poll::Ready(None)
+            }))
+            .boxed()
+    }
+}
+
+impl From<Recorder> for Client {
+    fn from(recorder: Recorder) -> Client {
+        Client::new(recorder)
+    }
+}
+
+impl Log {
+    fn write(&mut self, call: u64, method: Method, entry: &Entry) -> ProtobufResult<()> {
+        let mut output = CodedOutputStream::new(&mut self.file);
+        output.write_raw_varint64(call)?;
+        write_bytes(&mut output, method.path().as_bytes())?;
+        match entry {
+            Entry::Request(request) => {
+                output.write_raw_varint64(0)?;
+                write_bytes(&mut output, request)?;
+            }
+            Entry::Response(response) => {
+                output.write_raw_varint64(1)?;
+                write_bytes(&mut output, response)?;
+            }
+            Entry::Error(grpc_status, grpc_message) => {
+                output.write_raw_varint64(2)?;
+                output.write_raw_varint64(*grpc_status as u64)?;
+                write_bytes(&mut output, grpc_message.as_bytes())?;
+            }
+            Entry::End => output.write_raw_varint64(3)?,
+        }
+        output.flush()
+    }
+}
+
+impl Entry {
+    fn from_result<T: Message>(result: &Result<T, Error>) -> Entry {
+        match result {
+            Ok(response) => Entry::Response(encode(response)),
+            Err(Error::Grpc(grpc::Error::GrpcMessage(error))) => {
+                Entry::Error(error.grpc_status, error.grpc_message.clone())
+            }
+            Err(error) => Entry::Error(GrpcStatus::Internal as i32, error.to_string()),
+        }
+    }
+}
+
+impl Replay {
+    /// Loads the recording at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Replay, Error> {
+        let calls = read_calls(path.as_ref()).map_err(grpc::Error::from)?;
+        Ok(Replay {
+            calls: Arc::new(Mutex::new(calls.into_iter().map(Some).collect())),
+        })
+    }
+
+    /// Serves the recording on a random local port.
+    pub fn serve(&self) -> Result<TestServer, Error> {
+        TestServer::new(vec![
+            KVServer::new_service_def(self.clone()),
+            WatchServer::new_service_def(self.clone()),
+            LeaseServer::new_service_def(self.clone()),
+            LockServer::new_service_def(self.clone()),
+        ])
+    }
+
+    /// The number of recorded calls that have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        let calls = self
+            .calls
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        calls.iter().filter(|call| call.is_some()).count()
+    }
+
+    /// Takes the first unreplayed call to `method`, made with `request` if one is given.
+    fn take(&self, method: Method, request: Option<&[u8]>) -> Option<Call> {
+        let mut calls = self
+            .calls
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        calls
+            .iter_mut()
+            .find(|call| match call {
+                Some(call) if call.method == method => match request {
+                    Some(request) => match call.entries.front() {
+                        Some(Entry::Request(recorded)) => recorded.as_slice() == request,
+                        _ => false,
+                    },
+                    None => true,
+                },
+                _ => false,
+            })
+            .and_then(|call| call.take())
+    }
+
+    fn unary<Req: Message, Resp: Message>(
+        &self,
+        method: Method,
+        request: Req,
+    ) -> SingleResponse<Resp> {
+        let call = match self.take(method, Some(&encode(&request))) {
+            Some(call) => call,
+            None => return single(Err(unrecorded(method))),
+        };
+
+        for entry in call.entries {
+            match entry {
+                Entry::Response(response) => {
+                    return single(protobuf::parse_from_bytes(&response).map_err(grpc::Error::from))
+                }
+                Entry::Error(grpc_status, grpc_message) => {
+                    return single(Err(grpc::Error::GrpcMessage(GrpcMessageError {
+                        grpc_status,
+                        grpc_message,
+                    })))
+                }
+                Entry::Request(_) | Entry::End => {}
+            }
+        }
+
+        // The recording stopped before the call was answered.
+        SingleResponse::no_metadata(future::empty())
+    }
+
+    fn streaming<Req: Message, Resp: Message>(
+        &self,
+        method: Method,
+        requests: StreamingRequest<Req>,
+    ) -> StreamingResponse<Resp> {
+        let call = match self.take(method, None) {
+            Some(call) => call,
+            None => return StreamingResponse::no_metadata(stream::once(Err(unrecorded(method)))),
+        };
+
+        let responses = stream::unfold(Some((requests.0, call.entries)), |state| {
+            let state = state?;
+            Some(
+                future::loop_fn(state, replay_entry).map(|response| match response {
+                    Some((response, state)) => (Some(response), Some(state)),
+                    None => (None, None),
+                }),
+            )
+        })
+        .filter_map(|response| response);
+
+        StreamingResponse::no_metadata(responses)
+    }
+}
+
+type ReplayState<Req> = (GrpcStream<Req>, VecDeque<Entry>);
+
+/// Replays entries up to the next response, waiting for each recorded request to arrive first.
+///
+/// Resolves to `None` once the recorded stream ended or the client stopped sending requests.
+#[allow(clippy::type_complexity)]
+fn replay_entry<Req: Send + 'static, Resp: Message>(
+    (requests, mut entries): ReplayState<Req>,
+) -> Box<
+    dyn Future<Item = Loop<Option<(Resp, ReplayState<Req>)>, ReplayState<Req>>, Error = grpc::Error>
+        + Send,
+> {
+    match entries.pop_front() {
+        Some(Entry::Request(_)) => {
+            Box::new(requests.into_future().map_err(|(error, _)| error).map(
+                move |(request, requests)| match request {
+                    Some(_) => Loop::Continue((requests, entries)),
+                    None => Loop::Break(None),
+                },
+            ))
+        }
+        Some(Entry::Response(response)) => Box::new(future::result(
+            protobuf::parse_from_bytes(&response)
+                .map(|response| Loop::Break(Some((response, (requests, entries)))))
+                .map_err(grpc::Error::from),
+        )),
+        Some(Entry::Error(grpc_status, grpc_message)) => {
+            Box::new(future::err(grpc::Error::GrpcMessage(GrpcMessageError {
+                grpc_status,
+                grpc_message,
+            })))
+        }
+        Some(Entry::End) => Box::new(future::ok(Loop::Break(None))),
+        // The recording stopped while the stream was still open.
+        None => Box::new(future::empty()),
+    }
+}
+
+fn read_calls(path: &Path) -> ProtobufResult<Vec<Call>> {
+    let mut file = File::open(path)?;
+    let mut input = CodedInputStream::new(&mut file);
+    let mut calls: BTreeMap<u64, Call> = BTreeMap::new();
+
+    while !input.eof()? {
+        let call = input.read_raw_varint64()?;
+        let path = read_bytes(&mut input)?;
+        let method = *Method::ALL
+            .iter()
+            .find(|method| method.path().as_bytes() == path.as_slice())
+            .ok_or_else(|| invalid_recording("unknown method"))?;
+        let entry = match input.read_raw_varint64()? {
+            0 => Entry::Request(read_bytes(&mut input)?),
+            1 => Entry::Response(read_bytes(&mut input)?),
+            2 => {
+                let grpc_status = input.read_raw_varint64()? as i32;
+                let grpc_message = String::from_utf8_lossy(&read_bytes(&mut input)?).into_owned();
+                Entry::Error(grpc_status, grpc_message)
+            }
+            3 => Entry::End,
+            _ => return Err(invalid_recording("unknown entry")),
+        };
+
+        calls
+            .entry(call)
+            .or_insert_with(|| Call {
+                method,
+                entries: VecDeque::new(),
+            })
+            .entries
+            .push_back(entry);
+    }
+
+    Ok(calls.into_values().collect())
+}
+
+fn write_bytes(output: &mut CodedOutputStream, bytes: &[u8]) -> ProtobufResult<()> {
+    output.write_raw_varint64(bytes.len() as u64)?;
+    output.write_raw_bytes(bytes)
+}
+
+fn read_bytes(input: &mut CodedInputStream) -> ProtobufResult<Vec<u8>> {
+    let length = input.read_raw_varint64()?;
+    input.read_raw_bytes(length as u32)
+}
+
+fn encode<M: Message>(message: &M) -> Vec<u8> {
+    message.write_to_bytes().unwrap_or_default()
+}
+
+fn invalid_recording(message: &str) -> ProtobufError {
+    ProtobufError::IoError(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid recording: {}", message),
+    ))
+}
+
+fn unrecorded(method: Method) -> grpc::Error {
+    status(
+        GrpcStatus::NotFound,
+        &format!("no recorded call to {} matches the request", method.path()),
+    )
+}
+
+impl Transport for Recorder {
+    fn range(&self, request: RangeRequest) -> BoxFuture<'static, Result<RangeResponse, Error>> {
+        self.unary(Method::Range, &request, self.client.range(request.clone()))
+    }
+
+    fn put(&self, request: PutRequest) -> BoxFuture<'static, Result<PutResponse, Error>> {
+        self.unary(Method::Put, &request, self.client.put(request.clone()))
+    }
+
+    fn delete_range(
+        &self,
+        request: DeleteRangeRequest,
+    ) -> BoxFuture<'static, Result<DeleteRangeResponse, Error>> {
+        self.unary(
+            Method::DeleteRange,
+            &request,
+            self.client.delete_range(request.clone()),
+        )
+    }
+
+    fn txn(&self, request: TxnRequest) -> BoxFuture<'static, Result<TxnResponse, Error>> {
+        self.unary(Method::Txn, &request, self.client.txn(request.clone()))
+    }
+
+    fn compact(
+        &self,
+        request: CompactionRequest,
+    ) -> BoxFuture<'static, Result<CompactionResponse, Error>> {
+        self.unary(
+            Method::Compact,
+            &request,
+            self.client.compact(request.clone()),
+        )
+    }
+
+    fn watch(
+        &self,
+        requests: BoxStream<'static, WatchRequest>,
+    ) -> BoxStream<'static, Result<WatchResponse, Error>> {
+        self.streaming(Method::Watch, requests, |requests| {
+            self.client.watch(requests)
+        })
+    }
+
+    fn lease_grant(
+        &self,
+        request: LeaseGrantRequest,
+    ) -> BoxFuture<'static, Result<LeaseGrantResponse, Error>> {
+        self.unary(
+            Method::LeaseGrant,
+            &request,
+            self.client.lease_grant(request.clone()),
+        )
+    }
+
+    fn lease_revoke(
+        &self,
+        request: LeaseRevokeRequest,
+    ) -> BoxFuture<'static, Result<LeaseRevokeResponse, Error>> {
+        self.unary(
+            Method::LeaseRevoke,
+            &request,
+            self.client.lease_revoke(request.clone()),
+        )
+    }
+
+    fn lease_keep_alive(
+        &self,
+        requests: BoxStream<'static, LeaseKeepAliveRequest>,
+    ) -> BoxStream<'static, Result<LeaseKeepAliveResponse, Error>> {
+        self.streaming(Method::LeaseKeepAlive, requests, |requests| {
+            self.client.lease_keep_alive(requests)
+        })
+    }
+
+    fn lease_time_to_live(
+        &self,
+        request: LeaseTimeToLiveRequest,
+    ) -> BoxFuture<'static, Result<LeaseTimeToLiveResponse, Error>> {
+        self.unary(
+            Method::LeaseTimeToLive,
+            &request,
+            self.client.lease_time_to_live(request.clone()),
+        )
+    }
+
+    fn lease_leases(
+        &self,
+        request: LeaseLeasesRequest,
+    ) -> BoxFuture<'static, Result<LeaseLeasesResponse, Error>> {
+        self.unary(
+            Method::LeaseLeases,
+            &request,
+            self.client.lease_leases(request.clone()),
+        )
+    }
+
+    fn member_add(
+        &self,
+        request: MemberAddRequest,
+    ) -> BoxFuture<'static, Result<MemberAddResponse, Error>> {
+        self.client.member_add(request)
+    }
+
+    fn member_remove(
+        &self,
+        request: MemberRemoveRequest,
+    ) -> BoxFuture<'static, Result<MemberRemoveResponse, Error>> {
+        self.client.member_remove(request)
+    }
+
+    fn member_list(
+        &self,
+        request: MemberListRequest,
+    ) -> BoxFuture<'static, Result<MemberListResponse, Error>> {
+        self.client.member_list(request)
+    }
+
+    fn status(&self, request: StatusRequest) -> BoxFuture<'static, Result<StatusResponse, Error>> {
+        self.client.status(request)
+    }
+
+    fn snapshot(
+        &self,
+        request: SnapshotRequest,
+    ) -> BoxStream<'static, Result<SnapshotResponse, Error>> {
+        self.client.snapshot(request)
+    }
+
+    fn user_add(
+        &self,
+        request: AuthUserAddRequest,
+    ) -> BoxFuture<'static, Result<AuthUserAddResponse, Error>> {
+        self.client.user_add(request)
+    }
+
+    fn user_get(
+        &self,
+        request: AuthUserGetRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGetResponse, Error>> {
+        self.client.user_get(request)
+    }
+
+    fn user_list(
+        &self,
+        request: AuthUserListRequest,
+    ) -> BoxFuture<'static, Result<AuthUserListResponse, Error>> {
+        self.client.user_list(request)
+    }
+
+    fn user_delete(
+        &self,
+        request: AuthUserDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthUserDeleteResponse, Error>> {
+        self.client.user_delete(request)
+    }
+
+    fn user_change_password(
+        &self,
+        request: AuthUserChangePasswordRequest,
+    ) -> BoxFuture<'static, Result<AuthUserChangePasswordResponse, Error>> {
+        self.client.user_change_password(request)
+    }
+
+    fn user_grant_role(
+        &self,
+        request: AuthUserGrantRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGrantRoleResponse, Error>> {
+        self.client.user_grant_role(request)
+    }
+
+    fn user_revoke_role(
+        &self,
+        request: AuthUserRevokeRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserRevokeRoleResponse, Error>> {
+        self.client.user_revoke_role(request)
+    }
+
+    fn role_add(
+        &self,
+        request: AuthRoleAddRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleAddResponse, Error>> {
+        self.client.role_add(request)
+    }
+
+    fn role_get(
+        &self,
+        request: AuthRoleGetRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGetResponse, Error>> {
+        self.client.role_get(request)
+    }
+
+    fn role_list(
+        &self,
+        request: AuthRoleListRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleListResponse, Error>> {
+        self.client.role_list(request)
+    }
+
+    fn role_delete(
+        &self,
+        request: AuthRoleDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleDeleteResponse, Error>> {
+        self.client.role_delete(request)
+    }
+
+    fn role_grant_permission(
+        &self,
+        request: AuthRoleGrantPermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGrantPermissionResponse, Error>> {
+        self.client.role_grant_permission(request)
+    }
+
+    fn role_revoke_permission(
+        &self,
+        request: AuthRoleRevokePermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleRevokePermissionResponse, Error>> {
+        self.client.role_revoke_permission(request)
+    }
+
+    fn lock(&self, request: LockRequest) -> BoxFuture<'static, Result<LockResponse, Error>> {
+        self.unary(Method::Lock, &request, self.client.lock(request.clone()))
+    }
+
+    fn unlock(&self, request: UnlockRequest) -> BoxFuture<'static, Result<UnlockResponse, Error>> {
+        self.unary(
+            Method::Unlock,
+            &request,
+            self.client.unlock(request.clone()),
+        )
+    }
+
+    fn campaign(
+        &self,
+        request: CampaignRequest,
+    ) -> BoxFuture<'static, Result<CampaignResponse, Error>> {
+        self.client.campaign(request)
+    }
+
+    fn proclaim(
+        &self,
+        request: ProclaimRequest,
+    ) -> BoxFuture<'static, Result<ProclaimResponse, Error>> {
+        self.client.proclaim(request)
+    }
+
+    fn leader(&self, request: LeaderRequest) -> BoxFuture<'static, Result<LeaderResponse, Error>> {
+        self.client.leader(request)
+    }
+
+    fn observe(&self, request: LeaderRequest) -> BoxStream<'static, Result<LeaderResponse, Error>> {
+        self.client.observe(request)
+    }
+
+    fn resign(&self, request: ResignRequest) -> BoxFuture<'static, Result<ResignResponse, Error>> {
+        self.client.resign(request)
+    }
+
+    fn detach(&self, task: BoxFuture<'static, ()>) {
+        self.client.detach(task)
+    }
+}
+
+impl KV for Replay {
+    fn range(&self, _: RequestOptions, request: RangeRequest) -> SingleResponse<RangeResponse> {
+        self.unary(Method::Range, request)
+    }
+
+    fn put(&self, _: RequestOptions, request: PutRequest) -> SingleResponse<PutResponse> {
+        self.unary(Method::Put, request)
+    }
+
+    fn delete_range(
+        &self,
+        _: RequestOptions,
+        request: DeleteRangeRequest,
+    ) -> SingleResponse<DeleteRangeResponse> {
+        self.unary(Method::DeleteRange, request)
+    }
+
+    fn txn(&self, _: RequestOptions, request: TxnRequest) -> SingleResponse<TxnResponse> {
+        self.unary(Method::Txn, request)
+    }
+
+    fn compact(
+        &self,
+        _: RequestOptions,
+        request: CompactionRequest,
+    ) -> SingleResponse<CompactionResponse> {
+        self.unary(Method::Compact, request)
+    }
+}
+
+impl Watch for Replay {
+    fn watch(
+        &self,
+        _: RequestOptions,
+        requests: StreamingRequest<WatchRequest>,
+    ) -> StreamingResponse<WatchResponse> {
+        self.streaming(Method::Watch, requests)
+    }
+}
+
+impl Lease for Replay {
+    fn lease_grant(
+        &self,
+        _: RequestOptions,
+        request: LeaseGrantRequest,
+    ) -> SingleResponse<LeaseGrantResponse> {
+        self.unary(Method::LeaseGrant, request)
+    }
+
+    fn lease_revoke(
+        &self,
+        _: RequestOptions,
+        request: LeaseRevokeRequest,
+    ) -> SingleResponse<LeaseRevokeResponse> {
+        self.unary(Method::LeaseRevoke, request)
+    }
+
+    fn lease_keep_alive(
+        &self,
+        _: RequestOptions,
+        requests: StreamingRequest<LeaseKeepAliveRequest>,
+    ) -> StreamingResponse<LeaseKeepAliveResponse> {
+        self.streaming(Method::LeaseKeepAlive, requests)
+    }
+
+    fn lease_time_to_live(
+        &self,
+        _: RequestOptions,
+        request: LeaseTimeToLiveRequest,
+    ) -> SingleResponse<LeaseTimeToLiveResponse> {
+        self.unary(Method::LeaseTimeToLive, request)
+    }
+
+    fn lease_leases(
+        &self,
+        _: RequestOptions,
+        request: LeaseLeasesRequest,
+    ) -> SingleResponse<LeaseLeasesResponse> {
+        self.unary(Method::LeaseLeases, request)
+    }
+}
+
+impl Lock for Replay {
+    fn lock(&self, _: RequestOptions, request: LockRequest) -> SingleResponse<LockResponse> {
+        self.unary(Method::Lock, request)
+    }
+
+    fn unlock(&self, _: RequestOptions, request: UnlockRequest) -> SingleResponse<UnlockResponse> {
+        self.unary(Method::Unlock, request)
+    }
+}
@@ -0,0 +1,1140 @@
+use super::cluster::{CLUSTER_ID, MEMBER_ID};
+use crate::kv::{Event, Event_EventType, KeyValue};
+use crate::kv_client::prefix_range_end;
+use crate::rpc::{
+    Compare, Compare_CompareResult, Compare_CompareTarget, Compare_oneof_target_union,
+    DeleteRangeRequest, DeleteRangeResponse, LeaseGrantRequest, LeaseGrantResponse,
+    LeaseKeepAliveResponse, LeaseLeasesResponse, LeaseRevokeResponse, LeaseStatus,
+    LeaseTimeToLiveRequest, LeaseTimeToLiveResponse, PutRequest, PutResponse, RangeRequest,
+    RangeRequest_SortOrder, RangeRequest_SortTarget, RangeResponse, RequestOp,
+    RequestOp_oneof_request, ResponseHeader, ResponseOp, ResponseOp_oneof_response, TxnRequest,
+    TxnResponse, WatchCreateRequest, WatchCreateRequest_FilterType, WatchRequest,
+    WatchRequest_oneof_request_union, WatchResponse,
+};
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderKey, LeaderResponse, ProclaimRequest,
+    ProclaimResponse, ResignResponse,
+};
+use crate::v3lock::{LockRequest, LockResponse};
+use futures01::sync::{mpsc::UnboundedSender, oneshot};
+use grpc::{GrpcMessageError, GrpcStatus};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
+use std::time::Duration;
+
+/// The most compares or ops etcd accepts in each part of a transaction, its `--max-txn-ops`.
+const MAX_TXN_OPS: usize = 128;
+
+/// The in-memory state of a fake etcd server.
+pub(crate) struct Store {
+    data: Data,
+    now: Duration,
+    next_lease_id: i64,
+    next_watch_id: i64,
+    watchers: Vec<Watcher>,
+    /// Watch streams that asked for progress while one of their watchers was catching up.
+    deferred_progress: BTreeSet<u64>,
+    waiters: Vec<Waiter>,
+    observers: Vec<Observer>,
+}
+
+/// Everything a write can change, cloned so a failed transaction leaves no trace.
+#[derive(Clone)]
+struct Data {
+    revision: i64,
+    compact_revision: i64,
+    /// Every revision of every key, oldest first. Deletions are kept as tombstones with a version
+    /// of 0.
+    keys: BTreeMap<Vec<u8>, Vec<KeyValue>>,
+    /// Every event since the last compaction, with its previous key-value.
+    events: Vec<Event>,
+    leases: BTreeMap<i64, LeaseEntry>,
+}
+
+#[derive(Clone)]
+struct LeaseEntry {
+    ttl: i64,
+    deadline: Duration,
+    keys: BTreeSet<Vec<u8>>,
+}
+
+struct Watcher {
+    id: i64,
+    stream: u64,
+    request: WatchCreateRequest,
+    sender: UnboundedSender<Result<WatchResponse>>,
+    /// Whether the watcher replayed history and has not sent a new event since. etcd syncs such
+    /// watchers in the background and only answers progress requests once it has.
+    catching_up: bool,
+}
+
+/// A lock holder or election candidate waiting for every older key under its prefix to go.
+struct Waiter {
+    key: Vec<u8>,
+    create_revision: i64,
+    respond: Respond,
+}
+
+/// Answers a waiter with the header and its key's create revision once it holds the key, or with
+/// why it never will.
+type Respond = Box<dyn FnOnce(Result<(ResponseHeader, i64)>) + Send>;
+
+/// An `Observe` stream, sent an election's leader whenever it changes.
+struct Observer {
+    prefix: Vec<u8>,
+    sender: UnboundedSender<Result<LeaderResponse>>,
+    /// The key and mod revision of the leader last sent.
+    last: Option<(Vec<u8>, i64)>,
+}
+
+pub(crate) type Result<T> = std::result::Result<T, grpc::Error>;
+
+impl Store {
+    pub(crate) fn new() -> Store {
+        Store {
+            data: Data {
+                revision: 1,
+                compact_revision: 0,
+                keys: BTreeMap::new(),
+                events: Vec::new(),
+                leases: BTreeMap::new(),
+            },
+            now: Duration::from_secs(0),
+            next_lease_id: 1,
+            next_watch_id: 0,
+            watchers: Vec::new(),
+            deferred_progress: BTreeSet::new(),
+            waiters: Vec::new(),
+            observers: Vec::new(),
+        }
+    }
+
+    pub(crate) fn revision(&self) -> i64 {
+        self.data.revision
+    }
+
+    pub(crate) fn header(&self) -> ResponseHeader {
+        let mut header = ResponseHeader::new();
+        header.cluster_id = CLUSTER_ID;
+        header.member_id = MEMBER_ID;
+        header.revision = self.data.revision;
+        header.raft_term = 1;
+        header
+    }
+
+    pub(crate) fn range(&self, request: &RangeRequest) -> Result<RangeResponse> {
+        let mut response = self.data.range(request)?;
+        response.set_header(self.header());
+        Ok(response)
+    }
+
+    pub(crate) fn put(&mut self, request: &PutRequest) -> Result<PutResponse> {
+        let mut response = self.write(|data, revision| data.put(revision, request))?;
+        response.set_header(self.header());
+        Ok(response)
+    }
+
+    pub(crate) fn delete_range(
+        &mut self,
+        request: &DeleteRangeRequest,
+    ) -> Result<DeleteRangeResponse> {
+        let mut response = self.write(|data, revision| data.delete_range(revision, request))?;
+        response.set_header(self.header());
+        Ok(response)
+    }
+
+    pub(crate) fn txn(&mut self, request: &TxnRequest) -> Result<TxnResponse> {
+        let mut response = self.write(|data, revision| data.txn(revision, request))?;
+        let header = self.header();
+        set_txn_headers(&mut response, &header);
+        Ok(response)
+    }
+
+    pub(crate) fn compact(&mut self, revision: i64) -> Result<()> {
+        if revision <= self.data.compact_revision {
+            return Err(compacted());
+        }
+        if revision > self.data.revision {
+            return Err(future_revision());
+        }
+
+        let data = &mut self.data;
+        for history in data.keys.values_mut() {
+            let kept = history
+                .iter()
+                .rposition(|kv| kv.mod_revision <= revision)
+                .unwrap_or(0);
+            history.drain(..kept);
+            if history[0].mod_revision <= revision && history[0].version == 0 {
+                history.remove(0);
+            }
+        }
+        data.keys.retain(|_, history| !history.is_empty());
+        data.events
+            .retain(|event| event.get_kv().mod_revision >= revision);
+        data.compact_revision = revision;
+
+        Ok(())
+    }
+
+    pub(crate) fn lease_grant(
+        &mut self,
+        request: &LeaseGrantRequest,
+    ) -> Result<LeaseGrantResponse> {
+        let id = if request.ID == 0 {
+            while self.data.leases.contains_key(&self.next_lease_id) {
+                self.next_lease_id += 1;
+            }
+            self.next_lease_id
+        } else if self.data.leases.contains_key(&request.ID) {
+            return Err(status(
+                GrpcStatus::FailedPrecondition,
+                "etcdserver: lease already exists",
+            ));
+        } else {
+            request.ID
+        };
+
+        self.data.leases.insert(
+            id,
+            LeaseEntry {
+                ttl: request.TTL,
+                deadline: self.now + Duration::from_secs(request.TTL.max(0) as u64),
+                keys: BTreeSet::new(),
+            },
+        );
+
+        let mut response = LeaseGrantResponse::new();
+        response.set_header(self.header());
+        response.ID = id;
+        response.TTL = request.TTL;
+        Ok(response)
+    }
+
+    pub(crate) fn lease_revoke(&mut self, id: i64) -> Result<LeaseRevokeResponse> {
+        if !self.data.leases.contains_key(&id) {
+            return Err(lease_not_found());
+        }
+        self.write(|data, revision| {
+            data.revoke(revision, id);
+            Ok(())
+        })?;
+
+        let mut response = LeaseRevokeResponse::new();
+        response.set_header(self.header());
+        Ok(response)
+    }
+
+    pub(crate) fn lease_keep_alive(&mut self, id: i64) -> LeaseKeepAliveResponse {
+        let mut response = LeaseKeepAliveResponse::new();
+        response.set_header(self.header());
+        response.ID = id;
+        if let Some(lease) = self.data.leases.get_mut(&id) {
+            lease.deadline = self.now + Duration::from_secs(lease.ttl.max(0) as u64);
+            response.TTL = lease.ttl;
+        }
+        response
+    }
+
+    pub(crate) fn lease_time_to_live(
+        &self,
+        request: &LeaseTimeToLiveRequest,
+    ) -> LeaseTimeToLiveResponse {
+        let mut response = LeaseTimeToLiveResponse::new();
+        response.set_header(self.header());
+        response.ID = request.ID;
+        match self.data.leases.get(&request.ID) {
+            Some(lease) => {
+                let remaining = lease.deadline - self.now;
+                response.TTL =
+                    remaining.as_secs() as i64 + if remaining.subsec_nanos() > 0 { 1 } else { 0 };
+                response.grantedTTL = lease.ttl;
+                if request.keys {
+                    response.keys = lease.keys.iter().cloned().collect();
+                }
+            }
+            None => response.TTL = -1,
+        }
+        response
+    }
+
+    pub(crate) fn lease_leases(&self) -> LeaseLeasesResponse {
+        let mut response = LeaseLeasesResponse::new();
+        response.set_header(self.header());
+        response.leases = self
+            .data
+            .leases
+            .keys()
+            .map(|id| {
+                let mut status = LeaseStatus::new();
+                status.ID = *id;
+                status
+            })
+            .collect();
+        response
+    }
+
+    /// Moves the clock forward, revoking every lease whose deadline has passed.
+    pub(crate) fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+
+        let now = self.now;
+        let expired: Vec<i64> = self
+            .data
+            .leases
+            .iter()
+            .filter(|(_, lease)| lease.deadline <= now)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in expired {
+            trace!("Lease {:x} expired", id);
+            self.expire(id);
+        }
+    }
+
+    /// Revokes the lease `id` as if it had expired.
+    pub(crate) fn expire(&mut self, id: i64) {
+        let _ = self.write(|data, revision| {
+            data.revoke(revision, id);
+            Ok(())
+        });
+    }
+
+    /// Ends every open watch stream with an `Unavailable` status.
+    pub(crate) fn break_watches(&mut self) {
+        let mut broken = BTreeSet::new();
+        for watcher in self.watchers.drain(..) {
+            if broken.insert(watcher.stream) {
+                let _ = watcher
+                    .sender
+                    .unbounded_send(Err(status(GrpcStatus::Unavailable, "watch stream broken")));
+            }
+        }
+    }
+
+    /// Acquires the lock named in `request` on behalf of its lease, responding on `sender` once
+    /// every earlier holder has released it.
+    pub(crate) fn lock(
+        &mut self,
+        request: &LockRequest,
+        sender: oneshot::Sender<Result<LockResponse>>,
+    ) {
+        let lease = if request.lease == 0 {
+            // Like etcd, a lock without a lease gets a session lease with the default TTL.
+            let mut lease_grant_request = LeaseGrantRequest::new();
+            lease_grant_request.TTL = 60;
+            match self.lease_grant(&lease_grant_request) {
+                Ok(response) => response.ID,
+                Err(error) => {
+                    let _ = sender.send(Err(error));
+                    return;
+                }
+            }
+        } else {
+            request.lease
+        };
+
+        let key = [request.name.clone(), format!("/{:x}", lease).into_bytes()].concat();
+        let respond = {
+            let key = key.clone();
+            move |result: Result<(ResponseHeader, i64)>| {
+                let _ = sender.send(result.map(|(header, _)| {
+                    let mut response = LockResponse::new();
+                    response.set_header(header);
+                    response.key = key;
+                    response
+                }));
+            }
+        };
+        self.enqueue(key, lease, Vec::new(), Box::new(respond));
+    }
+
+    /// Campaigns in the election named in `request` on behalf of its lease, responding on
+    /// `sender` once every earlier candidate has resigned.
+    pub(crate) fn campaign(
+        &mut self,
+        request: &CampaignRequest,
+        sender: oneshot::Sender<Result<CampaignResponse>>,
+    ) {
+        let key = [
+            request.name.clone(),
+            format!("/{:x}", request.lease).into_bytes(),
+        ]
+        .concat();
+        let mut leader = LeaderKey::new();
+        leader.name = request.name.clone();
+        leader.key = key.clone();
+        leader.lease = request.lease;
+        let respond = move |result: Result<(ResponseHeader, i64)>| {
+            let _ = sender.send(result.map(|(header, create_revision)| {
+                leader.rev = create_revision;
+                let mut response = CampaignResponse::new();
+                response.set_header(header);
+                response.set_leader(leader);
+                response
+            }));
+        };
+        self.enqueue(key, request.lease, request.value.clone(), Box::new(respond));
+    }
+
+    /// Updates the leader's value, if `request` still names the leader.
+    pub(crate) fn proclaim(&mut self, request: &ProclaimRequest) -> Result<ProclaimResponse> {
+        let leader = request.get_leader();
+        if !self.holds(leader) {
+            return Err(status(
+                GrpcStatus::FailedPrecondition,
+                "election: not leader",
+            ));
+        }
+
+        let mut put_request = PutRequest::new();
+        put_request.key = leader.key.clone();
+        put_request.value = request.value.clone();
+        put_request.lease = leader.lease;
+        self.write(|data, revision| data.put(revision, &put_request))?;
+
+        let mut response = ProclaimResponse::new();
+        response.set_header(self.header());
+        Ok(response)
+    }
+
+    /// The key of the oldest candidate in the election `name`.
+    pub(crate) fn leader(&self, name: &[u8]) -> Result<LeaderResponse> {
+        let prefix = [name, b"/"].concat();
+        let kv = self
+            .data
+            .oldest(&prefix)
+            .cloned()
+            .ok_or_else(|| status(GrpcStatus::Unknown, "election: no leader"))?;
+
+        let mut response = LeaderResponse::new();
+        response.set_header(self.header());
+        response.set_kv(kv);
+        Ok(response)
+    }
+
+    /// Sends the leader of the election `name` on `sender` now, if there is one, and again
+    /// whenever it changes.
+    pub(crate) fn observe(&mut self, name: &[u8], sender: UnboundedSender<Result<LeaderResponse>>) {
+        let mut observer = Observer {
+            prefix: [name, b"/"].concat(),
+            sender,
+            last: None,
+        };
+        if observer.notify(&self.data, self.header()) {
+            self.observers.push(observer);
+        }
+    }
+
+    /// Deletes the leader's key, if `leader` still names the leader.
+    pub(crate) fn resign(&mut self, leader: &LeaderKey) -> Result<ResignResponse> {
+        if self.holds(leader) {
+            let mut delete_range_request = DeleteRangeRequest::new();
+            delete_range_request.key = leader.key.clone();
+            self.write(|data, revision| data.delete_range(revision, &delete_range_request))?;
+        }
+
+        let mut response = ResignResponse::new();
+        response.set_header(self.header());
+        Ok(response)
+    }
+
+    /// Handles one request on the watch stream `stream`, sending its responses on `sender`.
+    pub(crate) fn watch_request(
+        &mut self,
+        stream: u64,
+        request: WatchRequest,
+        sender: &UnboundedSender<Result<WatchResponse>>,
+    ) {
+        match request.request_union {
+            Some(WatchRequest_oneof_request_union::create_request(create_request)) => {
+                self.watch_create(stream, create_request, sender)
+            }
+            Some(WatchRequest_oneof_request_union::cancel_request(cancel_request)) => {
+                let id = cancel_request.watch_id;
+                self.watchers
+                    .retain(|watcher| watcher.stream != stream || watcher.id != id);
+
+                let mut response = WatchResponse::new();
+                response.set_header(self.header());
+                response.watch_id = id;
+                response.canceled = true;
+                let _ = sender.unbounded_send(Ok(response));
+            }
+            Some(WatchRequest_oneof_request_union::progress_request(_)) => {
+                if self.catching_up(stream) {
+                    self.deferred_progress.insert(stream);
+                } else {
+                    let _ = sender.unbounded_send(Ok(self.progress()));
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn watch_create(
+        &mut self,
+        stream: u64,
+        request: WatchCreateRequest,
+        sender: &UnboundedSender<Result<WatchResponse>>,
+    ) {
+        let id = if request.watch_id > 0 {
+            request.watch_id
+        } else {
+            self.next_watch_id += 1;
+            self.next_watch_id
+        };
+
+        let mut created = WatchResponse::new();
+        created.set_header(self.header());
+        created.watch_id = id;
+        created.created = true;
+        let _ = sender.unbounded_send(Ok(created));
+
+        if request.start_revision > 0 && request.start_revision < self.data.compact_revision {
+            let mut canceled = WatchResponse::new();
+            canceled.set_header(self.header());
+            canceled.watch_id = id;
+            canceled.canceled = true;
+            canceled.compact_revision = self.data.compact_revision;
+            let _ = sender.unbounded_send(Ok(canceled));
+            return;
+        }
+
+        let mut watcher = Watcher {
+            id,
+            stream,
+            request,
+            sender: sender.clone(),
+            catching_up: false,
+        };
+
+        // Catch up on history before any new events.
+        let start_revision = watcher.request.start_revision;
+        if start_revision > 0 {
+            let mut revision = start_revision;
+            while revision <= self.data.revision {
+                let events: Vec<Event> = self
+                    .data
+                    .events
+                    .iter()
+                    .filter(|event| event.get_kv().mod_revision == revision)
+                    .cloned()
+                    .collect();
+                watcher.notify(self.header(), &events);
+                revision += 1;
+            }
+        }
+        watcher.catching_up = start_revision > 0 && start_revision <= self.data.revision;
+
+        self.watchers.push(watcher);
+    }
+
+    /// Whether any watcher on `stream` is still catching up.
+    fn catching_up(&self, stream: u64) -> bool {
+        self.watchers
+            .iter()
+            .any(|watcher| watcher.stream == stream && watcher.catching_up)
+    }
+
+    fn progress(&self) -> WatchResponse {
+        let mut response = WatchResponse::new();
+        response.set_header(self.header());
+        response.watch_id = -1;
+        response
+    }
+
+    /// Answers the progress requests of every stream whose watchers have all caught up.
+    fn send_deferred_progress(&mut self) {
+        let deferred: Vec<u64> = self.deferred_progress.iter().cloned().collect();
+        for stream in deferred {
+            if self.catching_up(stream) {
+                continue;
+            }
+            self.deferred_progress.remove(&stream);
+            if let Some(watcher) = self
+                .watchers
+                .iter()
+                .find(|watcher| watcher.stream == stream)
+            {
+                let _ = watcher.sender.unbounded_send(Ok(self.progress()));
+            }
+        }
+    }
+
+    /// Applies `write` at the next revision, committing it only if it succeeds and notifying
+    /// watchers and lock waiters of its events.
+    fn write<F, T>(&mut self, write: F) -> Result<T>
+    where
+        F: FnOnce(&mut Data, i64) -> Result<T>,
+    {
+        let revision = self.data.revision + 1;
+        let mut data = self.data.clone();
+        let result = write(&mut data, revision)?;
+
+        let events: Vec<Event> = data
+            .events
+            .iter()
+            .filter(|event| event.get_kv().mod_revision == revision)
+            .cloned()
+            .collect();
+        if !events.is_empty() {
+            data.revision = revision;
+        }
+        self.data = data;
+
+        if !events.is_empty() {
+            let header = self.header();
+            self.watchers
+                .retain_mut(|watcher| watcher.notify(header.clone(), &events));
+            self.send_deferred_progress();
+            let data = &self.data;
+            self.observers
+                .retain_mut(|observer| observer.notify(data, header.clone()));
+            self.wake_waiters();
+        }
+
+        Ok(result)
+    }
+
+    /// Puts `key` with `value` on `lease`, unless it already holds that value, and queues
+    /// `respond` until every older key under the key's prefix is gone.
+    fn enqueue(&mut self, key: Vec<u8>, lease: i64, value: Vec<u8>, respond: Respond) {
+        if !self.data.leases.contains_key(&lease) {
+            respond(Err(lease_not_found()));
+            return;
+        }
+
+        let existing = self.data.get(&key, self.data.revision).cloned();
+        let create_revision = match existing {
+            Some(kv) if kv.value == value => kv.create_revision,
+            existing => {
+                let mut put_request = PutRequest::new();
+                put_request.key = key.clone();
+                put_request.value = value;
+                put_request.lease = lease;
+                if let Err(error) = self.write(|data, revision| data.put(revision, &put_request)) {
+                    respond(Err(error));
+                    return;
+                }
+                existing.map_or(self.data.revision, |kv| kv.create_revision)
+            }
+        };
+
+        self.waiters.push(Waiter {
+            key,
+            create_revision,
+            respond,
+        });
+        self.wake_waiters();
+    }
+
+    /// Responds to every waiter whose key is now the oldest under its prefix, or is gone.
+    fn wake_waiters(&mut self) {
+        let data = &self.data;
+        let header = self.header();
+        let (ready, waiting): (Vec<Waiter>, Vec<Waiter>) =
+            self.waiters.drain(..).partition(|waiter| {
+                let prefix = match waiter.key.iter().rposition(|byte| *byte == b'/') {
+                    Some(index) => &waiter.key[..=index],
+                    None => &waiter.key[..],
+                };
+                data.get(&waiter.key, data.revision).is_none()
+                    || data
+                        .live(prefix, &prefix_range_end(prefix), data.revision)
+                        .all(|kv| kv.create_revision >= waiter.create_revision)
+            });
+        self.waiters = waiting;
+
+        for waiter in ready {
+            let result = if data.get(&waiter.key, data.revision).is_some() {
+                Ok((header.clone(), waiter.create_revision))
+            } else {
+                Err(status(GrpcStatus::Unknown, "concurrency: session expired"))
+            };
+            (waiter.respond)(result);
+        }
+    }
+
+    /// Whether `leader` names the key of a candidate that has not resigned or expired since.
+    fn holds(&self, leader: &LeaderKey) -> bool {
+        match self.data.get(&leader.key, self.data.revision) {
+            Some(kv) => kv.create_revision == leader.rev,
+            None => false,
+        }
+    }
+}
+
+impl Data {
+    /// The value of `key` at `revision`, if it existed then.
+    fn get(&self, key: &[u8], revision: i64) -> Option<&KeyValue> {
+        self.keys
+            .get(key)
+            .and_then(|history| at_revision(history, revision))
+    }
+
+    /// The live key under `prefix` with the lowest create revision.
+    fn oldest(&self, prefix: &[u8]) -> Option<&KeyValue> {
+        self.live(prefix, &prefix_range_end(prefix), self.revision)
+            .min_by_key(|kv| kv.create_revision)
+    }
+
+    /// Every key in `[key, range_end)` that existed at `revision`, in key order.
+    fn live<'a>(
+        &'a self,
+        key: &[u8],
+        range_end: &[u8],
+        revision: i64,
+    ) -> impl Iterator<Item = &'a KeyValue> + 'a {
+        let start = Bound::Included(key.to_vec());
+        let end = if range_end.is_empty() {
+            Bound::Included(key.to_vec())
+        } else if range_end == [0] {
+            Bound::Unbounded
+        } else if key >= range_end {
+            // An inverted range is empty.
+            Bound::Excluded(key.to_vec())
+        } else {
+            Bound::Excluded(range_end.to_vec())
+        };
+
+        self.keys
+            .range((start, end))
+            .filter_map(move |(_, history)| at_revision(history, revision))
+    }
+
+    fn range(&self, request: &RangeRequest) -> Result<RangeResponse> {
+        key_provided(&request.key)?;
+        if request.revision > self.revision {
+            return Err(future_revision());
+        }
+        if request.revision > 0 && request.revision < self.compact_revision {
+            return Err(compacted());
+        }
+        let revision = if request.revision > 0 {
+            request.revision
+        } else {
+            self.revision
+        };
+
+        let mut kvs: Vec<KeyValue> = self
+            .live(&request.key, &request.range_end, revision)
+            .cloned()
+            .collect();
+        let count = kvs.len() as i64;
+
+        kvs.retain(|kv| {
+            (request.min_mod_revision == 0 || kv.mod_revision >= request.min_mod_revision)
+                && (request.max_mod_revision == 0 || kv.mod_revision <= request.max_mod_revision)
+                && (request.min_create_revision == 0
+                    || kv.create_revision >= request.min_create_revision)
+                && (request.max_create_revision == 0
+                    || kv.create_revision <= request.max_create_revision)
+        });
+
+        let sort_order = match (request.sort_order, request.sort_target) {
+            (RangeRequest_SortOrder::NONE, RangeRequest_SortTarget::KEY) => {
+                RangeRequest_SortOrder::NONE
+            }
+            (RangeRequest_SortOrder::NONE, _) => RangeRequest_SortOrder::ASCEND,
+            (sort_order, _) => sort_order,
+        };
+        if sort_order != RangeRequest_SortOrder::NONE {
+            match request.sort_target {
+                RangeRequest_SortTarget::KEY => {}
+                RangeRequest_SortTarget::VERSION => kvs.sort_by_key(|kv| kv.version),
+                RangeRequest_SortTarget::CREATE => kvs.sort_by_key(|kv| kv.create_revision),
+                RangeRequest_SortTarget::MOD => kvs.sort_by_key(|kv| kv.mod_revision),
+                RangeRequest_SortTarget::VALUE => kvs.sort_by(|a, b| a.value.cmp(&b.value)),
+            }
+            if sort_order == RangeRequest_SortOrder::DESCEND {
+                kvs.reverse();
+            }
+        }
+
+        let mut response = RangeResponse::new();
+        response.count = count;
+        if request.limit > 0 && kvs.len() > request.limit as usize {
+            kvs.truncate(request.limit as usize);
+            response.more = true;
+        }
+        if request.keys_only {
+            for kv in kvs.iter_mut() {
+                kv.value.clear();
+            }
+        }
+        if !request.count_only {
+            response.kvs = kvs.into();
+        }
+        Ok(response)
+    }
+
+    fn put(&mut self, revision: i64, request: &PutRequest) -> Result<PutResponse> {
+        key_provided(&request.key)?;
+
+        let previous = self.get(&request.key, revision).cloned();
+        if (request.ignore_value || request.ignore_lease) && previous.is_none() {
+            return Err(status(GrpcStatus::Argument, "etcdserver: key not found"));
+        }
+
+        let mut kv = KeyValue::new();
+        kv.key = request.key.clone();
+        kv.mod_revision = revision;
+        match &previous {
+            Some(previous) => {
+                kv.create_revision = previous.create_revision;
+                kv.version = previous.version + 1;
+            }
+            None => {
+                kv.create_revision = revision;
+                kv.version = 1;
+            }
+        }
+        kv.value = match &previous {
+            Some(previous) if request.ignore_value => previous.value.clone(),
+            _ => request.value.clone(),
+        };
+        kv.lease = match &previous {
+            Some(previous) if request.ignore_lease => previous.lease,
+            _ => request.lease,
+        };
+
+        if kv.lease != 0 && !self.leases.contains_key(&kv.lease) {
+            return Err(lease_not_found());
+        }
+        if let Some(previous) = &previous {
+            self.detach(previous);
+        }
+        if let Some(lease) = self.leases.get_mut(&kv.lease) {
+            lease.keys.insert(kv.key.clone());
+        }
+
+        self.record(Event_EventType::PUT, kv, previous.clone());
+
+        let mut response = PutResponse::new();
+        if request.prev_kv {
+            if let Some(previous) = previous {
+                response.set_prev_kv(previous);
+            }
+        }
+        Ok(response)
+    }
+
+    fn delete_range(
+        &mut self,
+        revision: i64,
+        request: &DeleteRangeRequest,
+    ) -> Result<DeleteRangeResponse> {
+        key_provided(&request.key)?;
+        let deleted: Vec<KeyValue> = self
+            .live(&request.key, &request.range_end, revision)
+            .cloned()
+            .collect();
+
+        for previous in deleted.iter() {
+            self.detach(previous);
+            let mut tombstone = KeyValue::new();
+            tombstone.key = previous.key.clone();
+            tombstone.mod_revision = revision;
+            self.record(Event_EventType::DELETE, tombstone, Some(previous.clone()));
+        }
+
+        let mut response = DeleteRangeResponse::new();
+        response.deleted = deleted.len() as i64;
+        if request.prev_kv {
+            response.prev_kvs = deleted.into();
+        }
+        Ok(response)
+    }
+
+    fn txn(&mut self, revision: i64, request: &TxnRequest) -> Result<TxnResponse> {
+        let max = MAX_TXN_OPS;
+        if request.compare.len() > max || request.success.len() > max || request.failure.len() > max
+        {
+            return Err(status(
+                GrpcStatus::Argument,
+                "etcdserver: too many operations in txn request",
+            ));
+        }
+        // etcd rejects a malformed op in either branch, whichever one runs.
+        for op in request.success.iter().chain(request.failure.iter()) {
+            check_op(op)?;
+        }
+
+        let succeeded = request
+            .compare
+            .iter()
+            .all(|compare| self.compare(compare, revision));
+        let ops = if succeeded {
+            &request.success
+        } else {
+            &request.failure
+        };
+
+        let mut response = TxnResponse::new();
+        response.succeeded = succeeded;
+        for op in ops.iter() {
+            response.responses.push(self.apply(revision, op)?);
+        }
+        Ok(response)
+    }
+
+    fn apply(&mut self, revision: i64, op: &RequestOp) -> Result<ResponseOp> {
+        let response = match &op.request {
+            Some(RequestOp_oneof_request::request_range(request)) => {
+                // Reads in a transaction see the transaction's own writes.
+                let current = self.revision;
+                self.revision = revision;
+                let response = self.range(request);
+                self.revision = current;
+                ResponseOp_oneof_response::response_range(response?)
+            }
+            Some(RequestOp_oneof_request::request_put(request)) => {
+                ResponseOp_oneof_response::response_put(self.put(revision, request)?)
+            }
+            Some(RequestOp_oneof_request::request_delete_range(request)) => {
+                ResponseOp_oneof_response::response_delete_range(
+                    self.delete_range(revision, request)?,
+                )
+            }
+            Some(RequestOp_oneof_request::request_txn(request)) => {
+                ResponseOp_oneof_response::response_txn(self.txn(revision, request)?)
+            }
+            None => {
+                return Err(status(
+                    GrpcStatus::Argument,
+                    "etcdserver: request op is not provided",
+                ))
+            }
+        };
+
+        let mut response_op = ResponseOp::new();
+        response_op.response = Some(response);
+        Ok(response_op)
+    }
+
+    fn compare(&self, compare: &Compare, revision: i64) -> bool {
+        let kvs: Vec<KeyValue> = self
+            .live(&compare.key, &compare.range_end, revision)
+            .cloned()
+            .collect();
+        if kvs.is_empty() {
+            // A missing key has no value to compare, but its revisions and version are 0.
+            return compare.target != Compare_CompareTarget::VALUE
+                && compare_key_value(compare, &KeyValue::new());
+        }
+        kvs.iter().all(|kv| compare_key_value(compare, kv))
+    }
+
+    /// Deletes every key attached to the lease `id`, then the lease itself.
+    fn revoke(&mut self, revision: i64, id: i64) {
+        let keys = match self.leases.get(&id) {
+            Some(lease) => lease.keys.clone(),
+            None => return,
+        };
+        for key in keys {
+            let mut request = DeleteRangeRequest::new();
+            request.key = key;
+            // Leased keys were put, so none of them is empty.
+            let _ = self.delete_range(revision, &request);
+        }
+        self.leases.remove(&id);
+    }
+
+    fn detach(&mut self, kv: &KeyValue) {
+        if let Some(lease) = self.leases.get_mut(&kv.lease) {
+            lease.keys.remove(&kv.key);
+        }
+    }
+
+    fn record(&mut self, event_type: Event_EventType, kv: KeyValue, previous: Option<KeyValue>) {
+        let mut event = Event::new();
+        event.field_type = event_type;
+        event.set_kv(kv.clone());
+        if let Some(previous) = previous {
+            event.set_prev_kv(previous);
+        }
+        self.events.push(event);
+        self.keys.entry(kv.key.clone()).or_default().push(kv);
+    }
+}
+
+impl Watcher {
+    /// Sends the events this watcher is interested in, returning whether it is still listening.
+    fn notify(&mut self, header: ResponseHeader, events: &[Event]) -> bool {
+        let request = &self.request;
+        let events: Vec<Event> = events
+            .iter()
+            .filter(|event| {
+                let kv = event.get_kv();
+                let in_range = if request.range_end.is_empty() {
+                    kv.key == request.key
+                } else if request.range_end == [0] {
+                    kv.key >= request.key
+                } else {
+                    kv.key >= request.key && kv.key < request.range_end
+                };
+                let filtered = match event.field_type {
+                    Event_EventType::PUT => WatchCreateRequest_FilterType::NOPUT,
+                    Event_EventType::DELETE => WatchCreateRequest_FilterType::NODELETE,
+                };
+                in_range && !request.filters.contains(&filtered)
+            })
+            .map(|event| {
+                let mut event = event.clone();
+                if !request.prev_kv {
+                    event.clear_prev_kv();
+                }
+                event
+            })
+            .collect();
+        if events.is_empty() {
+            return !self.sender.is_closed();
+        }
+
+        let mut response = WatchResponse::new();
+        response.set_header(header);
+        response.watch_id = self.id;
+        response.events = events.into();
+        self.catching_up = false;
+        self.sender.unbounded_send(Ok(response)).is_ok()
+    }
+}
+
+impl Observer {
+    /// Sends the leader if it changed since the last one sent, returning whether the stream is
+    /// still open.
+    fn notify(&mut self, data: &Data, header: ResponseHeader) -> bool {
+        let leader = data.oldest(&self.prefix);
+        let current = leader.map(|kv| (kv.key.clone(), kv.mod_revision));
+        if current == self.last {
+            return !self.sender.is_closed();
+        }
+        self.last = current;
+
+        match leader {
+            Some(kv) => {
+                let mut response = LeaderResponse::new();
+                response.set_header(header);
+                response.set_kv(kv.clone());
+                self.sender.unbounded_send(Ok(response)).is_ok()
+            }
+            None => !self.sender.is_closed(),
+        }
+    }
+}
+
+fn at_revision(history: &[KeyValue], revision: i64) -> Option<&KeyValue> {
+    history
+        .iter()
+        .rev()
+        .find(|kv| kv.mod_revision <= revision)
+        .filter(|kv| kv.version > 0)
+}
+
+fn compare_key_value(compare: &Compare, kv: &KeyValue) -> bool {
+    let ordering = match (compare.target, &compare.target_union) {
+        (Compare_CompareTarget::VERSION, Some(Compare_oneof_target_union::version(version))) => {
+            kv.version.cmp(version)
+        }
+        (
+            Compare_CompareTarget::CREATE,
+            Some(Compare_oneof_target_union::create_revision(revision)),
+        ) => kv.create_revision.cmp(revision),
+        (Compare_CompareTarget::MOD, Some(Compare_oneof_target_union::mod_revision(revision))) => {
+            kv.mod_revision.cmp(revision)
+        }
+        (Compare_CompareTarget::VALUE, Some(Compare_oneof_target_union::value(value))) => {
+            kv.value.cmp(value)
+        }
+        (Compare_CompareTarget::LEASE, Some(Compare_oneof_target_union::lease(lease))) => {
+            kv.lease.cmp(lease)
+        }
+        // An unset target compares against its zero value.
+        (Compare_CompareTarget::VALUE, _) => kv.value.as_slice().cmp(&[]),
+        (Compare_CompareTarget::VERSION, _) => kv.version.cmp(&0),
+        (Compare_CompareTarget::CREATE, _) => kv.create_revision.cmp(&0),
+        (Compare_CompareTarget::MOD, _) => kv.mod_revision.cmp(&0),
+        (Compare_CompareTarget::LEASE, _) => kv.lease.cmp(&0),
+    };
+
+    match compare.result {
+        Compare_CompareResult::EQUAL => ordering == std::cmp::Ordering::Equal,
+        Compare_CompareResult::GREATER => ordering == std::cmp::Ordering::Greater,
+        Compare_CompareResult::LESS => ordering == std::cmp::Ordering::Less,
+        Compare_CompareResult::NOT_EQUAL => ordering != std::cmp::Ordering::Equal,
+    }
+}
+
+fn set_txn_headers(response: &mut TxnResponse, header: &ResponseHeader) {
+    response.set_header(header.clone());
+    for op in response.responses.iter_mut() {
+        match &mut op.response {
+            Some(ResponseOp_oneof_response::response_range(response)) => {
+                response.set_header(header.clone())
+            }
+            Some(ResponseOp_oneof_response::response_put(response)) => {
+                response.set_header(header.clone())
+            }
+            Some(ResponseOp_oneof_response::response_delete_range(response)) => {
+                response.set_header(header.clone())
+            }
+            Some(ResponseOp_oneof_response::response_txn(response)) => {
+                set_txn_headers(response, header)
+            }
+            None => {}
+        }
+    }
+}
+
+pub(crate) fn status(status: GrpcStatus, message: &str) -> grpc::Error {
+    grpc::Error::GrpcMessage(GrpcMessageError {
+        grpc_status: status as i32,
+        grpc_message: message.to_string(),
+    })
+}
+
+fn key_provided(key: &[u8]) -> Result<()> {
+    if key.is_empty() {
+        return Err(status(
+            GrpcStatus::Argument,
+            "etcdserver: key is not provided",
+        ));
+    }
+    Ok(())
+}
+
+fn check_op(op: &RequestOp) -> Result<()> {
+    match &op.request {
+        Some(RequestOp_oneof_request::request_range(request)) => key_provided(&request.key),
+        Some(RequestOp_oneof_request::request_put(request)) => key_provided(&request.key),
+        Some(RequestOp_oneof_request::request_delete_range(request)) => key_provided(&request.key),
+        Some(RequestOp_oneof_request::request_txn(request)) => request
+            .success
+            .iter()
+            .chain(request.failure.iter())
+            .try_for_each(check_op),
+        None => Ok(()),
+    }
+}
+
+fn lease_not_found() -> grpc::Error {
+    status(
+        GrpcStatus::NotFound,
+        "etcdserver: requested lease not found",
+    )
+}
+
+fn compacted() -> grpc::Error {
+    status(
+        GrpcStatus::OutOfRange,
+        "etcdserver: mvcc: required revision has been compacted",
+    )
+}
+
+fn future_revision() -> grpc::Error {
+    status(
+        GrpcStatus::OutOfRange,
+        "etcdserver: mvcc: required revision is a future revision",
+    )
+}
@@ -0,0 +1,224 @@
+use super::Client;
+use crate::errors::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Connects to etcd at an endpoint such as `https://etcd.example.com:2379`, or over a Unix domain
+/// socket at an endpoint such as `unix:///var/run/etcd.sock`.
+///
+/// With the `tonic` feature the client runs over `TonicTransport` and can use TLS. Without it the
+/// client runs over the `grpc` crate, in plaintext only.
+///
+/// ```no_run
+/// # async fn example() -> Result<(), etcd::Error> {
+/// let client = etcd::Client::builder("https://etcd.example.com:2379")
+///     .ca_certificate_file("ca.pem")?
+///     .identity_files("client.pem", "client-key.pem")?
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ClientBuilder {
+    endpoint: String,
+    tls: TlsOptions,
+}
+
+#[derive(Default)]
+pub(crate) struct TlsOptions {
+    pub(crate) ca_certificates: Vec<Vec<u8>>,
+    pub(crate) identity: Option<(Vec<u8>, Vec<u8>)>,
+    pub(crate) server_name: Option<String>,
+    pub(crate) insecure_skip_verify: bool,
+}
+
+/// Where an endpoint points, parsed from `[http[s]://]host[:port]` or `unix://path`.
+#[derive(Clone)]
+pub(crate) enum Address {
+    Tcp {
+        https: bool,
+        host: String,
+        port: u16,
+    },
+    Unix(PathBuf),
+}
+
+impl ClientBuilder {
+    pub fn new<E: Into<String>>(endpoint: E) -> ClientBuilder {
+        ClientBuilder {
+            endpoint: endpoint.into(),
+            tls: TlsOptions::default(),
+        }
+    }
+
+    /// Trusts the CA certificates in a PEM bundle, in addition to any already added.
+    pub fn ca_certificate(mut self, pem: Vec<u8>) -> ClientBuilder {
+        self.tls.ca_certificates.push(pem);
+        self
+    }
+
+    pub fn ca_certificate_file<P: AsRef<Path>>(self, path: P) -> Result<ClientBuilder, Error> {
+        Ok(self.ca_certificate(fs::read(path)?))
+    }
+
+    /// Presents a PEM certificate chain and private key to the server, for mutual TLS.
+    pub fn identity(mut self, certificate: Vec<u8>, key: Vec<u8>) -> ClientBuilder {
+        self.tls.identity = Some((certificate, key));
+        self
+    }
+
+    pub fn identity_files<C: AsRef<Path>, K: AsRef<Path>>(
+        self,
+        certificate: C,
+        key: K,
+    ) -> Result<ClientBuilder, Error> {
+        Ok(self.identity(fs::read(certificate)?, fs::read(key)?))
+    }
+
+    /// Verifies the server's certificate against `server_name` instead of the endpoint's host, or
+    /// instead of `localhost` for a Unix domain socket.
+    pub fn server_name<S: Into<String>>(mut self, server_name: S) -> ClientBuilder {
+        self.tls.server_name = Some(server_name.into());
+        self
+    }
+
+    /// Accepts any server certificate. Only for testing.
+    pub fn insecure_skip_verify(mut self, insecure_skip_verify: bool) -> ClientBuilder {
+        self.tls.insecure_skip_verify = insecure_skip_verify;
+        self
+    }
+
+    /// Connects over TLS if the endpoint is `https` or any TLS option is set.
+    ///
+    /// Must be called from within a tokio runtime when using the `tonic` feature.
+    #[cfg(feature = "tonic")]
+    pub async fn connect(self) -> Result<Client, Error> {
+        use super::connector::Connector;
+        use super::tonic_transport::unavailable;
+        use super::{Endpoint, TonicTransport};
+
+        let address = Address::parse(&self.endpoint)?;
+        let tls = if address.is_https() || self.tls.is_configured() {
+            Some(self.tls.connector(address.host())?)
+        } else {
+            None
+        };
+
+        // The connector dials the address and does any TLS itself, so tonic is only ever given
+        // plain HTTP and the URI only names the authority.
+        let uri = format!("http://{}", address.authority());
+        let endpoint = Endpoint::from_shared(uri)
+            .map_err(|error| Error::Unrecoverable(format!("Invalid endpoint: {}", error)))?;
+        let channel = endpoint
+            .connect_with_connector(Connector::new(address, tls))
+            .await
+            .map_err(unavailable)?;
+        Ok(TonicTransport::new(channel).into())
+    }
+
+    /// Connects in plaintext. TLS needs the `tonic` feature.
+    #[cfg(not(feature = "tonic"))]
+    pub async fn connect(self) -> Result<Client, Error> {
+        let address = Address::parse(&self.endpoint)?;
+        if address.is_https() || self.tls.is_configured() {
+            return Err(Error::Tls("TLS needs the `tonic` feature".to_string()));
+        }
+
+        let client = match address {
+            Address::Tcp { host, port, .. } => {
+                grpc::Client::new_plain(&host, port, Default::default())?
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                grpc::Client::new_plain_unix(&path.to_string_lossy(), Default::default())?
+            }
+            #[cfg(not(unix))]
+            Address::Unix(_) => return Err(unix_unsupported()),
+        };
+        Ok(client.into())
+    }
+}
+
+impl TlsOptions {
+    fn is_configured(&self) -> bool {
+        !self.ca_certificates.is_empty()
+            || self.identity.is_some()
+            || self.server_name.is_some()
+            || self.insecure_skip_verify
+    }
+}
+
+impl Address {
+    pub(crate) fn parse(endpoint: &str) -> Result<Address, Error> {
+        let invalid = || Error::Unrecoverable(format!("Invalid endpoint: {}", endpoint));
+        if let Some(path) = endpoint.strip_prefix("unix://") {
+            if path.is_empty() {
+                return Err(invalid());
+            }
+            return Ok(Address::Unix(PathBuf::from(path)));
+        }
+
+        let (https, authority) = if let Some(authority) = endpoint.strip_prefix("https://") {
+            (true, authority)
+        } else if let Some(authority) = endpoint.strip_prefix("http://") {
+            (false, authority)
+        } else {
+            (false, endpoint)
+        };
+        let authority = authority.trim_end_matches('/');
+
+        // IPv6 hosts are bracketed, so the port follows the last colon after the closing bracket.
+        let (host, port) = match authority.rfind(':') {
+            Some(colon) if colon > authority.rfind(']').unwrap_or(0) => {
+                let port = authority[colon + 1..].parse().map_err(|_| invalid())?;
+                (&authority[..colon], port)
+            }
+            _ => (authority, 2379),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Address::Tcp {
+            https,
+            host: host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string(),
+            port,
+        })
+    }
+
+    fn is_https(&self) -> bool {
+        match self {
+            Address::Tcp { https, .. } => *https,
+            Address::Unix(_) => false,
+        }
+    }
+
+    /// The host to verify the server's certificate against, `localhost` for a socket.
+    #[cfg(feature = "tonic")]
+    fn host(&self) -> &str {
+        match self {
+            Address::Tcp { host, .. } => host,
+            Address::Unix(_) => "localhost",
+        }
+    }
+
+    /// The host and port, with IPv6 hosts bracketed.
+    #[cfg(feature = "tonic")]
+    fn authority(&self) -> String {
+        match self {
+            Address::Tcp { host, port, .. } if host.contains(':') => {
+                format!("[{}]:{}", host, port)
+            }
+            Address::Tcp { host, port, .. } => format!("{}:{}", host, port),
+            Address::Unix(_) => "localhost".to_string(),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn unix_unsupported() -> Error {
+    Error::Unrecoverable("Unix domain sockets are not supported on this platform".to_string())
+}
@@ -0,0 +1,194 @@
+use super::builder::{Address, TlsOptions};
+use crate::errors::Error;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use hyper_util::rt::TokioIo;
+use std::convert::TryFrom;
+use std::io;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{self, ring, CryptoProvider};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use tokio_rustls::TlsConnector;
+use tonic::codegen::http::Uri;
+use tower_service::Service;
+
+/// Opens the connections under a tonic channel to its address, wrapping them in TLS when
+/// configured.
+#[derive(Clone)]
+pub(crate) struct Connector {
+    address: Address,
+    tls: Option<(TlsConnector, ServerName<'static>)>,
+}
+
+pub(crate) trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Io for T {}
+
+impl Connector {
+    pub(crate) fn new(
+        address: Address,
+        tls: Option<(TlsConnector, ServerName<'static>)>,
+    ) -> Connector {
+        Connector { address, tls }
+    }
+}
+
+impl Service<Uri> for Connector {
+    type Response = TokioIo<Box<dyn Io>>;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<TokioIo<Box<dyn Io>>>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: Uri) -> Self::Future {
+        let address = self.address.clone();
+        let tls = self.tls.clone();
+        async move {
+            let stream: Box<dyn Io> = match address {
+                Address::Tcp { host, port, .. } => {
+                    let stream = TcpStream::connect((host.as_str(), port)).await?;
+                    stream.set_nodelay(true)?;
+                    Box::new(stream)
+                }
+                #[cfg(unix)]
+                Address::Unix(path) => Box::new(UnixStream::connect(path).await?),
+                #[cfg(not(unix))]
+                Address::Unix(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        super::builder::unix_unsupported().to_string(),
+                    ))
+                }
+            };
+
+            let stream: Box<dyn Io> = match tls {
+                Some((connector, server_name)) => {
+                    Box::new(connector.connect(server_name, stream).await?)
+                }
+                None => Box::new(stream),
+            };
+            Ok(TokioIo::new(stream))
+        }
+        .boxed()
+    }
+}
+
+impl TlsOptions {
+    /// The TLS connector and the name to verify the server's certificate against.
+    pub(crate) fn connector(
+        &self,
+        host: &str,
+    ) -> Result<(TlsConnector, ServerName<'static>), Error> {
+        let provider = Arc::new(ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?;
+
+        let builder = if self.insecure_skip_verify {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(SkipVerification(provider)))
+        } else {
+            let mut roots = RootCertStore::empty();
+            for pem in self.ca_certificates.iter() {
+                for certificate in certificates(pem)? {
+                    roots.add(certificate).map_err(tls_error)?;
+                }
+            }
+            if roots.is_empty() {
+                return Err(Error::Tls(
+                    "No CA certificate to verify the server with".to_string(),
+                ));
+            }
+            builder.with_root_certificates(roots)
+        };
+
+        let mut config = match &self.identity {
+            Some((certificate, key)) => {
+                let key = rustls_pemfile::private_key(&mut &key[..])?
+                    .ok_or_else(|| Error::Tls("No private key in PEM".to_string()))?;
+                builder
+                    .with_client_auth_cert(certificates(certificate)?, key)
+                    .map_err(tls_error)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        config.alpn_protocols = vec![b"h2".to_vec()];
+
+        let server_name = self.server_name.as_deref().unwrap_or(host).to_string();
+        let server_name = ServerName::try_from(server_name).map_err(tls_error)?;
+        Ok((TlsConnector::from(Arc::new(config)), server_name))
+    }
+}
+
+fn certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, Error> {
+    let certificates = rustls_pemfile::certs(&mut &pem[..]).collect::<Result<Vec<_>, _>>()?;
+    if certificates.is_empty() {
+        return Err(Error::Tls("No certificate in PEM".to_string()));
+    }
+    Ok(certificates)
+}
+
+fn tls_error<E: std::fmt::Display>(error: E) -> Error {
+    Error::Tls(error.to_string())
+}
+
+/// Accepts any certificate, while still checking the handshake is signed by it.
+#[derive(Debug)]
+struct SkipVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for SkipVerification {
+    fn verify_server_cert(
+        &self,
+        _: &CertificateDer<'_>,
+        _: &[CertificateDer<'_>],
+        _: &ServerName<'_>,
+        _: &[u8],
+        _: UnixTime,
+    ) -> Result<ServerCertVerified, tokio_rustls::rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            certificate,
+            signature,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, tokio_rustls::rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            certificate,
+            signature,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
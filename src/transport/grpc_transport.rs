@@ -0,0 +1,463 @@
+use super::Transport;
+use crate::errors::Error;
+use crate::rpc::{
+    AuthRoleAddRequest, AuthRoleAddResponse, AuthRoleDeleteRequest, AuthRoleDeleteResponse,
+    AuthRoleGetRequest, AuthRoleGetResponse, AuthRoleGrantPermissionRequest,
+    AuthRoleGrantPermissionResponse, AuthRoleListRequest, AuthRoleListResponse,
+    AuthRoleRevokePermissionRequest, AuthRoleRevokePermissionResponse, AuthUserAddRequest,
+    AuthUserAddResponse, AuthUserChangePasswordRequest, AuthUserChangePasswordResponse,
+    AuthUserDeleteRequest, AuthUserDeleteResponse, AuthUserGetRequest, AuthUserGetResponse,
+    AuthUserGrantRoleRequest, AuthUserGrantRoleResponse, AuthUserListRequest, AuthUserListResponse,
+    AuthUserRevokeRoleRequest, AuthUserRevokeRoleResponse, CompactionRequest, CompactionResponse,
+    DeleteRangeRequest, DeleteRangeResponse, LeaseGrantRequest, LeaseGrantResponse,
+    LeaseKeepAliveRequest, LeaseKeepAliveResponse, LeaseLeasesRequest, LeaseLeasesResponse,
+    LeaseRevokeRequest, LeaseRevokeResponse, LeaseTimeToLiveRequest, LeaseTimeToLiveResponse,
+    MemberAddRequest, MemberAddResponse, MemberListRequest, MemberListResponse,
+    MemberRemoveRequest, MemberRemoveResponse, PutRequest, PutResponse, RangeRequest,
+    RangeResponse, SnapshotRequest, SnapshotResponse, StatusRequest, StatusResponse, TxnRequest,
+    TxnResponse, WatchRequest, WatchResponse,
+};
+use crate::rpc_grpc::{
+    Auth, AuthClient, Cluster, ClusterClient, KVClient, Lease, LeaseClient, Maintenance,
+    MaintenanceClient, Watch, WatchClient, KV,
+};
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderRequest, LeaderResponse, ProclaimRequest,
+    ProclaimResponse, ResignRequest, ResignResponse,
+};
+use crate::v3election_grpc::{Election, ElectionClient};
+use crate::v3lock::{LockRequest, LockResponse, UnlockRequest, UnlockResponse};
+use crate::v3lock_grpc::{Lock, LockClient};
+use futures::compat::{Future01CompatExt, Stream01CompatExt};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, TryFutureExt, TryStreamExt};
+use grpc::{ClientStub, GrpcFuture, GrpcStream, RequestOptions, StreamingRequest};
+use std::sync::Arc;
+
+// Calls on the `grpc` crate start as soon as they are made.
+impl Transport for Arc<grpc::Client> {
+    fn range(&self, request: RangeRequest) -> BoxFuture<'static, Result<RangeResponse, Error>> {
+        let kv_client = KVClient::with_client(self.clone());
+        unary(
+            kv_client
+                .range(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn put(&self, request: PutRequest) -> BoxFuture<'static, Result<PutResponse, Error>> {
+        let kv_client = KVClient::with_client(self.clone());
+        unary(
+            kv_client
+                .put(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn delete_range(
+        &self,
+        request: DeleteRangeRequest,
+    ) -> BoxFuture<'static, Result<DeleteRangeResponse, Error>> {
+        let kv_client = KVClient::with_client(self.clone());
+        unary(
+            kv_client
+                .delete_range(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn txn(&self, request: TxnRequest) -> BoxFuture<'static, Result<TxnResponse, Error>> {
+        let kv_client = KVClient::with_client(self.clone());
+        unary(
+            kv_client
+                .txn(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn compact(
+        &self,
+        request: CompactionRequest,
+    ) -> BoxFuture<'static, Result<CompactionResponse, Error>> {
+        let kv_client = KVClient::with_client(self.clone());
+        unary(
+            kv_client
+                .compact(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn watch(
+        &self,
+        requests: BoxStream<'static, WatchRequest>,
+    ) -> BoxStream<'static, Result<WatchResponse, Error>> {
+        let watch_client = WatchClient::with_client(self.clone());
+        streaming(
+            watch_client
+                .watch(
+                    RequestOptions::new(),
+                    StreamingRequest::new(requests.map(Ok).compat()),
+                )
+                .drop_metadata(),
+        )
+    }
+
+    fn lease_grant(
+        &self,
+        request: LeaseGrantRequest,
+    ) -> BoxFuture<'static, Result<LeaseGrantResponse, Error>> {
+        let lease_client = LeaseClient::with_client(self.clone());
+        unary(
+            lease_client
+                .lease_grant(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn lease_revoke(
+        &self,
+        request: LeaseRevokeRequest,
+    ) -> BoxFuture<'static, Result<LeaseRevokeResponse, Error>> {
+        let lease_client = LeaseClient::with_client(self.clone());
+        unary(
+            lease_client
+                .lease_revoke(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn lease_keep_alive(
+        &self,
+        requests: BoxStream<'static, LeaseKeepAliveRequest>,
+    ) -> BoxStream<'static, Result<LeaseKeepAliveResponse, Error>> {
+        let lease_client = LeaseClient::with_client(self.clone());
+        streaming(
+            lease_client
+                .lease_keep_alive(
+                    RequestOptions::new(),
+                    StreamingRequest::new(requests.map(Ok).compat()),
+                )
+                .drop_metadata(),
+        )
+    }
+
+    fn lease_time_to_live(
+        &self,
+        request: LeaseTimeToLiveRequest,
+    ) -> BoxFuture<'static, Result<LeaseTimeToLiveResponse, Error>> {
+        let lease_client = LeaseClient::with_client(self.clone());
+        unary(
+            lease_client
+                .lease_time_to_live(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn lease_leases(
+        &self,
+        request: LeaseLeasesRequest,
+    ) -> BoxFuture<'static, Result<LeaseLeasesResponse, Error>> {
+        let lease_client = LeaseClient::with_client(self.clone());
+        unary(
+            lease_client
+                .lease_leases(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn member_add(
+        &self,
+        request: MemberAddRequest,
+    ) -> BoxFuture<'static, Result<MemberAddResponse, Error>> {
+        let cluster_client = ClusterClient::with_client(self.clone());
+        unary(
+            cluster_client
+                .member_add(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn member_remove(
+        &self,
+        request: MemberRemoveRequest,
+    ) -> BoxFuture<'static, Result<MemberRemoveResponse, Error>> {
+        let cluster_client = ClusterClient::with_client(self.clone());
+        unary(
+            cluster_client
+                .member_remove(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn member_list(
+        &self,
+        request: MemberListRequest,
+    ) -> BoxFuture<'static, Result<MemberListResponse, Error>> {
+        let cluster_client = ClusterClient::with_client(self.clone());
+        unary(
+            cluster_client
+                .member_list(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn status(&self, request: StatusRequest) -> BoxFuture<'static, Result<StatusResponse, Error>> {
+        let maintenance_client = MaintenanceClient::with_client(self.clone());
+        unary(
+            maintenance_client
+                .status(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn snapshot(
+        &self,
+        request: SnapshotRequest,
+    ) -> BoxStream<'static, Result<SnapshotResponse, Error>> {
+        let maintenance_client = MaintenanceClient::with_client(self.clone());
+        streaming(
+            maintenance_client
+                .snapshot(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn user_add(
+        &self,
+        request: AuthUserAddRequest,
+    ) -> BoxFuture<'static, Result<AuthUserAddResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .user_add(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn user_get(
+        &self,
+        request: AuthUserGetRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGetResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .user_get(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn user_list(
+        &self,
+        request: AuthUserListRequest,
+    ) -> BoxFuture<'static, Result<AuthUserListResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .user_list(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn user_delete(
+        &self,
+        request: AuthUserDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthUserDeleteResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .user_delete(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn user_change_password(
+        &self,
+        request: AuthUserChangePasswordRequest,
+    ) -> BoxFuture<'static, Result<AuthUserChangePasswordResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .user_change_password(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn user_grant_role(
+        &self,
+        request: AuthUserGrantRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGrantRoleResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .user_grant_role(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn user_revoke_role(
+        &self,
+        request: AuthUserRevokeRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserRevokeRoleResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .user_revoke_role(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn role_add(
+        &self,
+        request: AuthRoleAddRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleAddResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .role_add(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn role_get(
+        &self,
+        request: AuthRoleGetRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGetResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .role_get(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn role_list(
+        &self,
+        request: AuthRoleListRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleListResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .role_list(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn role_delete(
+        &self,
+        request: AuthRoleDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleDeleteResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .role_delete(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn role_grant_permission(
+        &self,
+        request: AuthRoleGrantPermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGrantPermissionResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .role_grant_permission(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn role_revoke_permission(
+        &self,
+        request: AuthRoleRevokePermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleRevokePermissionResponse, Error>> {
+        let auth_client = AuthClient::with_client(self.clone());
+        unary(
+            auth_client
+                .role_revoke_permission(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn lock(&self, request: LockRequest) -> BoxFuture<'static, Result<LockResponse, Error>> {
+        let lock_client = LockClient::with_client(self.clone());
+        unary(
+            lock_client
+                .lock(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn unlock(&self, request: UnlockRequest) -> BoxFuture<'static, Result<UnlockResponse, Error>> {
+        let lock_client = LockClient::with_client(self.clone());
+        unary(
+            lock_client
+                .unlock(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn campaign(
+        &self,
+        request: CampaignRequest,
+    ) -> BoxFuture<'static, Result<CampaignResponse, Error>> {
+        let election_client = ElectionClient::with_client(self.clone());
+        unary(
+            election_client
+                .campaign(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn proclaim(
+        &self,
+        request: ProclaimRequest,
+    ) -> BoxFuture<'static, Result<ProclaimResponse, Error>> {
+        let election_client = ElectionClient::with_client(self.clone());
+        unary(
+            election_client
+                .proclaim(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn leader(&self, request: LeaderRequest) -> BoxFuture<'static, Result<LeaderResponse, Error>> {
+        let election_client = ElectionClient::with_client(self.clone());
+        unary(
+            election_client
+                .leader(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn observe(&self, request: LeaderRequest) -> BoxStream<'static, Result<LeaderResponse, Error>> {
+        let election_client = ElectionClient::with_client(self.clone());
+        streaming(
+            election_client
+                .observe(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn resign(&self, request: ResignRequest) -> BoxFuture<'static, Result<ResignResponse, Error>> {
+        let election_client = ElectionClient::with_client(self.clone());
+        unary(
+            election_client
+                .resign(RequestOptions::new(), request)
+                .drop_metadata(),
+        )
+    }
+
+    fn detach(&self, task: BoxFuture<'static, ()>) {
+        // The task's calls are already in flight.
+        drop(task);
+    }
+}
+
+fn unary<T: Send + 'static>(response: GrpcFuture<T>) -> BoxFuture<'static, Result<T, Error>> {
+    response.compat().err_into().boxed()
+}
+
+fn streaming<T: Send + 'static>(responses: GrpcStream<T>) -> BoxStream<'static, Result<T, Error>> {
+    responses.compat().err_into().boxed()
+}
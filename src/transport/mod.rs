@@ -0,0 +1,260 @@
+//! The etcd services the high-level APIs are built on, independent of the gRPC stack that carries
+//! them.
+//!
+//! `Arc<grpc::Client>` is always a transport. With the `tonic` feature, `TonicTransport` runs the
+//! same services over tonic, which adds HTTP/2 keepalive and TLS. `ClientBuilder` picks the
+//! transport and sets up TLS.
+
+mod builder;
+#[cfg(feature = "tonic")]
+mod connector;
+mod grpc_transport;
+#[cfg(feature = "tonic")]
+mod tonic_transport;
+
+pub use self::builder::ClientBuilder;
+
+#[cfg(feature = "tonic")]
+pub use self::tonic_transport::TonicTransport;
+#[cfg(feature = "tonic")]
+pub use tonic::transport::Endpoint;
+
+use crate::errors::Error;
+use crate::rpc::{
+    AuthRoleAddRequest, AuthRoleAddResponse, AuthRoleDeleteRequest, AuthRoleDeleteResponse,
+    AuthRoleGetRequest, AuthRoleGetResponse, AuthRoleGrantPermissionRequest,
+    AuthRoleGrantPermissionResponse, AuthRoleListRequest, AuthRoleListResponse,
+    AuthRoleRevokePermissionRequest, AuthRoleRevokePermissionResponse, AuthUserAddRequest,
+    AuthUserAddResponse, AuthUserChangePasswordRequest, AuthUserChangePasswordResponse,
+    AuthUserDeleteRequest, AuthUserDeleteResponse, AuthUserGetRequest, AuthUserGetResponse,
+    AuthUserGrantRoleRequest, AuthUserGrantRoleResponse, AuthUserListRequest, AuthUserListResponse,
+    AuthUserRevokeRoleRequest, AuthUserRevokeRoleResponse, CompactionRequest, CompactionResponse,
+    DeleteRangeRequest, DeleteRangeResponse, LeaseGrantRequest, LeaseGrantResponse,
+    LeaseKeepAliveRequest, LeaseKeepAliveResponse, LeaseLeasesRequest, LeaseLeasesResponse,
+    LeaseRevokeRequest, LeaseRevokeResponse, LeaseTimeToLiveRequest, LeaseTimeToLiveResponse,
+    MemberAddRequest, MemberAddResponse, MemberListRequest, MemberListResponse,
+    MemberRemoveRequest, MemberRemoveResponse, PutRequest, PutResponse, RangeRequest,
+    RangeResponse, SnapshotRequest, SnapshotResponse, StatusRequest, StatusResponse, TxnRequest,
+    TxnResponse, WatchRequest, WatchResponse,
+};
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderRequest, LeaderResponse, ProclaimRequest,
+    ProclaimResponse, ResignRequest, ResignResponse,
+};
+use crate::v3lock::{LockRequest, LockResponse, UnlockRequest, UnlockResponse};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// The RPCs of etcd's `KV`, `Watch`, `Lease`, `Cluster`, `Maintenance`, `Auth`, `Lock` and
+/// `Election` services.
+///
+/// Calls start as soon as they are made, whether or not the returned future or stream is polled,
+/// so a watch sees every event after it is created.
+pub trait Transport: Send + Sync + 'static {
+    fn range(&self, request: RangeRequest) -> BoxFuture<'static, Result<RangeResponse, Error>>;
+
+    fn put(&self, request: PutRequest) -> BoxFuture<'static, Result<PutResponse, Error>>;
+
+    fn delete_range(
+        &self,
+        request: DeleteRangeRequest,
+    ) -> BoxFuture<'static, Result<DeleteRangeResponse, Error>>;
+
+    fn txn(&self, request: TxnRequest) -> BoxFuture<'static, Result<TxnResponse, Error>>;
+
+    fn compact(
+        &self,
+        request: CompactionRequest,
+    ) -> BoxFuture<'static, Result<CompactionResponse, Error>>;
+
+    fn watch(
+        &self,
+        requests: BoxStream<'static, WatchRequest>,
+    ) -> BoxStream<'static, Result<WatchResponse, Error>>;
+
+    fn lease_grant(
+        &self,
+        request: LeaseGrantRequest,
+    ) -> BoxFuture<'static, Result<LeaseGrantResponse, Error>>;
+
+    fn lease_revoke(
+        &self,
+        request: LeaseRevokeRequest,
+    ) -> BoxFuture<'static, Result<LeaseRevokeResponse, Error>>;
+
+    fn lease_keep_alive(
+        &self,
+        requests: BoxStream<'static, LeaseKeepAliveRequest>,
+    ) -> BoxStream<'static, Result<LeaseKeepAliveResponse, Error>>;
+
+    fn lease_time_to_live(
+        &self,
+        request: LeaseTimeToLiveRequest,
+    ) -> BoxFuture<'static, Result<LeaseTimeToLiveResponse, Error>>;
+
+    fn lease_leases(
+        &self,
+        request: LeaseLeasesRequest,
+    ) -> BoxFuture<'static, Result<LeaseLeasesResponse, Error>>;
+
+    fn member_add(
+        &self,
+        request: MemberAddRequest,
+    ) -> BoxFuture<'static, Result<MemberAddResponse, Error>>;
+
+    fn member_remove(
+        &self,
+        request: MemberRemoveRequest,
+    ) -> BoxFuture<'static, Result<MemberRemoveResponse, Error>>;
+
+    fn member_list(
+        &self,
+        request: MemberListRequest,
+    ) -> BoxFuture<'static, Result<MemberListResponse, Error>>;
+
+    fn status(&self, request: StatusRequest) -> BoxFuture<'static, Result<StatusResponse, Error>>;
+
+    fn snapshot(
+        &self,
+        request: SnapshotRequest,
+    ) -> BoxStream<'static, Result<SnapshotResponse, Error>>;
+
+    fn user_add(
+        &self,
+        request: AuthUserAddRequest,
+    ) -> BoxFuture<'static, Result<AuthUserAddResponse, Error>>;
+
+    fn user_get(
+        &self,
+        request: AuthUserGetRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGetResponse, Error>>;
+
+    fn user_list(
+        &self,
+        request: AuthUserListRequest,
+    ) -> BoxFuture<'static, Result<AuthUserListResponse, Error>>;
+
+    fn user_delete(
+        &self,
+        request: AuthUserDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthUserDeleteResponse, Error>>;
+
+    fn user_change_password(
+        &self,
+        request: AuthUserChangePasswordRequest,
+    ) -> BoxFuture<'static, Result<AuthUserChangePasswordResponse, Error>>;
+
+    fn user_grant_role(
+        &self,
+        request: AuthUserGrantRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGrantRoleResponse, Error>>;
+
+    fn user_revoke_role(
+        &self,
+        request: AuthUserRevokeRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserRevokeRoleResponse, Error>>;
+
+    fn role_add(
+        &self,
+        request: AuthRoleAddRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleAddResponse, Error>>;
+
+    fn role_get(
+        &self,
+        request: AuthRoleGetRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGetResponse, Error>>;
+
+    fn role_list(
+        &self,
+        request: AuthRoleListRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleListResponse, Error>>;
+
+    fn role_delete(
+        &self,
+        request: AuthRoleDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleDeleteResponse, Error>>;
+
+    fn role_grant_permission(
+        &self,
+        request: AuthRoleGrantPermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGrantPermissionResponse, Error>>;
+
+    fn role_revoke_permission(
+        &self,
+        request: AuthRoleRevokePermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleRevokePermissionResponse, Error>>;
+
+    fn lock(&self, request: LockRequest) -> BoxFuture<'static, Result<LockResponse, Error>>;
+
+    fn unlock(&self, request: UnlockRequest) -> BoxFuture<'static, Result<UnlockResponse, Error>>;
+
+    fn campaign(
+        &self,
+        request: CampaignRequest,
+    ) -> BoxFuture<'static, Result<CampaignResponse, Error>>;
+
+    fn proclaim(
+        &self,
+        request: ProclaimRequest,
+    ) -> BoxFuture<'static, Result<ProclaimResponse, Error>>;
+
+    fn leader(&self, request: LeaderRequest) -> BoxFuture<'static, Result<LeaderResponse, Error>>;
+
+    fn observe(&self, request: LeaderRequest) -> BoxStream<'static, Result<LeaderResponse, Error>>;
+
+    fn resign(&self, request: ResignRequest) -> BoxFuture<'static, Result<ResignResponse, Error>>;
+
+    /// Drives `task` to completion in the background, such as logging the outcome of releasing a
+    /// lease when its handle is dropped.
+    ///
+    /// The task's calls are already underway, so a transport may simply drop it instead.
+    fn detach(&self, task: BoxFuture<'static, ()>);
+}
+
+/// A cheaply cloned handle to etcd over any `Transport`.
+#[derive(Clone)]
+pub struct Client {
+    transport: Arc<dyn Transport>,
+}
+
+impl Client {
+    pub fn new<T: Transport>(transport: T) -> Client {
+        Client {
+            transport: Arc::new(transport),
+        }
+    }
+
+    /// Configures a connection to `endpoint`, such as `https://etcd.example.com:2379`.
+    pub fn builder<E: Into<String>>(endpoint: E) -> ClientBuilder {
+        ClientBuilder::new(endpoint)
+    }
+}
+
+impl Deref for Client {
+    type Target = dyn Transport;
+
+    fn deref(&self) -> &(dyn Transport + 'static) {
+        &*self.transport
+    }
+}
+
+impl From<Arc<grpc::Client>> for Client {
+    fn from(client: Arc<grpc::Client>) -> Client {
+        Client::new(client)
+    }
+}
+
+impl From<grpc::Client> for Client {
+    fn from(client: grpc::Client) -> Client {
+        Client::new(Arc::new(client))
+    }
+}
+
+#[cfg(feature = "tonic")]
+impl From<TonicTransport> for Client {
+    fn from(transport: TonicTransport) -> Client {
+        Client::new(transport)
+    }
+}
@@ -0,0 +1,463 @@
+use super::Transport;
+use crate::errors::Error;
+use crate::rpc::{
+    AuthRoleAddRequest, AuthRoleAddResponse, AuthRoleDeleteRequest, AuthRoleDeleteResponse,
+    AuthRoleGetRequest, AuthRoleGetResponse, AuthRoleGrantPermissionRequest,
+    AuthRoleGrantPermissionResponse, AuthRoleListRequest, AuthRoleListResponse,
+    AuthRoleRevokePermissionRequest, AuthRoleRevokePermissionResponse, AuthUserAddRequest,
+    AuthUserAddResponse, AuthUserChangePasswordRequest, AuthUserChangePasswordResponse,
+    AuthUserDeleteRequest, AuthUserDeleteResponse, AuthUserGetRequest, AuthUserGetResponse,
+    AuthUserGrantRoleRequest, AuthUserGrantRoleResponse, AuthUserListRequest, AuthUserListResponse,
+    AuthUserRevokeRoleRequest, AuthUserRevokeRoleResponse, CompactionRequest, CompactionResponse,
+    DeleteRangeRequest, DeleteRangeResponse, LeaseGrantRequest, LeaseGrantResponse,
+    LeaseKeepAliveRequest, LeaseKeepAliveResponse, LeaseLeasesRequest, LeaseLeasesResponse,
+    LeaseRevokeRequest, LeaseRevokeResponse, LeaseTimeToLiveRequest, LeaseTimeToLiveResponse,
+    MemberAddRequest, MemberAddResponse, MemberListRequest, MemberListResponse,
+    MemberRemoveRequest, MemberRemoveResponse, PutRequest, PutResponse, RangeRequest,
+    RangeResponse, SnapshotRequest, SnapshotResponse, StatusRequest, StatusResponse, TxnRequest,
+    TxnResponse, WatchRequest, WatchResponse,
+};
+use crate::v3election::{
+    CampaignRequest, CampaignResponse, LeaderRequest, LeaderResponse, ProclaimRequest,
+    ProclaimResponse, ResignRequest, ResignResponse,
+};
+use crate::v3lock::{LockRequest, LockResponse, UnlockRequest, UnlockResponse};
+use bytes::{Buf, BufMut};
+use futures::channel::{mpsc, oneshot};
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream};
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use grpc::{GrpcMessageError, GrpcStatus};
+use protobuf::Message;
+use std::marker::PhantomData;
+use tokio::runtime::Handle;
+use tonic::client::Grpc;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
+
+/// Runs etcd's services over a tonic `Channel`.
+///
+/// Keepalive, timeouts and the like are configured on the `Endpoint` before connecting.
+#[derive(Clone)]
+pub struct TonicTransport {
+    channel: Channel,
+    runtime: Handle,
+}
+
+impl TonicTransport {
+    /// Connects to `endpoint`, such as `Endpoint::from_static("http://localhost:2379")`.
+    ///
+    /// Must be called from within a tokio runtime, which is also used for background tasks.
+    pub async fn connect(endpoint: Endpoint) -> Result<TonicTransport, Error> {
+        let channel = endpoint.connect().await.map_err(unavailable)?;
+        Ok(TonicTransport::new(channel))
+    }
+
+    /// Wraps an existing channel.
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn new(channel: Channel) -> TonicTransport {
+        TonicTransport {
+            channel,
+            runtime: Handle::current(),
+        }
+    }
+
+    /// Makes a unary call, starting it right away on the runtime like the `grpc` crate does.
+    fn unary<Req, Resp>(
+        &self,
+        path: &'static str,
+        request: Req,
+    ) -> BoxFuture<'static, Result<Resp, Error>>
+    where
+        Req: Message + Sync,
+        Resp: Message + Sync,
+    {
+        let mut grpc = Grpc::new(self.channel.clone());
+        let (sender, receiver) = oneshot::channel();
+        self.runtime.spawn(async move {
+            let response = async {
+                grpc.ready().await.map_err(unavailable)?;
+                let response = grpc
+                    .unary(
+                        Request::new(request),
+                        PathAndQuery::from_static(path),
+                        ProtobufCodec::default(),
+                    )
+                    .await
+                    .map_err(from_status)?;
+                Ok(response.into_inner())
+            };
+            let _ = sender.send(response.await);
+        });
+
+        receiver
+            .map(|response| response.unwrap_or_else(|_| Err(shut_down())))
+            .boxed()
+    }
+
+    /// Makes a streaming call, starting it right away on the runtime like the `grpc` crate does.
+    fn streaming<Req, Resp>(
+        &self,
+        path: &'static str,
+        requests: BoxStream<'static, Req>,
+    ) -> BoxStream<'static, Result<Resp, Error>>
+    where
+        Req: Message + Sync,
+        Resp: Message + Sync,
+    {
+        let mut grpc = Grpc::new(self.channel.clone());
+        let (sender, receiver) = mpsc::unbounded();
+        self.runtime.spawn(async move {
+            let responses = async {
+                grpc.ready().await.map_err(unavailable)?;
+                let response = grpc
+                    .streaming(
+                        Request::new(requests),
+                        PathAndQuery::from_static(path),
+                        ProtobufCodec::default(),
+                    )
+                    .await
+                    .map_err(from_status)?;
+                Ok::<_, Error>(response.into_inner().map_err(from_status))
+            };
+            let mut responses = stream::once(responses).try_flatten().boxed();
+            while let Some(response) = responses.next().await {
+                if sender.unbounded_send(response).is_err() {
+                    break;
+                }
+            }
+        });
+
+        receiver.boxed()
+    }
+
+    fn server_streaming<Req, Resp>(
+        &self,
+        path: &'static str,
+        request: Req,
+    ) -> BoxStream<'static, Result<Resp, Error>>
+    where
+        Req: Message + Sync,
+        Resp: Message + Sync,
+    {
+        self.streaming(path, stream::once(async { request }).boxed())
+    }
+}
+
+impl Transport for TonicTransport {
+    fn range(&self, request: RangeRequest) -> BoxFuture<'static, Result<RangeResponse, Error>> {
+        self.unary("/etcdserverpb.KV/Range", request)
+    }
+
+    fn put(&self, request: PutRequest) -> BoxFuture<'static, Result<PutResponse, Error>> {
+        self.unary("/etcdserverpb.KV/Put", request)
+    }
+
+    fn delete_range(
+        &self,
+        request: DeleteRangeRequest,
+    ) -> BoxFuture<'static, Result<DeleteRangeResponse, Error>> {
+        self.unary("/etcdserverpb.KV/DeleteRange", request)
+    }
+
+    fn txn(&self, request: TxnRequest) -> BoxFuture<'static, Result<TxnResponse, Error>> {
+        self.unary("/etcdserverpb.KV/Txn", request)
+    }
+
+    fn compact(
+        &self,
+        request: CompactionRequest,
+    ) -> BoxFuture<'static, Result<CompactionResponse, Error>> {
+        self.unary("/etcdserverpb.KV/Compact", request)
+    }
+
+    fn watch(
+        &self,
+        requests: BoxStream<'static, WatchRequest>,
+    ) -> BoxStream<'static, Result<WatchResponse, Error>> {
+        self.streaming("/etcdserverpb.Watch/Watch", requests)
+    }
+
+    fn lease_grant(
+        &self,
+        request: LeaseGrantRequest,
+    ) -> BoxFuture<'static, Result<LeaseGrantResponse, Error>> {
+        self.unary("/etcdserverpb.Lease/LeaseGrant", request)
+    }
+
+    fn lease_revoke(
+        &self,
+        request: LeaseRevokeRequest,
+    ) -> BoxFuture<'static, Result<LeaseRevokeResponse, Error>> {
+        self.unary("/etcdserverpb.Lease/LeaseRevoke", request)
+    }
+
+    fn lease_keep_alive(
+        &self,
+        requests: BoxStream<'static, LeaseKeepAliveRequest>,
+    ) -> BoxStream<'static, Result<LeaseKeepAliveResponse, Error>> {
+        self.streaming("/etcdserverpb.Lease/LeaseKeepAlive", requests)
+    }
+
+    fn lease_time_to_live(
+        &self,
+        request: LeaseTimeToLiveRequest,
+    ) -> BoxFuture<'static, Result<LeaseTimeToLiveResponse, Error>> {
+        self.unary("/etcdserverpb.Lease/LeaseTimeToLive", request)
+    }
+
+    fn lease_leases(
+        &self,
+        request: LeaseLeasesRequest,
+    ) -> BoxFuture<'static, Result<LeaseLeasesResponse, Error>> {
+        self.unary("/etcdserverpb.Lease/LeaseLeases", request)
+    }
+
+    fn member_add(
+        &self,
+        request: MemberAddRequest,
+    ) -> BoxFuture<'static, Result<MemberAddResponse, Error>> {
+        self.unary("/etcdserverpb.Cluster/MemberAdd", request)
+    }
+
+    fn member_remove(
+        &self,
+        request: MemberRemoveRequest,
+    ) -> BoxFuture<'static, Result<MemberRemoveResponse, Error>> {
+        self.unary("/etcdserverpb.Cluster/MemberRemove", request)
+    }
+
+    fn member_list(
+        &self,
+        request: MemberListRequest,
+    ) -> BoxFuture<'static, Result<MemberListResponse, Error>> {
+        self.unary("/etcdserverpb.Cluster/MemberList", request)
+    }
+
+    fn status(&self, request: StatusRequest) -> BoxFuture<'static, Result<StatusResponse, Error>> {
+        self.unary("/etcdserverpb.Maintenance/Status", request)
+    }
+
+    fn snapshot(
+        &self,
+        request: SnapshotRequest,
+    ) -> BoxStream<'static, Result<SnapshotResponse, Error>> {
+        self.server_streaming("/etcdserverpb.Maintenance/Snapshot", request)
+    }
+
+    fn user_add(
+        &self,
+        request: AuthUserAddRequest,
+    ) -> BoxFuture<'static, Result<AuthUserAddResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/UserAdd", request)
+    }
+
+    fn user_get(
+        &self,
+        request: AuthUserGetRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGetResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/UserGet", request)
+    }
+
+    fn user_list(
+        &self,
+        request: AuthUserListRequest,
+    ) -> BoxFuture<'static, Result<AuthUserListResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/UserList", request)
+    }
+
+    fn user_delete(
+        &self,
+        request: AuthUserDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthUserDeleteResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/UserDelete", request)
+    }
+
+    fn user_change_password(
+        &self,
+        request: AuthUserChangePasswordRequest,
+    ) -> BoxFuture<'static, Result<AuthUserChangePasswordResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/UserChangePassword", request)
+    }
+
+    fn user_grant_role(
+        &self,
+        request: AuthUserGrantRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserGrantRoleResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/UserGrantRole", request)
+    }
+
+    fn user_revoke_role(
+        &self,
+        request: AuthUserRevokeRoleRequest,
+    ) -> BoxFuture<'static, Result<AuthUserRevokeRoleResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/UserRevokeRole", request)
+    }
+
+    fn role_add(
+        &self,
+        request: AuthRoleAddRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleAddResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/RoleAdd", request)
+    }
+
+    fn role_get(
+        &self,
+        request: AuthRoleGetRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGetResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/RoleGet", request)
+    }
+
+    fn role_list(
+        &self,
+        request: AuthRoleListRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleListResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/RoleList", request)
+    }
+
+    fn role_delete(
+        &self,
+        request: AuthRoleDeleteRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleDeleteResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/RoleDelete", request)
+    }
+
+    fn role_grant_permission(
+        &self,
+        request: AuthRoleGrantPermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleGrantPermissionResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/RoleGrantPermission", request)
+    }
+
+    fn role_revoke_permission(
+        &self,
+        request: AuthRoleRevokePermissionRequest,
+    ) -> BoxFuture<'static, Result<AuthRoleRevokePermissionResponse, Error>> {
+        self.unary("/etcdserverpb.Auth/RoleRevokePermission", request)
+    }
+
+    fn lock(&self, request: LockRequest) -> BoxFuture<'static, Result<LockResponse, Error>> {
+        self.unary("/v3lockpb.Lock/Lock", request)
+    }
+
+    fn unlock(&self, request: UnlockRequest) -> BoxFuture<'static, Result<UnlockResponse, Error>> {
+        self.unary("/v3lockpb.Lock/Unlock", request)
+    }
+
+    fn campaign(
+        &self,
+        request: CampaignRequest,
+    ) -> BoxFuture<'static, Result<CampaignResponse, Error>> {
+        self.unary("/v3electionpb.Election/Campaign", request)
+    }
+
+    fn proclaim(
+        &self,
+        request: ProclaimRequest,
+    ) -> BoxFuture<'static, Result<ProclaimResponse, Error>> {
+        self.unary("/v3electionpb.Election/Proclaim", request)
+    }
+
+    fn leader(&self, request: LeaderRequest) -> BoxFuture<'static, Result<LeaderResponse, Error>> {
+        self.unary("/v3electionpb.Election/Leader", request)
+    }
+
+    fn observe(&self, request: LeaderRequest) -> BoxStream<'static, Result<LeaderResponse, Error>> {
+        self.server_streaming("/v3electionpb.Election/Observe", request)
+    }
+
+    fn resign(&self, request: ResignRequest) -> BoxFuture<'static, Result<ResignResponse, Error>> {
+        self.unary("/v3electionpb.Election/Resign", request)
+    }
+
+    fn detach(&self, task: BoxFuture<'static, ()>) {
+        self.runtime.spawn(task);
+    }
+}
+
+/// Encodes and decodes the `protobuf` crate's messages, so tonic can share the generated types.
+struct ProtobufCodec<Req, Resp>(PhantomData<(Req, Resp)>);
+
+impl<Req, Resp> Default for ProtobufCodec<Req, Resp> {
+    fn default() -> Self {
+        ProtobufCodec(PhantomData)
+    }
+}
+
+impl<Req: Message, Resp: Message> Codec for ProtobufCodec<Req, Resp> {
+    type Encode = Req;
+    type Decode = Resp;
+    type Encoder = ProtobufCodec<Req, Resp>;
+    type Decoder = ProtobufCodec<Req, Resp>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        ProtobufCodec::default()
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        ProtobufCodec::default()
+    }
+}
+
+impl<Req: Message, Resp> Encoder for ProtobufCodec<Req, Resp> {
+    type Item = Req;
+    type Error = Status;
+
+    fn encode(&mut self, item: Req, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        item.write_to_writer(&mut dst.writer())
+            .map_err(|error| Status::internal(error.to_string()))
+    }
+}
+
+impl<Req, Resp: Message> Decoder for ProtobufCodec<Req, Resp> {
+    type Item = Resp;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Resp>, Status> {
+        let bytes = src.copy_to_bytes(src.remaining());
+        protobuf::parse_from_bytes(&bytes)
+            .map(Some)
+            .map_err(|error| Status::internal(error.to_string()))
+    }
+}
+
+/// Converts tonic's status to the `grpc` crate's, so callers see the same errors on either
+/// transport.
+fn from_status(status: Status) -> Error {
+    Error::Grpc(grpc::Error::GrpcMessage(GrpcMessageError {
+        grpc_status: status.code() as i32,
+        grpc_message: status.message().to_string(),
+    }))
+}
+
+fn shut_down() -> Error {
+    Error::Grpc(status_error(
+        GrpcStatus::Unavailable,
+        "The tonic runtime shut down".to_string(),
+    ))
+}
+
+/// Reports a connection failure with its causes, which tonic leaves out of its own message.
+pub(crate) fn unavailable(error: tonic::transport::Error) -> Error {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        // IO errors display the error they wrap, which is also their source.
+        let cause_message = cause.to_string();
+        if !message.ends_with(&cause_message) {
+            message = format!("{}: {}", message, cause_message);
+        }
+        source = cause.source();
+    }
+    Error::Grpc(status_error(GrpcStatus::Unavailable, message))
+}
+
+fn status_error(status: GrpcStatus, message: String) -> grpc::Error {
+    grpc::Error::GrpcMessage(GrpcMessageError {
+        grpc_status: status as i32,
+        grpc_message: message,
+    })
+}
@@ -0,0 +1,155 @@
+use crate::codec::{Codec, Json};
+use crate::errors::Error;
+use crate::kv::{Event_EventType, KeyValue};
+use crate::kv_client::{prefix_key, prefix_range_end, Kv};
+use crate::rpc::{DeleteRangeRequest, PutRequest, PutResponse, RangeRequest, WatchCreateRequest};
+use crate::transport::Client;
+use crate::watch::Watch;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+/// Values of type `T` stored in etcd through a `Codec`, JSON by default.
+///
+/// ```no_run
+/// # #[derive(serde::Serialize, serde::Deserialize)]
+/// # struct Config { replicas: u32 }
+/// # async fn example(client: etcd::Client) -> Result<(), etcd::Error> {
+/// let configs = etcd::TypedKv::<Config>::new(client);
+/// configs.put("config", &Config { replicas: 3 }).await?;
+/// let config = configs.get("config").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TypedKv<T, C = Json> {
+    kv: Kv,
+    codec: C,
+    values: PhantomData<fn() -> T>,
+}
+
+/// A change to a key watched through `TypedKv::watch`, at the revision it was made.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypedEvent<T> {
+    Put {
+        key: Vec<u8>,
+        value: T,
+        revision: i64,
+    },
+    Delete {
+        key: Vec<u8>,
+        revision: i64,
+    },
+}
+
+impl<T: Serialize + DeserializeOwned> TypedKv<T> {
+    pub fn new<I: Into<Client>>(client: I) -> TypedKv<T> {
+        TypedKv::with_codec(client, Json)
+    }
+}
+
+impl<T, C: Codec<T>> TypedKv<T, C> {
+    pub fn with_codec<I: Into<Client>>(client: I, codec: C) -> TypedKv<T, C> {
+        TypedKv {
+            kv: Kv::new(client),
+            codec,
+            values: PhantomData,
+        }
+    }
+
+    /// The untyped `Kv` underneath, for requests this type does not cover.
+    pub fn kv(&self) -> &Kv {
+        &self.kv
+    }
+
+    pub async fn get<K: Into<Vec<u8>>>(&self, key: K) -> Result<Option<T>, Error> {
+        match self.kv.get(key.into()).await? {
+            Some(kv) => decode(&self.codec, &kv).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Every value under `prefix`, in key order.
+    pub async fn get_prefix<K: Into<Vec<u8>>>(
+        &self,
+        prefix: K,
+    ) -> Result<Vec<(Vec<u8>, T)>, Error> {
+        let mut range_request = RangeRequest::new();
+        let prefix = prefix.into();
+        range_request.key = prefix_key(&prefix);
+        range_request.range_end = prefix_range_end(&prefix);
+        let range_response = self.kv.range(range_request).await?;
+
+        range_response
+            .kvs
+            .into_iter()
+            .map(|kv| {
+                let value = decode(&self.codec, &kv)?;
+                Ok((kv.key, value))
+            })
+            .collect()
+    }
+
+    pub async fn put<K: Into<Vec<u8>>>(&self, key: K, value: &T) -> Result<PutResponse, Error> {
+        let mut put_request = PutRequest::new();
+        put_request.key = key.into();
+        put_request.value = self.codec.encode(value)?;
+        self.kv.put(put_request).await
+    }
+
+    /// Deletes `key`, returning whether it existed.
+    pub async fn delete<K: Into<Vec<u8>>>(&self, key: K) -> Result<bool, Error> {
+        let mut delete_range_request = DeleteRangeRequest::new();
+        delete_range_request.key = key.into();
+        let delete_range_response = self.kv.delete_range(delete_range_request).await?;
+        Ok(delete_range_response.deleted > 0)
+    }
+
+    /// Watches a key or range, decoding each put.
+    ///
+    /// A value that fails to decode is yielded as `Error::Decode`, and the watch carries on.
+    pub fn watch(
+        &self,
+        watch_create_request: WatchCreateRequest,
+    ) -> impl Stream<Item = Result<TypedEvent<T>, Error>>
+    where
+        C: Clone + Send + 'static,
+        T: Send + 'static,
+    {
+        let codec = self.codec.clone();
+        Watch::new(watch_create_request, self.kv.client.clone())
+            .map_ok(move |response| {
+                let events: Vec<_> = response
+                    .events
+                    .into_iter()
+                    .map(|mut event| {
+                        let kv = event.take_kv();
+                        match event.field_type {
+                            Event_EventType::PUT => Ok(TypedEvent::Put {
+                                value: decode(&codec, &kv)?,
+                                revision: kv.mod_revision,
+                                key: kv.key,
+                            }),
+                            Event_EventType::DELETE => Ok(TypedEvent::Delete {
+                                revision: kv.mod_revision,
+                                key: kv.key,
+                            }),
+                        }
+                    })
+                    .collect();
+                stream::iter(events)
+            })
+            .try_flatten()
+            .boxed()
+    }
+}
+
+/// Decodes a stored value, naming its key in any error.
+fn decode<T, C: Codec<T>>(codec: &C, kv: &KeyValue) -> Result<T, Error> {
+    codec.decode(&kv.value).map_err(|error| match error {
+        Error::Decode(message) => {
+            Error::Decode(format!("{}: {}", String::from_utf8_lossy(&kv.key), message))
+        }
+        error => error,
+    })
+}
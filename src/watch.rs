@@ -0,0 +1,243 @@
+use crate::errors::Error;
+use crate::kv::{Event_EventType, KeyValue};
+use crate::kv_client::{prefix_key, prefix_range_end};
+use crate::rpc::{
+    RangeRequest, WatchCancelRequest, WatchCreateRequest, WatchProgressRequest, WatchRequest,
+    WatchRequest_oneof_request_union, WatchResponse,
+};
+use crate::transport::Client;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
+use futures::task::{Context, Poll};
+use std::collections::BTreeMap;
+use std::pin::Pin;
+
+/// A single watch on a key or range, streaming every response that carries events, and the
+/// answers to `request_progress`.
+pub struct Watch {
+    requests: UnboundedSender<WatchRequest>,
+    responses: BoxStream<'static, Result<WatchResponse, Error>>,
+    watch_id: Option<i64>,
+}
+
+impl Watch {
+    pub fn new<C: Into<Client>>(watch_create_request: WatchCreateRequest, client: C) -> Watch {
+        trace!("Creating watch...");
+
+        let (requests, receiver) = unbounded();
+        let mut watch_request = WatchRequest::new();
+        watch_request.request_union = Some(WatchRequest_oneof_request_union::create_request(
+            watch_create_request,
+        ));
+        let _ = requests.unbounded_send(watch_request);
+
+        let responses = client.into().watch(receiver.boxed());
+
+        Watch {
+            requests,
+            responses,
+            watch_id: None,
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        trace!("Canceling watch...");
+
+        if let Some(watch_id) = self.watch_id {
+            let mut watch_cancel_request = WatchCancelRequest::new();
+            watch_cancel_request.watch_id = watch_id;
+            let mut watch_request = WatchRequest::new();
+            watch_request.request_union = Some(WatchRequest_oneof_request_union::cancel_request(
+                watch_cancel_request,
+            ));
+            let _ = self.requests.unbounded_send(watch_request);
+        }
+    }
+
+    /// Asks etcd how far the watch has caught up. The answer is streamed as a response with no
+    /// events and a `watch_id` of -1, whose header revision every earlier event is at or below.
+    ///
+    /// etcd only answers once every watcher on the stream has caught up with the latest revision,
+    /// and before 3.5.8 drops the request instead, so a watch replaying history may never see it.
+    pub fn request_progress(&self) {
+        trace!("Requesting watch progress...");
+
+        let mut watch_request = WatchRequest::new();
+        watch_request.request_union = Some(WatchRequest_oneof_request_union::progress_request(
+            WatchProgressRequest::new(),
+        ));
+        let _ = self.requests.unbounded_send(watch_request);
+    }
+}
+
+impl Stream for Watch {
+    type Item = Result<WatchResponse, Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<WatchResponse, Error>>> {
+        loop {
+            let response = match futures::ready!(self.responses.poll_next_unpin(cx)) {
+                Some(Ok(response)) => response,
+                Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+                None => return Poll::Ready(None),
+            };
+
+            if response.created {
+                self.watch_id = Some(response.watch_id);
+            }
+
+            if response.canceled {
+                if response.compact_revision > 0 {
+                    return Poll::Ready(Some(Err(Error::Compacted {
+                        compact_revision: response.compact_revision,
+                    })));
+                }
+
+                if !response.cancel_reason.is_empty() {
+                    return Poll::Ready(Some(Err(Error::Unrecoverable(response.cancel_reason))));
+                }
+
+                return Poll::Ready(None);
+            }
+
+            if !response.events.is_empty() || response.watch_id == -1 {
+                return Poll::Ready(Some(Ok(response)));
+            }
+        }
+    }
+}
+
+/// Waits for `key` to be deleted at or after `revision`.
+pub(crate) async fn wait_delete(client: Client, key: Vec<u8>, revision: i64) -> Result<(), Error> {
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = key;
+    watch_create_request.start_revision = revision;
+
+    let mut watch = Watch::new(watch_create_request, client);
+    while let Some(response) = watch.try_next().await? {
+        if response
+            .events
+            .iter()
+            .any(|event| event.field_type == Event_EventType::DELETE)
+        {
+            return Ok(());
+        }
+    }
+
+    Err(Error::Unrecoverable(
+        "Lost watcher waiting for delete".to_string(),
+    ))
+}
+
+/// Waits for `key` to be put at or after `revision`.
+pub(crate) async fn wait_put(
+    client: Client,
+    key: Vec<u8>,
+    revision: i64,
+) -> Result<KeyValue, Error> {
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = key;
+    watch_create_request.start_revision = revision;
+
+    first_put(Watch::new(watch_create_request, client)).await
+}
+
+/// Waits for the first key to be put under `prefix` at or after `revision`.
+pub(crate) async fn wait_prefix_put(
+    client: Client,
+    prefix: Vec<u8>,
+    revision: i64,
+) -> Result<KeyValue, Error> {
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = prefix_key(&prefix);
+    watch_create_request.range_end = prefix_range_end(&prefix);
+    watch_create_request.start_revision = revision;
+
+    first_put(Watch::new(watch_create_request, client)).await
+}
+
+async fn first_put(mut watch: Watch) -> Result<KeyValue, Error> {
+    while let Some(response) = watch.try_next().await? {
+        let put = response
+            .events
+            .into_iter()
+            .find(|event| event.field_type == Event_EventType::PUT);
+        if let Some(event) = put {
+            return Ok(event.kv.unwrap_or_default());
+        }
+    }
+
+    Err(Error::Unrecoverable(
+        "Lost watcher waiting for put".to_string(),
+    ))
+}
+
+/// Keys under a prefix, with their values.
+pub(crate) type Snapshot = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Every key under `prefix` with its value, and the revision they were read at.
+pub(crate) async fn range_prefix(client: &Client, prefix: &[u8]) -> Result<(Snapshot, i64), Error> {
+    let mut range_request = RangeRequest::new();
+    range_request.key = prefix_key(prefix);
+    range_request.range_end = prefix_range_end(prefix);
+    let range_response = client.range(range_request).await?;
+
+    let revision = range_response.get_header().revision;
+    let keys = range_response
+        .kvs
+        .into_iter()
+        .map(|kv| (kv.key, kv.value))
+        .collect();
+    Ok((keys, revision))
+}
+
+/// Streams every key under `prefix` with its value, then all of them again after each revision
+/// that changes any. Ends after the first error.
+pub(crate) fn prefix_snapshots(
+    client: Client,
+    prefix: Vec<u8>,
+) -> BoxStream<'static, Result<Snapshot, Error>> {
+    enum State {
+        Start(Client, Vec<u8>),
+        Following(Watch, Snapshot),
+        Done,
+    }
+
+    stream::unfold(State::Start(client, prefix), |state| async move {
+        match state {
+            State::Start(client, prefix) => match range_prefix(&client, &prefix).await {
+                Ok((keys, revision)) => {
+                    let mut watch_create_request = WatchCreateRequest::new();
+                    watch_create_request.key = prefix_key(&prefix);
+                    watch_create_request.range_end = prefix_range_end(&prefix);
+                    watch_create_request.start_revision = revision + 1;
+                    let watch = Watch::new(watch_create_request, client);
+                    Some((Ok(keys.clone()), State::Following(watch, keys)))
+                }
+                Err(error) => Some((Err(error), State::Done)),
+            },
+            State::Following(mut watch, mut keys) => match watch.try_next().await {
+                Ok(Some(response)) => {
+                    for mut event in response.events.into_iter() {
+                        let kv = event.take_kv();
+                        match event.field_type {
+                            Event_EventType::PUT => {
+                                keys.insert(kv.key, kv.value);
+                            }
+                            Event_EventType::DELETE => {
+                                keys.remove(&kv.key);
+                            }
+                        }
+                    }
+                    Some((Ok(keys.clone()), State::Following(watch, keys)))
+                }
+                Ok(None) => None,
+                Err(error) => Some((Err(error), State::Done)),
+            },
+            State::Done => None,
+        }
+    })
+    .boxed()
+}
@@ -0,0 +1,185 @@
+//! `CachedKv` against the in-memory fake, including resyncs after broken and compacted watches.
+
+mod common;
+
+use common::{eventually, put, serve};
+use etcd::rpc::{CompactionRequest, DeleteRangeRequest, RangeRequest};
+use etcd::testing::{Fault, Method};
+use etcd::{prefix_range_end, CacheMetrics, CachedKv, Kv};
+use futures::executor::block_on;
+use std::time::Duration;
+
+fn value(cache: &CachedKv, key: &[u8]) -> Option<Vec<u8>> {
+    block_on(cache.get(key.to_vec()))
+        .unwrap()
+        .map(|kv| kv.value)
+}
+
+#[test]
+fn serves_reads_from_the_cache() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    put(&kv, b"config/a", b"1");
+    let revision = put(&kv, b"other", b"2");
+
+    let cache = block_on(CachedKv::new(client.clone(), b"config/".to_vec())).unwrap();
+    assert_eq!(cache.revision(), Some(revision));
+    assert_eq!(value(&cache, b"config/a"), Some(b"1".to_vec()));
+    assert_eq!(value(&cache, b"config/missing"), None);
+    assert_eq!(value(&cache, b"other"), Some(b"2".to_vec()));
+
+    let metrics = cache.metrics();
+    assert_eq!(
+        metrics,
+        CacheMetrics {
+            hits: 2,
+            misses: 1,
+            resyncs: 0
+        }
+    );
+    assert!((metrics.hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn follows_changes() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    put(&kv, b"config/a", b"1");
+    let cache = block_on(CachedKv::new(client.clone(), b"config/".to_vec())).unwrap();
+
+    let revision = put(&kv, b"config/a", b"2");
+    eventually(|| cache.revision() == Some(revision));
+    assert_eq!(value(&cache, b"config/a"), Some(b"2".to_vec()));
+
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"config/a".to_vec();
+    let deleted = block_on(kv.delete_range(delete_range_request))
+        .unwrap()
+        .get_header()
+        .revision;
+    eventually(|| cache.revision() == Some(deleted));
+    assert_eq!(value(&cache, b"config/a"), None);
+    assert_eq!(cache.metrics().misses, 0);
+}
+
+#[test]
+fn caches_the_whole_keyspace_for_an_empty_prefix() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    put(&kv, b"a", b"1");
+    let cache = block_on(CachedKv::new(client.clone(), Vec::new())).unwrap();
+    assert_eq!(value(&cache, b"a"), Some(b"1".to_vec()));
+
+    let revision = put(&kv, b"b", b"2");
+    eventually(|| cache.revision() == Some(revision));
+    assert_eq!(value(&cache, b"b"), Some(b"2".to_vec()));
+    assert_eq!(cache.metrics().misses, 0);
+}
+
+#[test]
+fn ranges_match_etcd() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    let first = put(&kv, b"config/a", b"value");
+    put(&kv, b"config/b", b"value");
+    put(&kv, b"config/c", b"value");
+    let cache = block_on(CachedKv::new(client.clone(), b"config/".to_vec())).unwrap();
+
+    let mut range_request = RangeRequest::new();
+    range_request.key = b"config/".to_vec();
+    range_request.range_end = prefix_range_end(b"config/");
+    range_request.limit = 2;
+    let cached = block_on(cache.range(range_request.clone())).unwrap();
+    let direct = block_on(kv.range(range_request.clone())).unwrap();
+    assert_eq!(cached.kvs, direct.kvs);
+    assert_eq!(cached.count, 3);
+    assert!(cached.more);
+    assert_eq!(cached.get_header().revision, direct.get_header().revision);
+
+    // Ranges past the prefix, and reads at an older revision, go to etcd.
+    range_request.range_end = vec![0];
+    block_on(cache.range(range_request.clone())).unwrap();
+    range_request.range_end = prefix_range_end(b"config/");
+    range_request.revision = first;
+    assert_eq!(block_on(cache.range(range_request)).unwrap().kvs.len(), 1);
+    assert_eq!(cache.metrics().misses, 2);
+}
+
+#[test]
+fn revision_filters_match_etcd() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    let a = put(&kv, b"config/a", b"1");
+    let b = put(&kv, b"config/b", b"1");
+    put(&kv, b"config/c", b"1");
+    let updated = put(&kv, b"config/a", b"2");
+    let cache = block_on(CachedKv::new(client.clone(), b"config/".to_vec())).unwrap();
+    eventually(|| cache.revision() == Some(updated));
+
+    let mut range_request = RangeRequest::new();
+    range_request.key = b"config/".to_vec();
+    range_request.range_end = prefix_range_end(b"config/");
+    let mut filtered = vec![range_request; 4];
+    filtered[0].min_mod_revision = updated;
+    filtered[1].max_mod_revision = b;
+    filtered[2].min_create_revision = b;
+    filtered[3].max_create_revision = a;
+    for filtered in filtered.iter() {
+        for &limit in [0, 1].iter() {
+            for &count_only in [false, true].iter() {
+                let mut range_request = filtered.clone();
+                range_request.limit = limit;
+                range_request.count_only = count_only;
+
+                let hits = cache.metrics().hits;
+                let cached = block_on(cache.range(range_request.clone())).unwrap();
+                assert_eq!(cache.metrics().hits, hits + 1);
+                let direct = block_on(kv.range(range_request.clone())).unwrap();
+                assert_eq!(
+                    (cached.kvs, cached.count, cached.more),
+                    (direct.kvs, direct.count, direct.more),
+                    "{:?}",
+                    range_request
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn resyncs_after_the_watch_breaks() {
+    let (etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    put(&kv, b"config/a", b"1");
+    let cache = block_on(CachedKv::new(client.clone(), b"config/".to_vec())).unwrap();
+
+    // The cache's watch starts in the background, so break it until it has been.
+    eventually(|| {
+        etcd.break_watches();
+        cache.metrics().resyncs > 0
+    });
+
+    let revision = put(&kv, b"config/a", b"2");
+    eventually(|| cache.revision() == Some(revision));
+    assert_eq!(value(&cache, b"config/a"), Some(b"2".to_vec()));
+}
+
+#[test]
+fn resyncs_after_compaction() {
+    let (etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    put(&kv, b"config/a", b"1");
+
+    // Hold back the cache's watch until its start revision has been compacted away.
+    etcd.inject_once(Method::Watch, Fault::Delay(Duration::from_millis(500)));
+    let cache = block_on(CachedKv::new(client.clone(), b"config/".to_vec())).unwrap();
+    put(&kv, b"config/a", b"2");
+    let revision = put(&kv, b"config/b", b"3");
+    let mut compaction_request = CompactionRequest::new();
+    compaction_request.revision = revision;
+    block_on(kv.compact(compaction_request)).unwrap();
+
+    eventually(|| cache.metrics().resyncs == 1 && cache.revision() == Some(revision));
+    assert_eq!(value(&cache, b"config/a"), Some(b"2".to_vec()));
+    assert_eq!(value(&cache, b"config/b"), Some(b"3".to_vec()));
+}
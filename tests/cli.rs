@@ -0,0 +1,326 @@
+//! The `etcd-rs` binary against the in-memory fake, and its cluster and auth commands against a
+//! real etcd too.
+
+mod common;
+
+use common::{eventually, serve, LocalEtcd};
+use etcd::testing::TestServer;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+
+/// Runs `etcd-rs` against `endpoint` with `stdin` as its input.
+fn run(endpoint: &str, args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_etcd-rs"))
+        .arg("--endpoint")
+        .arg(endpoint)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Could not run etcd-rs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Runs a command that should succeed, returning what it printed.
+fn ok(endpoint: &str, args: &[&str]) -> String {
+    ok_with_input(endpoint, args, "")
+}
+
+fn ok_with_input(endpoint: &str, args: &[&str], stdin: &str) -> String {
+    let output = run(endpoint, args, stdin);
+    assert!(
+        output.status.success(),
+        "{:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn json(endpoint: &str, args: &[&str]) -> Value {
+    let args: Vec<&str> = ["-w", "json"].iter().chain(args).cloned().collect();
+    serde_json::from_str(&ok(endpoint, &args)).unwrap()
+}
+
+fn endpoint(server: &TestServer) -> String {
+    format!("http://127.0.0.1:{}", server.port())
+}
+
+#[test]
+fn put_get_and_del() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+
+    assert_eq!(ok(&endpoint, &["put", "app/a", "1"]), "OK\n");
+    assert_eq!(ok_with_input(&endpoint, &["put", "app/b"], "two"), "OK\n");
+    ok(&endpoint, &["put", "other", "3"]);
+
+    let table = ok(&endpoint, &["get", "app/", "--prefix"]);
+    let rows: Vec<&str> = table
+        .lines()
+        .filter(|line| line.starts_with("| app/"))
+        .collect();
+    assert_eq!(rows.len(), 2, "{}", table);
+    assert!(rows[0].contains("| 1 "), "{}", table);
+    assert!(rows[1].contains("| two "), "{}", table);
+
+    let response = json(&endpoint, &["get", "app/", "--prefix", "--limit=1"]);
+    assert_eq!(response["count"], 2);
+    assert_eq!(response["more"], true);
+    assert_eq!(response["kvs"][0]["key"], "YXBwL2E=");
+    assert_eq!(response["kvs"][0]["value"], "MQ==");
+
+    assert_eq!(
+        ok(&endpoint, &["get", "app/", "--prefix", "--count-only"]),
+        "2\n"
+    );
+    assert_eq!(ok(&endpoint, &["del", "app/", "--prefix"]), "2\n");
+    assert_eq!(json(&endpoint, &["get", "", "--from-key"])["count"], 1);
+}
+
+#[test]
+fn txn_from_stdin() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+    ok(&endpoint, &["put", "state", "ready"]);
+
+    let txn = "value(\"state\") = \"ready\"\n\nput state \"in progress\"\nget state\n\ndel state\n";
+    let output = ok_with_input(&endpoint, &["txn"], txn);
+    assert!(output.starts_with("SUCCESS\n"), "{}", output);
+    assert!(output.contains("| state | in progress |"), "{}", output);
+
+    let output = ok_with_input(&endpoint, &["-w", "json", "txn"], txn);
+    let response: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(response["succeeded"], false);
+    assert_eq!(response["responses"][0]["deleted"], 1);
+
+    // etcdctl's input ends the last group with a blank line too.
+    let output = ok_with_input(&endpoint, &["-w", "json", "txn"], &format!("{}\n", txn));
+    let response: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(response["succeeded"], false);
+    assert_eq!(response["responses"][0]["deleted"], 0);
+}
+
+#[test]
+fn lease_grant_list_and_revoke() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+
+    let granted = json(&endpoint, &["lease", "grant", "60"]);
+    assert_eq!(granted["ttl"], 60);
+    let id = format!("{:x}", granted["id"].as_i64().unwrap());
+    ok(&endpoint, &["put", "leased", "v", "--lease", &id]);
+
+    assert_eq!(
+        json(&endpoint, &["lease", "list"])["leases"][0],
+        granted["id"]
+    );
+    let output = ok(&endpoint, &["lease", "timetolive", &id, "--keys"]);
+    assert!(output.contains("attached keys([leased])"), "{}", output);
+    let output = ok(&endpoint, &["lease", "keep-alive", &id, "--once"]);
+    assert_eq!(output, format!("lease {} keepalived with TTL(60)\n", id));
+
+    ok(&endpoint, &["lease", "revoke", &id]);
+    assert_eq!(json(&endpoint, &["get", "leased"])["count"], 0);
+}
+
+#[test]
+fn lock_runs_a_command_and_passes_on_its_exit_code() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+
+    let output = ok(&endpoint, &["lock", "job", "--", "echo", "working"]);
+    let mut lines = output.lines();
+    assert!(lines.next().unwrap().starts_with("job/"), "{}", output);
+    assert_eq!(lines.next(), Some("working"));
+    // The lock's lease, and so its key, are gone once the command exits.
+    assert_eq!(json(&endpoint, &["get", "job/", "--prefix"])["count"], 0);
+
+    let output = run(&endpoint, &["lock", "job", "--", "sh", "-c", "exit 3"], "");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn lock_kills_the_command_when_its_lease_is_lost() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+
+    let locked = {
+        let endpoint = endpoint.clone();
+        thread::spawn(move || {
+            let command = "echo started; sleep 2; echo finished";
+            run(
+                &endpoint,
+                &["lock", "job", "--ttl", "1", "--", "sh", "-c", command],
+                "",
+            )
+        })
+    };
+    let mut lease = Value::Null;
+    eventually(|| {
+        lease = json(&endpoint, &["get", "job/", "--prefix"])["kvs"][0]["lease"].clone();
+        !lease.is_null()
+    });
+    ok(
+        &endpoint,
+        &["lease", "revoke", &format!("{:x}", lease.as_i64().unwrap())],
+    );
+
+    let output = locked.join().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Lost the lock"), "{}", stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("started"), "{}", stdout);
+    assert!(!stdout.contains("finished"), "{}", stdout);
+}
+
+#[test]
+fn endpoint_health() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+
+    let output = ok(&endpoint, &["endpoint", "health"]);
+    assert!(
+        output.starts_with(&format!("{} is healthy", endpoint)),
+        "{}",
+        output
+    );
+
+    drop(server);
+    let output = run(&endpoint, &["endpoint", "health"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Error: "));
+}
+
+#[test]
+fn usage_errors() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+
+    for args in [
+        &["frobnicate"][..],
+        &["get"],
+        &["get", "a", "--bogus"],
+        &["get", "a", "b", "--prefix"],
+        &["-w", "yaml", "get", "a"],
+        &["lease", "revoke", "not-hex"],
+    ] {
+        let output = run(&endpoint, args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Usage: etcd-rs"));
+    }
+}
+
+#[test]
+fn cluster_and_auth_against_the_fake() {
+    let (_etcd, server, _client) = serve();
+    let endpoint = endpoint(&server);
+    cluster_and_auth(&endpoint);
+
+    let members = json(&endpoint, &["member", "list"]);
+    assert_eq!(members["members"][0]["client_urls"][0], endpoint);
+    let added = json(
+        &endpoint,
+        &["member", "add", "--peer-urls=http://10.0.0.2:2380"],
+    );
+    assert_eq!(added["members"].as_array().unwrap().len(), 2);
+    let id = format!("{:x}", added["member"]["id"].as_u64().unwrap());
+    let table = ok(&endpoint, &["member", "list"]);
+    assert!(
+        table.contains(&format!("| {} | unstarted |", id)),
+        "{}",
+        table
+    );
+    assert_eq!(
+        ok(&endpoint, &["member", "remove", &id]),
+        format!("Member {} removed from cluster\n", id)
+    );
+    let output = run(&endpoint, &["member", "remove", &id], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("member not found"));
+}
+
+#[test]
+fn cluster_and_auth_against_etcd() {
+    let etcd = match LocalEtcd::spawn() {
+        Some(etcd) => etcd,
+        None => return,
+    };
+    let endpoint = etcd.endpoint();
+
+    let members = json(&endpoint, &["member", "list"]);
+    assert_eq!(members["members"][0]["name"], "test");
+    cluster_and_auth(&endpoint);
+}
+
+/// Membership, status, a snapshot and user and role management on a fresh single-member cluster.
+fn cluster_and_auth(endpoint: &str) {
+    ok(endpoint, &["put", "app/a", "1"]);
+
+    let members = json(endpoint, &["member", "list"]);
+    assert_eq!(members["members"].as_array().unwrap().len(), 1);
+    let status = json(endpoint, &["endpoint", "status"]);
+    assert_eq!(status["leader"], status["header"]["member_id"]);
+    assert_eq!(status["leader"], members["members"][0]["id"]);
+
+    let directory = tempfile::TempDir::new().unwrap();
+    let path = directory.path().join("snapshot.db");
+    let path = path.to_str().unwrap();
+    ok(endpoint, &["snapshot", "save", path]);
+    assert!(std::fs::metadata(path).unwrap().len() > 0);
+
+    ok(endpoint, &["role", "add", "reader"]);
+    ok(
+        endpoint,
+        &[
+            "role",
+            "grant-permission",
+            "reader",
+            "read",
+            "app/",
+            "--prefix",
+        ],
+    );
+    let role = json(endpoint, &["role", "get", "reader"]);
+    assert_eq!(role["permissions"][0]["type"], "read");
+    assert_eq!(json(endpoint, &["role", "list"])["roles"][0], "reader");
+
+    ok_with_input(endpoint, &["user", "add", "alice"], "secret\n");
+    ok(endpoint, &["user", "passwd", "alice", "--password=changed"]);
+    ok(endpoint, &["user", "grant-role", "alice", "reader"]);
+    assert_eq!(
+        json(endpoint, &["user", "get", "alice"])["roles"][0],
+        "reader"
+    );
+    assert_eq!(json(endpoint, &["user", "list"])["users"][0], "alice");
+    ok(endpoint, &["user", "revoke-role", "alice", "reader"]);
+    assert_eq!(
+        json(endpoint, &["user", "get", "alice"])["roles"],
+        json!([])
+    );
+
+    ok(
+        endpoint,
+        &["role", "revoke-permission", "reader", "app/", "--prefix"],
+    );
+    assert_eq!(
+        json(endpoint, &["role", "get", "reader"])["permissions"],
+        json!([])
+    );
+    ok(endpoint, &["user", "delete", "alice"]);
+    ok(endpoint, &["role", "delete", "reader"]);
+
+    let output = run(endpoint, &["user", "get", "alice"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("user name not found"));
+}
@@ -0,0 +1,133 @@
+// Each test binary only uses part of the harness.
+#![allow(dead_code)]
+
+pub mod scenarios;
+
+use etcd::rpc::PutRequest;
+use etcd::testing::{FakeEtcd, TestServer};
+use etcd::{Client, Kv};
+use futures::executor::block_on;
+use futures::TryStreamExt;
+use std::env;
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Serves a fresh fake on a random local port, returning it with a client for it.
+pub fn serve() -> (FakeEtcd, TestServer, Client) {
+    let etcd = FakeEtcd::new();
+    let server = etcd.serve().expect("Could not serve the fake");
+    let client = server.client().unwrap().into();
+    (etcd, server, client)
+}
+
+/// Puts `value` at `key`, returning the revision of the put.
+pub fn put(kv: &Kv, key: &[u8], value: &[u8]) -> i64 {
+    let mut put_request = PutRequest::new();
+    put_request.key = key.to_vec();
+    put_request.value = value.to_vec();
+    block_on(kv.put(put_request)).unwrap().get_header().revision
+}
+
+/// Every key under `prefix` with its value, in key order.
+pub fn pairs(kv: &Kv, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+    block_on(
+        kv.scan(prefix.to_vec(), 0)
+            .map_ok(|kv| (kv.key, kv.value))
+            .try_collect(),
+    )
+    .unwrap()
+}
+
+/// Polls `condition` until it holds, failing the test after 10 seconds.
+pub fn eventually<F: FnMut() -> bool>(mut condition: F) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !condition() {
+        assert!(Instant::now() < deadline, "condition was not met in time");
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// An `etcd` process listening on random local ports with a throwaway data dir, killed on drop.
+pub struct LocalEtcd {
+    process: Child,
+    port: u16,
+    _data_dir: TempDir,
+}
+
+impl LocalEtcd {
+    /// Spawns `etcd` from the PATH, or returns `None` if it is not installed.
+    pub fn spawn() -> Option<LocalEtcd> {
+        let path = env::var_os("PATH")?;
+        if !env::split_paths(&path).any(|dir| dir.join("etcd").is_file()) {
+            eprintln!("etcd is not on the PATH, skipping");
+            return None;
+        }
+
+        let data_dir = TempDir::new().expect("Could not create a data dir");
+        let port = free_port();
+        let client_url = format!("http://127.0.0.1:{}", port);
+        let peer_url = format!("http://127.0.0.1:{}", free_port());
+        let process = Command::new("etcd")
+            .arg("--name=test")
+            .arg(format!("--data-dir={}", data_dir.path().display()))
+            .arg(format!("--listen-client-urls={}", client_url))
+            .arg(format!("--advertise-client-urls={}", client_url))
+            .arg(format!("--listen-peer-urls={}", peer_url))
+            .arg(format!("--initial-advertise-peer-urls={}", peer_url))
+            .arg(format!("--initial-cluster=test={}", peer_url))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Could not spawn etcd");
+
+        let etcd = LocalEtcd {
+            process,
+            port,
+            _data_dir: data_dir,
+        };
+        etcd.wait_ready();
+        Some(etcd)
+    }
+
+    pub fn client(&self) -> Arc<grpc::Client> {
+        client(self.port)
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    fn wait_ready(&self) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let kv = Kv::new(self.client());
+        // Like `etcdctl endpoint health`, which reads the `health` key.
+        while block_on(kv.get(b"health".to_vec())).is_err() {
+            assert!(Instant::now() < deadline, "etcd did not start in time");
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Drop for LocalEtcd {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+pub fn client(port: u16) -> Arc<grpc::Client> {
+    let client = grpc::Client::new_plain("127.0.0.1", port, Default::default())
+        .expect("Could not create a client");
+    Arc::new(client)
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|address| address.port())
+        .expect("Could not find a free port")
+}
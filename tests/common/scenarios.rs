@@ -0,0 +1,725 @@
+//! Scenarios run against both a real etcd and the in-memory fake.
+
+use super::{eventually, put};
+use etcd::kv::Event_EventType;
+use etcd::rpc::{
+    CompactionRequest, Compare, Compare_CompareResult, Compare_CompareTarget, DeleteRangeRequest,
+    PutRequest, RangeRequest, RequestOp, TxnRequest, WatchCreateRequest,
+};
+use etcd::stm::{self, Isolation};
+use etcd::{
+    prefix_range_end, Barrier, Client, DoubleBarrier, Election, Error, Kv, Leadership, Lease, Lock,
+    PriorityQueue, Queue, Watch,
+};
+use futures::executor::block_on;
+use futures::{stream, FutureExt, StreamExt, TryStreamExt};
+use std::rc::Rc;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+pub fn kv(client: Client) {
+    let kv = Kv::new(client);
+
+    put(&kv, b"kv/a", b"1");
+    put(&kv, b"kv/b", b"2");
+    put(&kv, b"kv/a", b"3");
+
+    let a = block_on(kv.get(b"kv/a".to_vec())).unwrap().unwrap();
+    assert_eq!(a.value, b"3");
+    assert_eq!(a.version, 2);
+    assert!(a.mod_revision > a.create_revision);
+    assert!(block_on(kv.get(b"kv/missing".to_vec())).unwrap().is_none());
+
+    let mut range_request = RangeRequest::new();
+    range_request.key = b"kv/".to_vec();
+    range_request.range_end = prefix_range_end(b"kv/");
+    let range_response = block_on(kv.range(range_request)).unwrap();
+    let keys: Vec<&[u8]> = range_response.kvs.iter().map(|kv| &kv.key[..]).collect();
+    assert_eq!(keys, vec![&b"kv/a"[..], &b"kv/b"[..]]);
+
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"kv/".to_vec();
+    delete_range_request.range_end = prefix_range_end(b"kv/");
+    delete_range_request.prev_kv = true;
+    let delete_range_response = block_on(kv.delete_range(delete_range_request)).unwrap();
+    assert_eq!(delete_range_response.deleted, 2);
+    assert_eq!(delete_range_response.prev_kvs.len(), 2);
+    assert!(block_on(kv.get(b"kv/b".to_vec())).unwrap().is_none());
+}
+
+pub fn scan(client: Client) {
+    let kv = Kv::new(client);
+    for i in 0..7 {
+        put(&kv, format!("scan/{}", i).as_bytes(), b"before");
+    }
+    put(&kv, b"scanned", b"outside the prefix");
+
+    let keys: Vec<Vec<u8>> = block_on(
+        kv.scan(b"scan/".to_vec(), 3)
+            .map_ok(|kv| kv.key)
+            .try_collect(),
+    )
+    .unwrap();
+    let expected: Vec<Vec<u8>> = (0..7).map(|i| format!("scan/{}", i).into_bytes()).collect();
+    assert_eq!(keys, expected);
+
+    // Changes after the first page do not show up in later ones.
+    let mut scan = kv.scan(b"scan/".to_vec(), 3);
+    let first = block_on(scan.try_next()).unwrap().unwrap();
+    assert_eq!(first.key, b"scan/0");
+    put(&kv, b"scan/5", b"after");
+    put(&kv, b"scan/7", b"after");
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"scan/4".to_vec();
+    block_on(kv.delete_range(delete_range_request)).unwrap();
+
+    let rest: Vec<_> = block_on(scan.try_collect::<Vec<_>>()).unwrap();
+    let keys: Vec<&[u8]> = rest.iter().map(|kv| &kv.key[..]).collect();
+    assert_eq!(keys, &expected[1..]);
+    assert!(rest.iter().all(|kv| kv.value == b"before"));
+
+    assert_eq!(
+        block_on(kv.scan(b"empty/".to_vec(), 3).try_collect::<Vec<_>>()).unwrap(),
+        vec![]
+    );
+
+    // An empty prefix scans every key.
+    let all: Vec<_> = block_on(kv.scan(Vec::new(), 3).try_collect::<Vec<_>>()).unwrap();
+    assert_eq!(all.len(), 8);
+    assert_eq!(all[7].key, b"scanned");
+}
+
+pub fn history(client: Client) {
+    let kv = Kv::new(client);
+    let first = put(&kv, b"history/key", b"1");
+    let other = put(&kv, b"history/other", b"1");
+    let second = put(&kv, b"history/key", b"2");
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"history/key".to_vec();
+    let deleted = block_on(kv.delete_range(delete_range_request))
+        .unwrap()
+        .get_header()
+        .revision;
+    let other_again = put(&kv, b"history/other", b"2");
+    let third = put(&kv, b"history/key", b"3");
+
+    let value_at = |revision| {
+        block_on(kv.get_at(b"history/key".to_vec(), revision))
+            .unwrap()
+            .map(|kv| kv.value)
+    };
+    assert_eq!(value_at(first), Some(b"1".to_vec()));
+    assert_eq!(value_at(other), Some(b"1".to_vec()));
+    assert_eq!(value_at(deleted), None);
+    assert_eq!(value_at(third), Some(b"3".to_vec()));
+
+    let history = |from, to| -> Vec<(Event_EventType, i64, Vec<u8>)> {
+        block_on(kv.history(b"history/key".to_vec(), from, to))
+            .unwrap()
+            .into_iter()
+            .map(|event| {
+                let kv = event.get_kv();
+                (event.field_type, kv.mod_revision, kv.value.clone())
+            })
+            .collect()
+    };
+    assert_eq!(
+        history(first, third),
+        vec![
+            (Event_EventType::PUT, first, b"1".to_vec()),
+            (Event_EventType::PUT, second, b"2".to_vec()),
+            (Event_EventType::DELETE, deleted, vec![]),
+            (Event_EventType::PUT, third, b"3".to_vec()),
+        ]
+    );
+    // The key does not exist at the end of this one, nor change at all in the last.
+    assert_eq!(
+        history(other, other_again),
+        vec![
+            (Event_EventType::PUT, second, b"2".to_vec()),
+            (Event_EventType::DELETE, deleted, vec![]),
+        ]
+    );
+    assert_eq!(history(other_again, other_again), vec![]);
+
+    let mut compaction_request = CompactionRequest::new();
+    compaction_request.revision = second;
+    block_on(kv.compact(compaction_request)).unwrap();
+    assert_eq!(value_at(second), Some(b"2".to_vec()));
+    match block_on(kv.get_at(b"history/key".to_vec(), first)) {
+        Err(Error::Compacted { compact_revision }) => assert_eq!(compact_revision, second),
+        result => panic!("Expected a compaction error, got {:?}", result),
+    }
+    match block_on(kv.history(b"history/key".to_vec(), first, third)) {
+        Err(Error::Compacted { compact_revision }) => assert_eq!(compact_revision, second),
+        result => panic!("Expected a compaction error, got {:?}", result),
+    }
+    // The compaction revision itself is kept.
+    assert_eq!(
+        history(second, third),
+        vec![
+            (Event_EventType::PUT, second, b"2".to_vec()),
+            (Event_EventType::DELETE, deleted, vec![]),
+            (Event_EventType::PUT, third, b"3".to_vec()),
+        ]
+    );
+
+    // Nothing is written after the key is deleted, so no later event ends the watch.
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"history/key".to_vec();
+    let deleted_again = block_on(kv.delete_range(delete_range_request))
+        .unwrap()
+        .get_header()
+        .revision;
+    assert_eq!(
+        history(third, deleted_again),
+        vec![
+            (Event_EventType::PUT, third, b"3".to_vec()),
+            (Event_EventType::DELETE, deleted_again, vec![]),
+        ]
+    );
+    assert_eq!(
+        history(deleted_again, deleted_again),
+        vec![(Event_EventType::DELETE, deleted_again, vec![])]
+    );
+
+    // A key that never existed has no history, and reading it returns without waiting.
+    assert_eq!(
+        block_on(kv.history(b"history/missing".to_vec(), second, deleted_again)).unwrap(),
+        vec![]
+    );
+}
+
+pub fn empty_key(client: Client) {
+    let kv = Kv::new(client);
+    put(&kv, b"empty/key", b"1");
+
+    assert_key_not_provided(block_on(kv.range(RangeRequest::new())));
+    assert_key_not_provided(block_on(kv.put(PutRequest::new())));
+    assert_key_not_provided(block_on(kv.delete_range(DeleteRangeRequest::new())));
+
+    // An op with an empty key is rejected even in the branch that would not run.
+    let mut compare = Compare::new();
+    compare.key = b"empty/key".to_vec();
+    compare.target = Compare_CompareTarget::VERSION;
+    compare.result = Compare_CompareResult::GREATER;
+    compare.set_version(0);
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"empty/key".to_vec();
+    let mut success = RequestOp::new();
+    success.set_request_delete_range(delete_range_request);
+    let mut failure = RequestOp::new();
+    failure.set_request_range(RangeRequest::new());
+    let mut txn_request = TxnRequest::new();
+    txn_request.compare.push(compare);
+    txn_request.success.push(success);
+    txn_request.failure.push(failure);
+    assert_key_not_provided(block_on(kv.txn(txn_request)));
+    assert!(block_on(kv.get(b"empty/key".to_vec())).unwrap().is_some());
+}
+
+fn assert_key_not_provided<T: std::fmt::Debug>(result: Result<T, Error>) {
+    match result {
+        Err(Error::Grpc(grpc::Error::GrpcMessage(error))) => {
+            assert_eq!(error.grpc_message, "etcdserver: key is not provided")
+        }
+        result => panic!("Expected the key to be rejected, got {:?}", result),
+    }
+}
+
+pub fn txn(client: Client) {
+    let kv = Kv::new(client);
+
+    let create = || {
+        let mut compare = Compare::new();
+        compare.key = b"txn/key".to_vec();
+        compare.target = Compare_CompareTarget::CREATE;
+        compare.result = Compare_CompareResult::EQUAL;
+        compare.set_create_revision(0);
+
+        let mut put_request = PutRequest::new();
+        put_request.key = b"txn/key".to_vec();
+        put_request.value = b"created".to_vec();
+        let mut success = RequestOp::new();
+        success.set_request_put(put_request);
+
+        let mut range_request = RangeRequest::new();
+        range_request.key = b"txn/key".to_vec();
+        let mut failure = RequestOp::new();
+        failure.set_request_range(range_request);
+
+        let mut txn_request = TxnRequest::new();
+        txn_request.compare.push(compare);
+        txn_request.success.push(success);
+        txn_request.failure.push(failure);
+        block_on(kv.txn(txn_request)).unwrap()
+    };
+
+    let first = create();
+    assert!(first.succeeded);
+    assert!(first.responses[0].has_response_put());
+
+    let second = create();
+    assert!(!second.succeeded);
+    let existing = &second.responses[0].get_response_range().kvs[0];
+    assert_eq!(existing.value, b"created");
+    assert_eq!(existing.create_revision, first.get_header().revision);
+}
+
+pub fn watch(client: Client) {
+    let kv = Kv::new(client.clone());
+    let start = put(&kv, b"watch/key", b"1");
+
+    // Watching from the first put replays it before the later events.
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = b"watch/key".to_vec();
+    watch_create_request.start_revision = start;
+    let watch = Watch::new(watch_create_request, client);
+
+    put(&kv, b"watch/key", b"2");
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"watch/key".to_vec();
+    block_on(kv.delete_range(delete_range_request)).unwrap();
+
+    let events: Vec<_> = block_on(
+        watch
+            .map_ok(|response| stream::iter(response.events.into_vec()).map(Ok::<_, Error>))
+            .try_flatten()
+            .take(3)
+            .try_collect(),
+    )
+    .unwrap();
+    let observed: Vec<_> = events
+        .iter()
+        .map(|event| (event.field_type, event.get_kv().value.clone()))
+        .collect();
+    assert_eq!(
+        observed,
+        vec![
+            (Event_EventType::PUT, b"1".to_vec()),
+            (Event_EventType::PUT, b"2".to_vec()),
+            (Event_EventType::DELETE, Vec::new()),
+        ]
+    );
+}
+
+pub fn lease(client: Client) {
+    let kv = Kv::new(client.clone());
+    let lease = block_on(Lease::new(60, client)).unwrap();
+    assert_ne!(lease.lease_id, 0);
+
+    let mut put_request = PutRequest::new();
+    put_request.key = b"lease/key".to_vec();
+    put_request.lease = lease.lease_id;
+    block_on(kv.put(put_request)).unwrap();
+    let attached = block_on(kv.get(b"lease/key".to_vec())).unwrap().unwrap();
+    assert_eq!(attached.lease, lease.lease_id);
+
+    // Dropping the lease revokes it, deleting every key attached to it.
+    drop(lease);
+    eventually(|| block_on(kv.get(b"lease/key".to_vec())).unwrap().is_none());
+}
+
+pub fn lock(client: Client) {
+    let (acquired, acquisitions) = mpsc::channel();
+    let (release, released) = mpsc::channel::<()>();
+
+    let first_client = client.clone();
+    let first_acquired = acquired.clone();
+    let first = thread::spawn(move || {
+        let lease = Rc::new(block_on(Lease::new(60, first_client)).unwrap());
+        let lock = block_on(Lock::new(b"lock/name".to_vec(), lease)).unwrap();
+        assert!(lock.key.starts_with(b"lock/name/"));
+        first_acquired.send("first").unwrap();
+
+        released.recv().unwrap();
+        // Dropping the lock unlocks it.
+        drop(lock);
+    });
+    assert_eq!(acquisitions.recv().unwrap(), "first");
+
+    let second = thread::spawn(move || {
+        let lease = Rc::new(block_on(Lease::new(60, client)).unwrap());
+        let _lock = block_on(Lock::new(b"lock/name".to_vec(), lease)).unwrap();
+        acquired.send("second").unwrap();
+    });
+
+    // The second holder waits for the first to unlock.
+    assert!(acquisitions
+        .recv_timeout(Duration::from_millis(500))
+        .is_err());
+    release.send(()).unwrap();
+    assert_eq!(
+        acquisitions.recv_timeout(Duration::from_secs(10)).unwrap(),
+        "second"
+    );
+
+    first.join().unwrap();
+    second.join().unwrap();
+}
+
+pub fn blocking(client: Client) {
+    let client = etcd::blocking::Client::new(client);
+
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = b"blocking/key".to_vec();
+    let mut watch = client.watch(watch_create_request);
+
+    let lease = client.lease(60).unwrap();
+    let mut put_request = PutRequest::new();
+    put_request.key = b"blocking/key".to_vec();
+    put_request.value = b"value".to_vec();
+    put_request.lease = lease.lease_id;
+    client.put(put_request).unwrap();
+    assert_eq!(
+        client.get(b"blocking/key".to_vec()).unwrap().unwrap().value,
+        b"value"
+    );
+
+    let response = watch.next().unwrap().unwrap();
+    assert_eq!(response.events[0].get_kv().value, b"value");
+
+    let lock = client
+        .lock(b"blocking/lock".to_vec(), Rc::new(lease))
+        .unwrap();
+    assert!(lock.key.starts_with(b"blocking/lock/"));
+}
+
+pub fn election(client: Client) {
+    let (elected, elections) = mpsc::channel();
+    let (resign, resigned) = mpsc::channel::<()>();
+
+    let first_client = client.clone();
+    let first_elected = elected.clone();
+    let first = thread::spawn(move || {
+        let lease = Rc::new(block_on(Lease::new(60, first_client)).unwrap());
+        let election = Election::new(b"election/name".to_vec(), lease);
+        block_on(election.campaign(b"first".to_vec())).unwrap();
+        block_on(election.proclaim(b"proclaimed".to_vec())).unwrap();
+        first_elected.send("first").unwrap();
+
+        resigned.recv().unwrap();
+        block_on(election.resign()).unwrap();
+        // A candidate that resigned is no longer the leader.
+        assert!(matches!(
+            block_on(election.proclaim(b"again".to_vec())),
+            Err(Error::ElectionNotLeader)
+        ));
+    });
+    assert_eq!(elections.recv().unwrap(), "first");
+
+    let lease = Rc::new(block_on(Lease::new(60, client.clone())).unwrap());
+    let observer = Election::new(b"election/name".to_vec(), lease);
+    assert_eq!(block_on(observer.leader()).unwrap().value, b"proclaimed");
+    let mut observed = Box::pin(observer.observe());
+    assert_eq!(
+        block_on(observed.try_next()).unwrap().unwrap().value,
+        b"proclaimed"
+    );
+
+    let (finish, finished) = mpsc::channel::<()>();
+    let second = thread::spawn(move || {
+        let lease = Rc::new(block_on(Lease::new(60, client)).unwrap());
+        let election = Election::new(b"election/name".to_vec(), lease);
+        block_on(election.campaign(b"second".to_vec())).unwrap();
+        elected.send("second").unwrap();
+        // Dropping the lease would delete the key, and end the leadership.
+        finished.recv().unwrap();
+    });
+
+    // The second candidate waits for the first to resign.
+    assert!(elections.recv_timeout(Duration::from_millis(500)).is_err());
+    resign.send(()).unwrap();
+    assert_eq!(
+        elections.recv_timeout(Duration::from_secs(10)).unwrap(),
+        "second"
+    );
+    assert_eq!(
+        block_on(observed.try_next()).unwrap().unwrap().value,
+        b"second"
+    );
+
+    finish.send(()).unwrap();
+    first.join().unwrap();
+    second.join().unwrap();
+}
+
+pub fn leadership(client: Client) {
+    let (elected, elections) = mpsc::channel();
+    let (resign, resigned) = mpsc::channel::<()>();
+
+    let first_client = client.clone();
+    let first_elected = elected.clone();
+    let first = thread::spawn(move || {
+        let lease = Rc::new(block_on(Lease::new(60, first_client)).unwrap());
+        let leadership = block_on(Leadership::new(
+            b"leadership/name".to_vec(),
+            b"first".to_vec(),
+            lease,
+        ))
+        .unwrap();
+        assert!(leadership.key.starts_with(b"leadership/name/"));
+        block_on(leadership.proclaim(b"proclaimed".to_vec())).unwrap();
+        first_elected.send("first").unwrap();
+
+        resigned.recv().unwrap();
+        block_on(leadership.resign()).unwrap();
+    });
+    assert_eq!(elections.recv().unwrap(), "first");
+
+    let leader = block_on(Leadership::leader(
+        b"leadership/name".to_vec(),
+        client.clone(),
+    ))
+    .unwrap();
+    assert_eq!(leader.value, b"proclaimed");
+    let mut observed = Box::pin(Leadership::observe(
+        b"leadership/name".to_vec(),
+        client.clone(),
+    ));
+    assert_eq!(
+        block_on(observed.try_next()).unwrap().unwrap().value,
+        b"proclaimed"
+    );
+
+    let (finish, finished) = mpsc::channel::<()>();
+    let second = thread::spawn(move || {
+        let lease = Rc::new(block_on(Lease::new(60, client)).unwrap());
+        let _leadership = block_on(Leadership::new(
+            b"leadership/name".to_vec(),
+            b"second".to_vec(),
+            lease,
+        ))
+        .unwrap();
+        elected.send("second").unwrap();
+        finished.recv().unwrap();
+    });
+
+    // The second candidate waits for the first to resign.
+    assert!(elections.recv_timeout(Duration::from_millis(500)).is_err());
+    resign.send(()).unwrap();
+    assert_eq!(
+        elections.recv_timeout(Duration::from_secs(10)).unwrap(),
+        "second"
+    );
+    assert_eq!(
+        block_on(observed.try_next()).unwrap().unwrap().value,
+        b"second"
+    );
+
+    finish.send(()).unwrap();
+    first.join().unwrap();
+    second.join().unwrap();
+}
+
+pub fn queue(client: Client) {
+    let queue = Queue::new(b"queue/fifo".to_vec(), client.clone());
+    for value in ["a", "b", "c"].iter() {
+        block_on(queue.enqueue(value.as_bytes().to_vec())).unwrap();
+    }
+    for value in ["a", "b", "c"].iter() {
+        assert_eq!(block_on(queue.dequeue()).unwrap(), value.as_bytes());
+    }
+
+    let priority_queue = PriorityQueue::new(b"queue/priority".to_vec(), client.clone());
+    for (value, priority) in [("low", 5), ("urgent", 1), ("urgent too", 1), ("normal", 3)].iter() {
+        block_on(priority_queue.enqueue(value.as_bytes().to_vec(), *priority)).unwrap();
+    }
+    for value in ["urgent", "urgent too", "normal", "low"].iter() {
+        assert_eq!(
+            block_on(priority_queue.dequeue()).unwrap(),
+            value.as_bytes()
+        );
+    }
+
+    // A dequeue from an empty queue waits for the next enqueue.
+    let (dequeued, dequeues) = mpsc::channel();
+    let consumer_client = client.clone();
+    let consumer = thread::spawn(move || {
+        let queue = Queue::new(b"queue/fifo".to_vec(), consumer_client);
+        dequeued.send(block_on(queue.dequeue()).unwrap()).unwrap();
+    });
+    assert!(dequeues.recv_timeout(Duration::from_millis(500)).is_err());
+    block_on(queue.enqueue(b"late".to_vec())).unwrap();
+    assert_eq!(
+        dequeues.recv_timeout(Duration::from_secs(10)).unwrap(),
+        b"late"
+    );
+    consumer.join().unwrap();
+}
+
+pub fn barrier(client: Client) {
+    let kv = Kv::new(client.clone());
+    let barrier = Barrier::new(b"barrier/key".to_vec(), client.clone());
+    // Nothing waits on a barrier that is not held.
+    block_on(barrier.wait()).unwrap();
+
+    block_on(barrier.hold()).unwrap();
+    assert!(matches!(block_on(barrier.hold()), Err(Error::KeyExists)));
+
+    let wait = |client: Client| {
+        let (released, releases) = mpsc::channel();
+        let waiter = thread::spawn(move || {
+            let barrier = Barrier::new(b"barrier/key".to_vec(), client);
+            block_on(barrier.wait()).unwrap();
+            released.send(()).unwrap();
+        });
+        assert!(releases.recv_timeout(Duration::from_millis(500)).is_err());
+        (waiter, releases)
+    };
+
+    // Putting the key again does not release the barrier, but deleting it does.
+    let (waiter, releases) = wait(client.clone());
+    put(&kv, b"barrier/key", b"held again");
+    assert!(releases.recv_timeout(Duration::from_millis(500)).is_err());
+    block_on(barrier.release()).unwrap();
+    releases.recv_timeout(Duration::from_secs(10)).unwrap();
+    waiter.join().unwrap();
+
+    // A release counts even if the barrier is held again straight after.
+    block_on(barrier.hold()).unwrap();
+    let (waiter, releases) = wait(client);
+    block_on(barrier.release()).unwrap();
+    block_on(barrier.hold()).unwrap();
+    releases.recv_timeout(Duration::from_secs(10)).unwrap();
+    waiter.join().unwrap();
+}
+
+pub fn double_barrier(client: Client) {
+    let (entered, entries) = mpsc::channel();
+    let (leave, leaves) = mpsc::channel::<()>();
+    let leaves = Arc::new(Mutex::new(leaves));
+    let (left, exits) = mpsc::channel();
+
+    let participant = |name: &'static str| {
+        let client = client.clone();
+        let entered = entered.clone();
+        let leaves = leaves.clone();
+        let left = left.clone();
+        thread::spawn(move || {
+            let lease = Rc::new(block_on(Lease::new(60, client)).unwrap());
+            let barrier = DoubleBarrier::new(b"barrier/double".to_vec(), 3, lease);
+            block_on(barrier.enter()).unwrap();
+            entered.send(name).unwrap();
+
+            leaves.lock().unwrap().recv().unwrap();
+            block_on(barrier.leave()).unwrap();
+            left.send(name).unwrap();
+        })
+    };
+
+    // Nobody gets in until all three have arrived.
+    let mut participants = vec![participant("first"), participant("second")];
+    assert!(entries.recv_timeout(Duration::from_millis(500)).is_err());
+    participants.push(participant("third"));
+    let mut names: Vec<_> = (0..3)
+        .map(|_| entries.recv_timeout(Duration::from_secs(10)).unwrap())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["first", "second", "third"]);
+
+    // Nobody gets out until all three have left.
+    leave.send(()).unwrap();
+    leave.send(()).unwrap();
+    assert!(exits.recv_timeout(Duration::from_millis(500)).is_err());
+    leave.send(()).unwrap();
+    for _ in 0..3 {
+        exits.recv_timeout(Duration::from_secs(10)).unwrap();
+    }
+    for participant in participants {
+        participant.join().unwrap();
+    }
+}
+
+pub fn stm(client: Client) {
+    let kv = Kv::new(client.clone());
+
+    // Moves one unit from `stm/a` to `stm/b`, while another client puts `interference` after the
+    // first attempt reads `stm/a`. Returns the number of attempts and the final balances.
+    let transfer = |isolation, interference: &'static [u8]| {
+        put(&kv, b"stm/a", b"10");
+        put(&kv, b"stm/b", b"0");
+        put(&kv, b"stm/c", b"0");
+
+        let mut attempts = 0;
+        block_on(stm::run_isolated(client.clone(), isolation, |tx| {
+            attempts += 1;
+            let first = attempts == 1;
+            let kv = kv.clone();
+            async move {
+                let a = number(tx.get("stm/a").await?);
+                if first {
+                    let mut put_request = PutRequest::new();
+                    put_request.key = interference.to_vec();
+                    put_request.value = b"5".to_vec();
+                    kv.put(put_request).await?;
+                }
+                let b = number(tx.get("stm/b").await?);
+                tx.put("stm/a", (a - 1).to_string());
+                tx.put("stm/b", (b + 1).to_string());
+                tx.put("stm/c", "moved");
+                Ok(())
+            }
+            .boxed()
+        }))
+        .unwrap();
+
+        let value = |key: &[u8]| block_on(kv.get(key.to_vec())).unwrap().unwrap().value;
+        (attempts, number(value(b"stm/a")), number(value(b"stm/b")))
+    };
+
+    // A key read before the interference changed, so both serializable levels and repeatable
+    // reads retry, and read committed loses the interfering update.
+    assert_eq!(
+        transfer(Isolation::SerializableSnapshot, b"stm/a"),
+        (2, 4, 1)
+    );
+    assert_eq!(transfer(Isolation::Serializable, b"stm/a"), (2, 4, 1));
+    assert_eq!(transfer(Isolation::RepeatableReads, b"stm/a"), (2, 4, 1));
+    assert_eq!(transfer(Isolation::ReadCommitted, b"stm/a"), (1, 9, 1));
+
+    // A key read after the interference changed. The serializable levels read it at the first
+    // read's revision and retry, while the others read the new value and commit.
+    assert_eq!(
+        transfer(Isolation::SerializableSnapshot, b"stm/b"),
+        (2, 9, 6)
+    );
+    assert_eq!(transfer(Isolation::Serializable, b"stm/b"), (2, 9, 6));
+    assert_eq!(transfer(Isolation::RepeatableReads, b"stm/b"), (1, 9, 6));
+    assert_eq!(transfer(Isolation::ReadCommitted, b"stm/b"), (1, 9, 6));
+
+    // A key only written changed. Only a serializable snapshot retries.
+    assert_eq!(
+        transfer(Isolation::SerializableSnapshot, b"stm/c"),
+        (2, 9, 1)
+    );
+    assert_eq!(transfer(Isolation::Serializable, b"stm/c"), (1, 9, 1));
+
+    // The blocking client retries the same way.
+    put(&kv, b"stm/a", b"10");
+    let blocking = etcd::blocking::Client::new(client);
+    let mut attempts = 0;
+    let a = blocking
+        .stm_isolated(Isolation::RepeatableReads, |tx| {
+            attempts += 1;
+            let a = number(tx.get("stm/a")?);
+            if attempts == 1 {
+                put(&kv, b"stm/a", b"5");
+            }
+            tx.put("stm/a", (a - 1).to_string());
+            tx.delete("stm/b");
+            Ok(a)
+        })
+        .unwrap();
+    assert_eq!((attempts, a), (2, 5));
+    assert_eq!(
+        blocking.get(b"stm/a".to_vec()).unwrap().unwrap().value,
+        b"4"
+    );
+    assert!(blocking.get(b"stm/b".to_vec()).unwrap().is_none());
+}
+
+fn number(value: Vec<u8>) -> i64 {
+    String::from_utf8(value).unwrap().parse().unwrap()
+}
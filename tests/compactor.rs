@@ -0,0 +1,105 @@
+//! `Compactor` against the in-memory fake.
+
+mod common;
+
+use common::{eventually, put, serve};
+use etcd::{Compactor, Error, Kv, Retention};
+use futures::executor::block_on;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn compact() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    let first = put(&kv, b"compactor/key", b"1");
+    let second = put(&kv, b"compactor/key", b"2");
+    put(&kv, b"compactor/key", b"3");
+
+    let mut compactor = Compactor::new(client).physical(true);
+    assert_eq!(block_on(compactor.compact(second)).unwrap(), second);
+    assert_eq!(compactor.compact_revision(), second);
+    assert!(matches!(
+        block_on(kv.get_at(b"compactor/key".to_vec(), first)),
+        Err(Error::Compacted { compact_revision }) if compact_revision == second
+    ));
+
+    for revision in [first, second].iter() {
+        assert!(matches!(
+            block_on(compactor.compact(*revision)),
+            Err(Error::Compacted { compact_revision }) if compact_revision == second
+        ));
+    }
+    assert!(block_on(compactor.compact(100)).is_err());
+    assert_eq!(compactor.compact_revision(), second);
+}
+
+#[test]
+fn retain_revisions() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    for value in 0..5 {
+        put(&kv, b"compactor/key", value.to_string().as_bytes());
+    }
+    let revision = put(&kv, b"compactor/key", b"5");
+
+    let mut compactor = Compactor::new(client);
+    assert_eq!(
+        block_on(compactor.retain(Retention::Revisions(2))).unwrap(),
+        Some(revision - 2)
+    );
+    assert_eq!(
+        block_on(compactor.retain(Retention::Revisions(2))).unwrap(),
+        None
+    );
+
+    let revision = put(&kv, b"compactor/key", b"6");
+    assert_eq!(
+        block_on(compactor.retain(Retention::Revisions(2))).unwrap(),
+        Some(revision - 2)
+    );
+    assert_eq!(
+        block_on(compactor.retain(Retention::Revisions(10))).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn retain_duration() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    let retention = Retention::Duration(Duration::from_millis(300));
+    let mut compactor = Compactor::new(client);
+
+    let sampled = put(&kv, b"compactor/key", b"1");
+    assert_eq!(block_on(compactor.retain(retention)).unwrap(), None);
+    thread::sleep(Duration::from_millis(150));
+    let later = put(&kv, b"compactor/key", b"2");
+    assert_eq!(block_on(compactor.retain(retention)).unwrap(), None);
+
+    // Only the first sample is old enough.
+    thread::sleep(Duration::from_millis(200));
+    put(&kv, b"compactor/key", b"3");
+    assert_eq!(
+        block_on(compactor.retain(retention)).unwrap(),
+        Some(sampled)
+    );
+    assert_eq!(block_on(compactor.retain(retention)).unwrap(), None);
+
+    thread::sleep(Duration::from_millis(150));
+    assert_eq!(block_on(compactor.retain(retention)).unwrap(), Some(later));
+}
+
+#[test]
+fn periodic() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    let compaction = Compactor::new(client)
+        .spawn(Retention::Revisions(1), Duration::from_millis(20))
+        .unwrap();
+
+    let revision = put(&kv, b"compactor/key", b"1");
+    put(&kv, b"compactor/key", b"2");
+    eventually(|| compaction.compact_revision() == revision);
+    compaction.stop();
+}
@@ -0,0 +1,169 @@
+//! `ConfigWatcher` against the in-memory fake.
+
+mod common;
+
+use common::{put, serve};
+use etcd::rpc::{DeleteRangeRequest, PutRequest, RequestOp, TxnRequest};
+use etcd::{Client, ConfigWatcher, Error, Kv};
+use futures::executor::block_on;
+use futures::TryStreamExt;
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    replicas: u32,
+    db: Db,
+    #[serde(default)]
+    debug: bool,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Db {
+    host: String,
+    port: u16,
+}
+
+fn put_request(key: &str, value: &str) -> PutRequest {
+    let mut put_request = PutRequest::new();
+    put_request.key = key.as_bytes().to_vec();
+    put_request.value = value.as_bytes().to_vec();
+    put_request
+}
+
+fn seed(kv: &Kv) {
+    put(kv, b"app/name", b"web");
+    put(kv, b"app/replicas", b"3");
+    put(kv, b"app/db/host", b"db.example.com");
+    put(kv, b"app/db/port", b"5432");
+    put(kv, b"other/replicas", b"not a number");
+}
+
+fn config(replicas: u32, debug: bool) -> Config {
+    Config {
+        name: "web".to_string(),
+        replicas,
+        db: Db {
+            host: "db.example.com".to_string(),
+            port: 5432,
+        },
+        debug,
+    }
+}
+
+fn watcher(client: &Client) -> ConfigWatcher<Config> {
+    ConfigWatcher::new(client.clone(), b"app/".to_vec()).validate(|config: &Config| {
+        if config.replicas == 0 {
+            Err("replicas must be positive".to_string())
+        } else {
+            Ok(())
+        }
+    })
+}
+
+#[test]
+fn load() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    seed(&kv);
+    assert_eq!(block_on(watcher(&client).load()).unwrap(), config(3, false));
+}
+
+#[test]
+fn missing_fields() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    put(&kv, b"app/name", b"web");
+    assert!(matches!(
+        block_on(watcher(&client).load()),
+        Err(Error::Decode(_))
+    ));
+}
+
+#[test]
+fn reloads_on_changes() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    seed(&kv);
+    let mut configs = watcher(&client).watch();
+    assert_eq!(
+        block_on(configs.try_next()).unwrap(),
+        Some(config(3, false))
+    );
+
+    put(&kv, b"app/replicas", b"5");
+    assert_eq!(
+        block_on(configs.try_next()).unwrap(),
+        Some(config(5, false))
+    );
+
+    // A txn changing several keys reloads once.
+    let mut txn_request = TxnRequest::new();
+    for (key, value) in &[("app/replicas", "7"), ("app/debug", "true")] {
+        let mut op = RequestOp::new();
+        op.set_request_put(put_request(key, value));
+        txn_request.success.push(op);
+    }
+    block_on(client.txn(txn_request)).unwrap();
+    assert_eq!(block_on(configs.try_next()).unwrap(), Some(config(7, true)));
+
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"app/debug".to_vec();
+    block_on(client.delete_range(delete_range_request)).unwrap();
+    assert_eq!(
+        block_on(configs.try_next()).unwrap(),
+        Some(config(7, false))
+    );
+}
+
+#[test]
+fn rejects_bad_updates() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    seed(&kv);
+    let mut configs = watcher(&client).watch();
+    assert_eq!(
+        block_on(configs.try_next()).unwrap(),
+        Some(config(3, false))
+    );
+
+    put(&kv, b"app/replicas", b"0");
+    match block_on(configs.try_next()) {
+        Err(Error::InvalidConfig(message)) => assert_eq!(message, "replicas must be positive"),
+        other => panic!("expected an invalid configuration, got {:?}", other),
+    }
+    put(&kv, b"app/db/port", b"not a port");
+    assert!(matches!(
+        block_on(configs.try_next()),
+        Err(Error::Decode(_))
+    ));
+
+    put(&kv, b"app/replicas", b"4");
+    put(&kv, b"app/db/port", b"5432");
+    assert!(block_on(configs.try_next()).is_err());
+    assert_eq!(
+        block_on(configs.try_next()).unwrap(),
+        Some(config(4, false))
+    );
+}
+
+#[test]
+fn empty_prefix() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    put(&kv, b"name", b"web");
+    put(&kv, b"replicas", b"3");
+    put(&kv, b"db/host", b"db.example.com");
+    put(&kv, b"db/port", b"5432");
+
+    // The whole keyspace is the configuration.
+    let watcher = ConfigWatcher::<Config>::new(client.clone(), Vec::new());
+    assert_eq!(block_on(watcher.load()).unwrap(), config(3, false));
+    let mut configs = watcher.watch();
+    assert_eq!(
+        block_on(configs.try_next()).unwrap(),
+        Some(config(3, false))
+    );
+    put(&kv, b"debug", b"true");
+    assert_eq!(block_on(configs.try_next()).unwrap(), Some(config(3, true)));
+}
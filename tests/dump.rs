@@ -0,0 +1,157 @@
+//! `etcd::dump` export and import between in-memory fakes.
+
+mod common;
+
+use common::{pairs, put, serve};
+use etcd::dump::{self, ImportOptions, ImportSummary, Record};
+use etcd::rpc::{DeleteRangeRequest, PutRequest};
+use etcd::{prefix_range_end, Client, Error, Kv, Lease};
+use futures::executor::block_on;
+use futures::TryStreamExt;
+
+/// The lease of the only key under `prefix`.
+fn lease_of(kv: &Kv, prefix: &[u8]) -> i64 {
+    let kvs: Vec<_> = block_on(kv.scan(prefix.to_vec(), 0).try_collect()).unwrap();
+    assert_eq!(kvs.len(), 1);
+    kvs[0].lease
+}
+
+fn export(client: &Client, prefix: &[u8]) -> Vec<u8> {
+    let mut exported = Vec::new();
+    block_on(dump::export(client.clone(), prefix.to_vec(), &mut exported)).unwrap();
+    exported
+}
+
+#[test]
+fn round_trip() {
+    let (_source_etcd, _source_server, source) = serve();
+    let source_kv = Kv::new(source.clone());
+    put(&source_kv, b"app/a", b"1");
+    put(&source_kv, b"app/b", b"{\"json\": true}");
+    put(&source_kv, b"app/\xff\x00", b"\x00\x01\x02");
+    put(&source_kv, b"other", b"not exported");
+
+    let exported = export(&source, b"app/");
+    let records: Vec<Record> = String::from_utf8(exported.clone())
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].key, b"app/a");
+    assert_eq!(records[0].version, 1);
+    assert!(records[0].mod_revision > 0);
+
+    let (_destination_etcd, _destination_server, destination) = serve();
+    let summary = block_on(dump::import(
+        destination.clone(),
+        &exported[..],
+        ImportOptions::new().batch_size(2),
+    ))
+    .unwrap();
+    assert_eq!(
+        summary,
+        ImportSummary {
+            imported: 3,
+            skipped: 0
+        }
+    );
+    assert_eq!(
+        pairs(&Kv::new(destination), b""),
+        pairs(&source_kv, b"app/")
+    );
+}
+
+#[test]
+fn skip_existing() {
+    let (_source_etcd, _source_server, source) = serve();
+    let source_kv = Kv::new(source.clone());
+    put(&source_kv, b"app/a", b"exported");
+    put(&source_kv, b"app/b", b"exported");
+    let exported = export(&source, b"app/");
+
+    let (_destination_etcd, _destination_server, destination) = serve();
+    let destination_kv = Kv::new(destination.clone());
+    put(&destination_kv, b"app/a", b"existing");
+    let summary = block_on(dump::import(
+        destination.clone(),
+        &exported[..],
+        ImportOptions::new().skip_existing(true),
+    ))
+    .unwrap();
+    assert_eq!(
+        summary,
+        ImportSummary {
+            imported: 1,
+            skipped: 1
+        }
+    );
+    assert_eq!(
+        pairs(&destination_kv, b"app/"),
+        vec![
+            (b"app/a".to_vec(), b"existing".to_vec()),
+            (b"app/b".to_vec(), b"exported".to_vec()),
+        ]
+    );
+
+    block_on(dump::import(
+        destination.clone(),
+        &exported[..],
+        ImportOptions::new(),
+    ))
+    .unwrap();
+    assert_eq!(pairs(&destination_kv, b"app/"), pairs(&source_kv, b"app/"));
+}
+
+#[test]
+fn leases() {
+    let (_etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+    let lease = block_on(Lease::new(60, client.clone())).unwrap();
+    let mut put_request = PutRequest::new();
+    put_request.key = b"app/leased".to_vec();
+    put_request.value = b"1".to_vec();
+    put_request.lease = lease.lease_id;
+    block_on(kv.put(put_request)).unwrap();
+    let exported = export(&client, b"app/");
+
+    let clear = || {
+        let mut delete_range_request = DeleteRangeRequest::new();
+        delete_range_request.key = b"app/".to_vec();
+        delete_range_request.range_end = prefix_range_end(b"app/");
+        block_on(kv.delete_range(delete_range_request)).unwrap();
+    };
+
+    clear();
+    block_on(dump::import(
+        client.clone(),
+        &exported[..],
+        ImportOptions::new(),
+    ))
+    .unwrap();
+    assert_eq!(lease_of(&kv, b"app/"), 0);
+
+    clear();
+    let options = ImportOptions::new().preserve_leases(true);
+    block_on(dump::import(client.clone(), &exported[..], options)).unwrap();
+    assert_eq!(lease_of(&kv, b"app/"), lease.lease_id);
+}
+
+#[test]
+fn invalid_lines() {
+    let (_etcd, _server, client) = serve();
+    let input = b"{\"key\":\"YQ==\",\"value\":\"MQ==\",\"lease\":0,\"create_revision\":2,\"mod_revision\":2,\"version\":1}\n\nnot json\n";
+    let result = block_on(dump::import(
+        client.clone(),
+        &input[..],
+        ImportOptions::new(),
+    ));
+    match result {
+        Err(Error::Decode(message)) => assert!(message.starts_with("line 3:"), "{}", message),
+        result => panic!("Expected a decode error, got {:?}", result),
+    }
+
+    let input = b"{\"key\":\"not base64!\",\"value\":\"\",\"lease\":0,\"create_revision\":0,\"mod_revision\":0,\"version\":0}\n";
+    let result = block_on(dump::import(client, &input[..], ImportOptions::new()));
+    assert!(matches!(result, Err(Error::Decode(_))));
+}
@@ -0,0 +1,110 @@
+//! Scenarios against a local `etcd`, skipped when it is not installed.
+
+mod common;
+
+use common::{scenarios, LocalEtcd};
+
+#[test]
+fn kv() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::kv(etcd.client().into());
+    }
+}
+
+#[test]
+fn scan() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::scan(etcd.client().into());
+    }
+}
+
+#[test]
+fn history() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::history(etcd.client().into());
+    }
+}
+
+#[test]
+fn txn() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::txn(etcd.client().into());
+    }
+}
+
+#[test]
+fn watch() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::watch(etcd.client().into());
+    }
+}
+
+#[test]
+fn lease() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::lease(etcd.client().into());
+    }
+}
+
+#[test]
+fn lock() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::lock(etcd.client().into());
+    }
+}
+
+#[test]
+fn blocking() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::blocking(etcd.client().into());
+    }
+}
+
+#[test]
+fn election() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::election(etcd.client().into());
+    }
+}
+
+#[test]
+fn leadership() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::leadership(etcd.client().into());
+    }
+}
+
+#[test]
+fn queue() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::queue(etcd.client().into());
+    }
+}
+
+#[test]
+fn barrier() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::barrier(etcd.client().into());
+    }
+}
+
+#[test]
+fn double_barrier() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::double_barrier(etcd.client().into());
+    }
+}
+
+#[test]
+fn stm() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::stm(etcd.client().into());
+    }
+}
+
+#[test]
+fn empty_key() {
+    if let Some(etcd) = LocalEtcd::spawn() {
+        scenarios::empty_key(etcd.client().into());
+    }
+}
@@ -0,0 +1,269 @@
+//! The same scenarios as `tests/etcd.rs`, against the in-memory fake.
+
+mod common;
+
+use common::{put, scenarios, serve};
+use etcd::rpc::{PutRequest, WatchCreateRequest};
+use etcd::testing::{Fault, Method};
+use etcd::{Error, Kv, Leadership, Lease, Watch};
+use futures::executor::block_on;
+use futures::StreamExt;
+use grpc::GrpcStatus;
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn kv() {
+    let (_etcd, _server, client) = serve();
+    scenarios::kv(client);
+}
+
+#[test]
+fn scan() {
+    let (_etcd, _server, client) = serve();
+    scenarios::scan(client);
+}
+
+#[test]
+fn history() {
+    let (_etcd, _server, client) = serve();
+    scenarios::history(client);
+}
+
+#[test]
+fn txn() {
+    let (_etcd, _server, client) = serve();
+    scenarios::txn(client);
+}
+
+#[test]
+fn watch() {
+    let (_etcd, _server, client) = serve();
+    scenarios::watch(client);
+}
+
+#[test]
+fn lease() {
+    let (_etcd, _server, client) = serve();
+    scenarios::lease(client);
+}
+
+#[test]
+fn lock() {
+    let (_etcd, _server, client) = serve();
+    scenarios::lock(client);
+}
+
+#[test]
+fn blocking() {
+    let (_etcd, _server, client) = serve();
+    scenarios::blocking(client);
+}
+
+#[test]
+fn election() {
+    let (_etcd, _server, client) = serve();
+    scenarios::election(client);
+}
+
+#[test]
+fn leadership() {
+    let (_etcd, _server, client) = serve();
+    scenarios::leadership(client);
+}
+
+#[test]
+fn leadership_is_lost_when_its_lease_expires() {
+    let (etcd, _server, client) = serve();
+
+    let lease = Rc::new(block_on(Lease::new(10, client.clone())).unwrap());
+    let lease_id = lease.lease_id;
+    let leadership = block_on(Leadership::new(
+        b"expiring".to_vec(),
+        b"leader".to_vec(),
+        lease,
+    ))
+    .unwrap();
+    block_on(leadership.proclaim(b"still leading".to_vec())).unwrap();
+
+    etcd.expire(lease_id);
+    match block_on(leadership.proclaim(b"too late".to_vec())) {
+        Err(Error::Grpc(grpc::Error::GrpcMessage(error))) => {
+            assert_eq!(error.grpc_message, "election: not leader")
+        }
+        result => panic!("Expected the proclamation to fail, got {:?}", result),
+    }
+    assert!(block_on(Leadership::leader(b"expiring".to_vec(), client)).is_err());
+}
+
+#[test]
+fn queue() {
+    let (_etcd, _server, client) = serve();
+    scenarios::queue(client);
+}
+
+#[test]
+fn barrier() {
+    let (_etcd, _server, client) = serve();
+    scenarios::barrier(client);
+}
+
+#[test]
+fn double_barrier() {
+    let (_etcd, _server, client) = serve();
+    scenarios::double_barrier(client);
+}
+
+#[test]
+fn stm() {
+    let (_etcd, _server, client) = serve();
+    scenarios::stm(client);
+}
+
+#[test]
+fn empty_key() {
+    let (_etcd, _server, client) = serve();
+    scenarios::empty_key(client);
+}
+
+#[test]
+fn status_faults_reach_the_client() {
+    let (etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+
+    etcd.inject_once(
+        Method::Range,
+        Fault::status(GrpcStatus::Unavailable, "no leader"),
+    );
+    assert_eq!(
+        status(block_on(kv.get(b"key".to_vec()))),
+        (GrpcStatus::Unavailable as i32, "no leader".to_string())
+    );
+    assert!(block_on(kv.get(b"key".to_vec())).unwrap().is_none());
+
+    etcd.inject(
+        Method::LeaseGrant,
+        Fault::status(GrpcStatus::Internal, "out of IDs"),
+    );
+    for _ in 0..2 {
+        assert_eq!(
+            status(block_on(Lease::new(10, client.clone())).map(|lease| lease.lease_id)),
+            (GrpcStatus::Internal as i32, "out of IDs".to_string())
+        );
+    }
+    etcd.clear_faults();
+    block_on(Lease::new(10, client.clone())).unwrap();
+
+    etcd.inject_once(
+        Method::Watch,
+        Fault::status(GrpcStatus::Unavailable, "no watch"),
+    );
+    let mut watch = Watch::new(watch_create_request(b"key"), client);
+    assert_eq!(
+        status(block_on(watch.next()).unwrap()),
+        (GrpcStatus::Unavailable as i32, "no watch".to_string())
+    );
+}
+
+#[test]
+fn delayed_calls_are_served_late() {
+    let (etcd, _server, client) = serve();
+    let kv = Kv::new(client);
+
+    etcd.inject_once(Method::Put, Fault::Delay(Duration::from_millis(300)));
+    let started = Instant::now();
+    put(&kv, b"key", b"value");
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert!(block_on(kv.get(b"key".to_vec())).unwrap().is_some());
+}
+
+#[test]
+fn dropped_calls_never_respond() {
+    let (etcd, _server, client) = serve();
+
+    etcd.inject_once(Method::Range, Fault::Drop);
+    let (responded, responses) = mpsc::channel();
+    let kv = Kv::new(client.clone());
+    thread::spawn(move || {
+        let _ = responded.send(block_on(kv.get(b"key".to_vec())).is_ok());
+    });
+    assert!(responses.recv_timeout(Duration::from_millis(500)).is_err());
+
+    // Only the one call was dropped.
+    assert!(block_on(Kv::new(client).get(b"key".to_vec()))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn broken_watches_end_with_unavailable() {
+    let (etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+
+    let mut watch_create_request = watch_create_request(b"key");
+    watch_create_request.start_revision = etcd.revision() + 1;
+    let mut watch = Watch::new(watch_create_request, client);
+    put(&kv, b"key", b"value");
+    assert_eq!(block_on(watch.next()).unwrap().unwrap().events.len(), 1);
+
+    etcd.break_watches();
+    assert_eq!(
+        status(block_on(watch.next()).unwrap()),
+        (
+            GrpcStatus::Unavailable as i32,
+            "watch stream broken".to_string()
+        )
+    );
+}
+
+#[test]
+fn expired_leases_reach_the_client() {
+    let (etcd, _server, client) = serve();
+    let kv = Kv::new(client.clone());
+
+    let expire = |expire: &dyn Fn(i64)| {
+        let lease = block_on(Lease::new(10, client.clone())).unwrap();
+        let mut put_request = PutRequest::new();
+        put_request.key = b"leased".to_vec();
+        put_request.lease = lease.lease_id;
+        block_on(kv.put(put_request.clone())).unwrap();
+
+        let mut keep_alive = lease.keep_alive();
+        assert_eq!(block_on(keep_alive.refresh()).unwrap(), 10);
+        expire(lease.lease_id);
+
+        assert!(matches!(
+            block_on(keep_alive.refresh()),
+            Err(Error::LeaseExpired)
+        ));
+        assert!(block_on(kv.get(b"leased".to_vec())).unwrap().is_none());
+        assert_eq!(
+            status(block_on(kv.put(put_request))),
+            (
+                GrpcStatus::NotFound as i32,
+                "etcdserver: requested lease not found".to_string()
+            )
+        );
+    };
+
+    expire(&|lease_id| etcd.expire(lease_id));
+    expire(&|_| etcd.advance(Duration::from_secs(11)));
+}
+
+fn watch_create_request(key: &[u8]) -> WatchCreateRequest {
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = key.to_vec();
+    watch_create_request
+}
+
+/// The gRPC status and message a call failed with.
+fn status<T: std::fmt::Debug>(result: Result<T, Error>) -> (i32, String) {
+    match result {
+        Err(Error::Grpc(grpc::Error::GrpcMessage(error))) => {
+            (error.grpc_status, error.grpc_message)
+        }
+        result => panic!("Expected a gRPC status, got {:?}", result),
+    }
+}
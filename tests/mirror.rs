@@ -0,0 +1,175 @@
+//! `Mirror` between two in-memory fakes.
+
+mod common;
+
+use common::{eventually, pairs, put, serve};
+use etcd::rpc::{CompactionRequest, DeleteRangeRequest};
+use etcd::testing::{Fault, Method};
+use etcd::{Error, Kv, Mirror, MirrorProgress};
+use futures::executor::block_on;
+use std::thread;
+use std::time::Duration;
+
+fn delete(kv: &Kv, key: &[u8]) -> i64 {
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = key.to_vec();
+    block_on(kv.delete_range(delete_range_request))
+        .unwrap()
+        .get_header()
+        .revision
+}
+
+fn pair(key: &str, value: &str) -> (Vec<u8>, Vec<u8>) {
+    (key.as_bytes().to_vec(), value.as_bytes().to_vec())
+}
+
+#[test]
+fn sync() {
+    let (_source_etcd, _source_server, source) = serve();
+    let source = Kv::new(source);
+    let (_destination_etcd, _destination_server, destination) = serve();
+    let destination = Kv::new(destination);
+    put(&source, b"app/a", b"1");
+    let revision = put(&source, b"app/b", b"2");
+    put(&source, b"other", b"not mirrored");
+
+    let mirror = Mirror::new(
+        source.client.clone(),
+        destination.client.clone(),
+        b"app/".to_vec(),
+    );
+    assert_eq!(block_on(mirror.sync()).unwrap(), revision + 1);
+    assert_eq!(
+        pairs(&destination, b""),
+        vec![pair("app/a", "1"), pair("app/b", "2")]
+    );
+    assert_eq!(
+        mirror.progress(),
+        MirrorProgress {
+            source_revision: revision + 1,
+            mirrored_revision: revision + 1,
+        }
+    );
+}
+
+#[test]
+fn replicates_changes() {
+    let (source_etcd, _source_server, source) = serve();
+    let source = Kv::new(source);
+    let (_destination_etcd, _destination_server, destination) = serve();
+    let destination = Kv::new(destination);
+    put(&source, b"app/a", b"1");
+    put(&source, b"app/b", b"2");
+
+    let mirror = Mirror::new(
+        source.client.clone(),
+        destination.client.clone(),
+        b"app/".to_vec(),
+    )
+    .with_destination_prefix(b"copy/".to_vec())
+    .spawn()
+    .unwrap();
+    eventually(|| pairs(&destination, b"") == vec![pair("copy/a", "1"), pair("copy/b", "2")]);
+
+    put(&source, b"app/a", b"3");
+    delete(&source, b"app/b");
+    put(&source, b"app/c", b"4");
+    let revision = put(&source, b"other", b"not mirrored");
+    eventually(|| pairs(&destination, b"") == vec![pair("copy/a", "3"), pair("copy/c", "4")]);
+    eventually(|| mirror.progress().mirrored_revision == revision - 1);
+    assert_eq!(mirror.progress().lag(), 0);
+
+    // A broken watch resumes from the last mirrored revision.
+    source_etcd.break_watches();
+    put(&source, b"app/d", b"5");
+    eventually(|| pairs(&destination, b"").len() == 3);
+    assert_eq!(pairs(&destination, b"")[2], pair("copy/d", "5"));
+
+    mirror.stop().unwrap();
+}
+
+#[test]
+fn mirrors_the_whole_keyspace_for_an_empty_prefix() {
+    let (_source_etcd, _source_server, source) = serve();
+    let source = Kv::new(source);
+    let (_destination_etcd, _destination_server, destination) = serve();
+    let destination = Kv::new(destination);
+    put(&source, b"a", b"1");
+
+    let mirror = Mirror::new(
+        source.client.clone(),
+        destination.client.clone(),
+        Vec::new(),
+    )
+    .spawn()
+    .unwrap();
+    eventually(|| pairs(&destination, b"") == vec![pair("a", "1")]);
+    put(&source, b"b", b"2");
+    eventually(|| pairs(&destination, b"") == vec![pair("a", "1"), pair("b", "2")]);
+
+    mirror.stop().unwrap();
+}
+
+#[test]
+fn stops_after_compaction() {
+    let (source_etcd, _source_server, source) = serve();
+    let source = Kv::new(source);
+    let (_destination_etcd, _destination_server, destination) = serve();
+    let destination = Kv::new(destination);
+    put(&source, b"app/a", b"1");
+
+    // Hold back the mirror's watch until its start revision has been compacted away.
+    source_etcd.inject_once(Method::Watch, Fault::Delay(Duration::from_millis(500)));
+    let mirror = Mirror::new(
+        source.client.clone(),
+        destination.client.clone(),
+        b"app/".to_vec(),
+    )
+    .spawn()
+    .unwrap();
+    eventually(|| mirror.progress().mirrored_revision > 0);
+    put(&source, b"app/a", b"2");
+    let revision = put(&source, b"app/b", b"3");
+    let mut compaction_request = CompactionRequest::new();
+    compaction_request.revision = revision;
+    block_on(source.compact(compaction_request)).unwrap();
+
+    thread::sleep(Duration::from_secs(1));
+    match mirror.stop() {
+        Err(Error::Compacted { compact_revision }) => assert_eq!(compact_revision, revision),
+        result => panic!("Expected a compaction error, got {:?}", result),
+    }
+}
+
+#[test]
+fn splits_large_revisions() {
+    let (_source_etcd, _source_server, source) = serve();
+    let source = Kv::new(source);
+    let (_destination_etcd, _destination_server, destination) = serve();
+    let destination = Kv::new(destination);
+    for i in 0..200 {
+        put(&source, format!("app/{:03}", i).as_bytes(), b"1");
+    }
+
+    let mirror = Mirror::new(
+        source.client.clone(),
+        destination.client.clone(),
+        b"app/".to_vec(),
+    )
+    .spawn()
+    .unwrap();
+    eventually(|| pairs(&destination, b"").len() == 200);
+
+    // One revision deleting more keys than etcd allows ops in a single transaction.
+    let mut delete_range_request = DeleteRangeRequest::new();
+    delete_range_request.key = b"app/".to_vec();
+    delete_range_request.range_end = b"app0".to_vec();
+    let revision = block_on(source.delete_range(delete_range_request))
+        .unwrap()
+        .get_header()
+        .revision;
+    eventually(|| pairs(&destination, b"").is_empty());
+    eventually(|| mirror.progress().mirrored_revision == revision);
+
+    mirror.stop().unwrap();
+}
@@ -0,0 +1,86 @@
+//! The shared scenarios through a `Namespace`, and what they leave in the keyspace around it.
+
+mod common;
+
+use common::{put, scenarios, serve};
+use etcd::rpc::{PutRequest, RangeRequest};
+use etcd::{Kv, Namespace};
+use futures::executor::block_on;
+
+fn keys(kv: &Kv, key: &[u8], range_end: &[u8]) -> Vec<Vec<u8>> {
+    let mut range_request = RangeRequest::new();
+    range_request.key = key.to_vec();
+    range_request.range_end = range_end.to_vec();
+    let range_response = block_on(kv.range(range_request)).unwrap();
+    range_response.kvs.into_iter().map(|kv| kv.key).collect()
+}
+
+#[test]
+fn kv() {
+    let (_etcd, _server, client) = serve();
+    scenarios::kv(Namespace::new(client, "/team-x/").into());
+}
+
+#[test]
+fn txn() {
+    let (_etcd, _server, client) = serve();
+    scenarios::txn(Namespace::new(client.clone(), "/team-x/").into());
+
+    let raw = Kv::new(client);
+    assert_eq!(keys(&raw, b"txn/", b"txn0"), Vec::<Vec<u8>>::new());
+    assert_eq!(keys(&raw, b"/team-x/txn/", b"/team-x/txn0").len(), 1);
+}
+
+#[test]
+fn watch() {
+    let (_etcd, _server, client) = serve();
+    scenarios::watch(Namespace::new(client, "/team-x/").into());
+}
+
+#[test]
+fn lock() {
+    let (_etcd, _server, client) = serve();
+    scenarios::lock(Namespace::new(client, "/team-x/").into());
+}
+
+#[test]
+fn keys_are_prefixed() {
+    let (_etcd, _server, client) = serve();
+    let team_x = Kv::new(Namespace::new(client.clone(), "/team-x/"));
+    let team_y = Kv::new(Namespace::new(client.clone(), "/team-y/"));
+    let raw = Kv::new(client);
+
+    put(&team_x, b"config", b"x");
+    let mut put_request = PutRequest::new();
+    put_request.key = b"config".to_vec();
+    put_request.value = b"y".to_vec();
+    put_request.prev_kv = true;
+    let put_response = block_on(team_y.put(put_request)).unwrap();
+    assert!(!put_response.has_prev_kv());
+
+    let stored = block_on(raw.get(b"/team-x/config".to_vec()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.value, b"x");
+    let config = block_on(team_x.get(b"config".to_vec())).unwrap().unwrap();
+    assert_eq!(config.key, b"config");
+    assert_eq!(config.value, b"x");
+
+    // A range end of `[0]` stops at the end of the namespace.
+    assert_eq!(keys(&team_x, b"", &[0]), vec![b"config".to_vec()]);
+    assert_eq!(keys(&raw, &[0], &[0]).len(), 2);
+}
+
+#[test]
+fn zero_key_starts_at_the_namespace() {
+    let (_etcd, _server, client) = serve();
+    let team_x = Kv::new(Namespace::new(client, "/team-x/"));
+    put(&team_x, b"", b"root");
+    put(&team_x, b"config", b"x");
+
+    // The zero key stands in for an empty prefix, so it covers the namespace's own key too.
+    assert_eq!(
+        keys(&team_x, &[0], &[0]),
+        vec![b"".to_vec(), b"config".to_vec()]
+    );
+}
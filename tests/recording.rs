@@ -0,0 +1,96 @@
+//! Recording a session against the in-memory fake with `Recorder`, and replaying it with `Replay`.
+
+use etcd::kv::{Event, KeyValue};
+use etcd::rpc::{PutRequest, PutResponse, WatchCreateRequest};
+use etcd::testing::{FakeEtcd, Recorder, Replay};
+use etcd::{Client, Error, Kv, Lease, Watch};
+use futures::executor::block_on;
+use futures::StreamExt;
+
+/// What a session saw, to compare between the recording and the replay.
+#[derive(Debug, PartialEq)]
+struct Transcript {
+    put: PutResponse,
+    value: Option<KeyValue>,
+    events: Vec<Event>,
+    lease_id: i64,
+    ttl: i64,
+}
+
+/// Puts and reads a key, watches it change, and keeps a lease alive before revoking it.
+fn session(client: Client) -> Result<Transcript, Error> {
+    let kv = Kv::new(client.clone());
+
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = b"recorded".to_vec();
+    // Watch from the put below, which is the first write to a fresh fake.
+    watch_create_request.start_revision = 2;
+    let mut watch = Watch::new(watch_create_request, client.clone());
+
+    let mut put_request = PutRequest::new();
+    put_request.key = b"recorded".to_vec();
+    put_request.value = b"value".to_vec();
+    let put = block_on(kv.put(put_request))?;
+    let value = block_on(kv.get(b"recorded".to_vec()))?;
+    let events = block_on(watch.next()).unwrap()?.events.into_vec();
+
+    let lease = block_on(Lease::new(30, client))?;
+    let lease_id = lease.lease_id;
+    let ttl = block_on(lease.keep_alive().refresh())?;
+    block_on(lease.revoke())?;
+
+    Ok(Transcript {
+        put,
+        value,
+        events,
+        lease_id,
+        ttl,
+    })
+}
+
+#[test]
+fn replays_a_recorded_session() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("session.recording");
+
+    let etcd = FakeEtcd::new();
+    let server = etcd.serve().expect("Could not serve the fake");
+    let recorder = Recorder::new(server.client().unwrap(), &path).unwrap();
+    let recorded = session(recorder.into()).unwrap();
+    assert_eq!(recorded.events.len(), 1);
+    drop(server);
+
+    let replay = Replay::open(&path).unwrap();
+    let server = replay.serve().expect("Could not serve the replay");
+    let replayed = session(server.client().unwrap().into()).unwrap();
+    assert_eq!(replayed, recorded);
+}
+
+#[test]
+fn rejects_requests_that_were_not_recorded() {
+    let directory = tempfile::tempdir().unwrap();
+    let path = directory.path().join("session.recording");
+
+    let etcd = FakeEtcd::new();
+    let server = etcd.serve().expect("Could not serve the fake");
+    let recorder = Recorder::new(server.client().unwrap(), &path).unwrap();
+    block_on(Kv::new(recorder).get(b"recorded".to_vec())).unwrap();
+
+    let replay = Replay::open(&path).unwrap();
+    let server = replay.serve().expect("Could not serve the replay");
+    let kv = Kv::new(server.client().unwrap());
+    match block_on(kv.get(b"diverged".to_vec())) {
+        Err(Error::Grpc(grpc::Error::GrpcMessage(error))) => assert_eq!(
+            error.grpc_message,
+            "no recorded call to /etcdserverpb.KV/Range matches the request"
+        ),
+        result => panic!(
+            "Expected the replay to reject the request, got {:?}",
+            result
+        ),
+    }
+    assert_eq!(replay.remaining(), 1);
+
+    block_on(kv.get(b"recorded".to_vec())).unwrap();
+    assert_eq!(replay.remaining(), 0);
+}
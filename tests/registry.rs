@@ -0,0 +1,125 @@
+//! `ServiceRegistry` and lease keep-alives against the in-memory fake.
+
+mod common;
+
+use common::{eventually, serve};
+use etcd::{Client, Error, Kv, Lease, ServiceInstance, ServiceRegistry};
+use futures::executor::block_on;
+use futures::TryStreamExt;
+use std::thread;
+use std::time::Duration;
+
+fn instance(id: &str, endpoint: &str) -> ServiceInstance {
+    ServiceInstance {
+        id: id.to_string(),
+        endpoint: endpoint.as_bytes().to_vec(),
+    }
+}
+
+fn lease_of(client: &Client, key: &[u8]) -> Option<i64> {
+    block_on(Kv::new(client.clone()).get(key.to_vec()))
+        .unwrap()
+        .map(|kv| kv.lease)
+}
+
+#[test]
+fn keep_alive() {
+    let (etcd, _server, client) = serve();
+    let lease = block_on(Lease::new(5, client)).unwrap();
+    let mut keep_alive = lease.keep_alive();
+
+    etcd.advance(Duration::from_secs(4));
+    assert_eq!(block_on(keep_alive.refresh()).unwrap(), 5);
+    etcd.advance(Duration::from_secs(4));
+    assert_eq!(block_on(keep_alive.refresh()).unwrap(), 5);
+
+    etcd.advance(Duration::from_secs(6));
+    assert!(matches!(
+        block_on(keep_alive.refresh()),
+        Err(Error::LeaseExpired)
+    ));
+}
+
+#[test]
+fn register_and_discover() {
+    let (_etcd, _server, client) = serve();
+    let registry = ServiceRegistry::new(client.clone());
+    let mut discovered = registry.discover("web");
+    assert_eq!(block_on(discovered.try_next()).unwrap(), Some(vec![]));
+
+    let first = block_on(registry.register("web", "web-1", b"10.0.0.1:80".to_vec())).unwrap();
+    assert_eq!(first.key(), b"/services/web/web-1");
+    assert_eq!(
+        block_on(discovered.try_next()).unwrap(),
+        Some(vec![instance("web-1", "10.0.0.1:80")])
+    );
+    let _second = block_on(registry.register("web", "web-2", b"10.0.0.2:80".to_vec())).unwrap();
+    let _other = block_on(registry.register("db", "db-1", b"10.0.1.1:5432".to_vec())).unwrap();
+    assert_eq!(
+        block_on(discovered.try_next()).unwrap(),
+        Some(vec![
+            instance("web-1", "10.0.0.1:80"),
+            instance("web-2", "10.0.0.2:80"),
+        ])
+    );
+
+    // Deregistering revokes the lease, deleting the instance.
+    first.deregister();
+    assert_eq!(lease_of(&client, b"/services/web/web-1"), None);
+    assert_eq!(
+        block_on(discovered.try_next()).unwrap(),
+        Some(vec![instance("web-2", "10.0.0.2:80")])
+    );
+    assert_eq!(
+        block_on(registry.instances("web")).unwrap(),
+        vec![instance("web-2", "10.0.0.2:80")]
+    );
+}
+
+#[test]
+fn dropping_deregisters() {
+    let (_etcd, _server, client) = serve();
+    let registry = ServiceRegistry::new(client.clone());
+    let registration =
+        block_on(registry.register("web", "web-1", b"10.0.0.1:80".to_vec())).unwrap();
+
+    drop(registration);
+    eventually(|| lease_of(&client, b"/services/web/web-1").is_none());
+}
+
+#[test]
+fn keeps_registrations_alive() {
+    let (etcd, _server, client) = serve();
+    let registry = ServiceRegistry::new(client.clone()).with_ttl(3);
+    let _registration =
+        block_on(registry.register("web", "web-1", b"10.0.0.1:80".to_vec())).unwrap();
+    let lease = lease_of(&client, b"/services/web/web-1").unwrap();
+
+    // Each pause spans a refresh, so the lease outlives several TTLs of the fake's clock.
+    for _ in 0..4 {
+        thread::sleep(Duration::from_millis(1500));
+        etcd.advance(Duration::from_secs(2));
+    }
+    assert_eq!(lease_of(&client, b"/services/web/web-1"), Some(lease));
+}
+
+#[test]
+fn registers_again_after_losing_the_lease() {
+    let (etcd, _server, client) = serve();
+    let registry = ServiceRegistry::new(client.clone()).with_ttl(1);
+    let _registration =
+        block_on(registry.register("web", "web-1", b"10.0.0.1:80".to_vec())).unwrap();
+    let kv = Kv::new(client);
+    let registered = block_on(kv.get(b"/services/web/web-1".to_vec()))
+        .unwrap()
+        .unwrap();
+
+    // Expiring the lease deletes the key, so it comes back with a later create revision.
+    etcd.expire(registered.lease);
+    eventually(
+        || match block_on(kv.get(b"/services/web/web-1".to_vec())).unwrap() {
+            Some(renewed) => renewed.create_revision > registered.create_revision,
+            None => false,
+        },
+    );
+}
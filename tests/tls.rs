@@ -0,0 +1,224 @@
+//! Connecting over TLS, through a TLS-terminating proxy in front of the fake server.
+
+mod common;
+
+use common::scenarios;
+use etcd::testing::{FakeEtcd, TestServer};
+use etcd::{Client, ClientBuilder, Error, Kv};
+use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+/// A certificate authority and a certificate and key it issued.
+struct Pki {
+    ca: String,
+    ca_der: CertificateDer<'static>,
+    certificate: CertificateDer<'static>,
+    certificate_pem: String,
+    key_pem: String,
+    key: Vec<u8>,
+}
+
+impl Pki {
+    fn new(name: &str) -> Pki {
+        let ca_key = KeyPair::generate().unwrap();
+        let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+
+        let key = KeyPair::generate().unwrap();
+        let certificate = CertificateParams::new(vec![name.to_string()])
+            .unwrap()
+            .signed_by(&key, &ca, &ca_key)
+            .unwrap();
+
+        Pki {
+            ca: ca.pem(),
+            ca_der: ca.der().clone(),
+            certificate: certificate.der().clone(),
+            certificate_pem: certificate.pem(),
+            key_pem: key.serialize_pem(),
+            key: key.serialize_der(),
+        }
+    }
+}
+
+/// The fake server behind a proxy that requires client certificates issued by `clients`' CA.
+struct Proxy {
+    port: u16,
+    runtime: Runtime,
+    _server: TestServer,
+}
+
+impl Proxy {
+    fn start(server: &Pki, clients: &Pki) -> Proxy {
+        let fake = FakeEtcd::new().serve().expect("Could not serve the fake");
+        let upstream = fake.port();
+        let runtime = Runtime::new().unwrap();
+
+        let provider = Arc::new(ring::default_provider());
+        let mut roots = RootCertStore::empty();
+        roots.add(clients.ca_der.clone()).unwrap();
+        let verifier =
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+                .unwrap();
+        let mut config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(
+                vec![server.certificate.clone()],
+                PrivatePkcs8KeyDer::from(server.key.clone()).into(),
+            )
+            .unwrap();
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        runtime.spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let acceptor = acceptor.clone();
+                tokio::spawn(async move {
+                    let mut stream = match acceptor.accept(stream).await {
+                        Ok(stream) => stream,
+                        Err(_) => return,
+                    };
+                    if let Ok(mut upstream) = TcpStream::connect(("127.0.0.1", upstream)).await {
+                        let _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+                    }
+                });
+            }
+        });
+
+        Proxy {
+            port,
+            runtime,
+            _server: fake,
+        }
+    }
+
+    fn builder(&self) -> ClientBuilder {
+        Client::builder(format!("https://127.0.0.1:{}", self.port)).server_name("localhost")
+    }
+
+    fn connect(&self, builder: ClientBuilder) -> Result<Client, Error> {
+        self.runtime.block_on(builder.connect())
+    }
+
+    /// Connects and makes a call, since a rejected client certificate only shows after the
+    /// handshake.
+    fn get(&self, builder: ClientBuilder) -> Result<(), Error> {
+        self.runtime.block_on(async {
+            let client = builder.connect().await?;
+            Kv::new(client).get(b"key".to_vec()).await?;
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn mutual_tls() {
+    let server = Pki::new("localhost");
+    let client = Pki::new("client");
+    let proxy = Proxy::start(&server, &client);
+
+    let builder = proxy
+        .builder()
+        .ca_certificate(server.ca.into_bytes())
+        .identity(
+            client.certificate_pem.into_bytes(),
+            client.key_pem.into_bytes(),
+        );
+    scenarios::kv(proxy.connect(builder).unwrap());
+}
+
+#[test]
+fn pem_files() {
+    let server = Pki::new("localhost");
+    let client = Pki::new("client");
+    let proxy = Proxy::start(&server, &client);
+
+    let dir = tempfile::tempdir().unwrap();
+    let ca = dir.path().join("ca.pem");
+    let certificate = dir.path().join("client.pem");
+    let key = dir.path().join("client-key.pem");
+    std::fs::write(&ca, &server.ca).unwrap();
+    std::fs::write(&certificate, &client.certificate_pem).unwrap();
+    std::fs::write(&key, &client.key_pem).unwrap();
+
+    let builder = proxy
+        .builder()
+        .ca_certificate_file(&ca)
+        .unwrap()
+        .identity_files(&certificate, &key)
+        .unwrap();
+    assert!(proxy.get(builder).is_ok());
+}
+
+#[test]
+fn unknown_ca() {
+    let server = Pki::new("localhost");
+    let client = Pki::new("client");
+    let proxy = Proxy::start(&server, &client);
+
+    let builder = proxy
+        .builder()
+        .ca_certificate(Pki::new("localhost").ca.into_bytes())
+        .identity(
+            client.certificate_pem.into_bytes(),
+            client.key_pem.into_bytes(),
+        );
+    assert!(proxy.get(builder).is_err());
+}
+
+#[test]
+fn wrong_server_name() {
+    let server = Pki::new("localhost");
+    let client = Pki::new("client");
+    let proxy = Proxy::start(&server, &client);
+
+    let builder = proxy
+        .builder()
+        .server_name("etcd.example.com")
+        .ca_certificate(server.ca.into_bytes())
+        .identity(
+            client.certificate_pem.into_bytes(),
+            client.key_pem.into_bytes(),
+        );
+    assert!(proxy.get(builder).is_err());
+}
+
+#[test]
+fn missing_client_certificate() {
+    let server = Pki::new("localhost");
+    let client = Pki::new("client");
+    let proxy = Proxy::start(&server, &client);
+
+    let builder = proxy.builder().ca_certificate(server.ca.into_bytes());
+    assert!(proxy.get(builder).is_err());
+}
+
+#[test]
+fn insecure_skip_verify() {
+    let server = Pki::new("localhost");
+    let client = Pki::new("client");
+    let proxy = Proxy::start(&server, &client);
+
+    let builder = proxy
+        .builder()
+        .server_name("etcd.example.com")
+        .insecure_skip_verify(true)
+        .identity(
+            client.certificate_pem.into_bytes(),
+            client.key_pem.into_bytes(),
+        );
+    assert!(proxy.get(builder).is_ok());
+}
@@ -0,0 +1,109 @@
+//! The same scenarios as `tests/fake.rs`, over the tonic transport.
+
+mod common;
+
+use common::scenarios;
+use etcd::testing::{FakeEtcd, TestServer};
+use etcd::transport::{Endpoint, TonicTransport};
+use etcd::Client;
+use tokio::runtime::Runtime;
+
+/// A fake server and a tonic client connected to it, running on their own runtime.
+struct Connection {
+    client: Client,
+    _runtime: Runtime,
+    _server: TestServer,
+}
+
+fn connect() -> Connection {
+    let server = FakeEtcd::new().serve().expect("Could not serve the fake");
+    let runtime = Runtime::new().expect("Could not start a runtime");
+    let endpoint = Endpoint::from_shared(format!("http://127.0.0.1:{}", server.port())).unwrap();
+    let transport = runtime
+        .block_on(TonicTransport::connect(endpoint))
+        .expect("Could not connect to the fake");
+
+    Connection {
+        client: transport.into(),
+        _runtime: runtime,
+        _server: server,
+    }
+}
+
+#[test]
+fn kv() {
+    let connection = connect();
+    scenarios::kv(connection.client.clone());
+}
+
+#[test]
+fn txn() {
+    let connection = connect();
+    scenarios::txn(connection.client.clone());
+}
+
+#[test]
+fn watch() {
+    let connection = connect();
+    scenarios::watch(connection.client.clone());
+}
+
+#[test]
+fn lease() {
+    let connection = connect();
+    scenarios::lease(connection.client.clone());
+}
+
+#[test]
+fn lock() {
+    let connection = connect();
+    scenarios::lock(connection.client.clone());
+}
+
+#[test]
+fn blocking() {
+    let connection = connect();
+    scenarios::blocking(connection.client.clone());
+}
+
+#[test]
+fn election() {
+    let connection = connect();
+    scenarios::election(connection.client.clone());
+}
+
+#[test]
+fn leadership() {
+    let connection = connect();
+    scenarios::leadership(connection.client.clone());
+}
+
+#[test]
+fn queue() {
+    let connection = connect();
+    scenarios::queue(connection.client.clone());
+}
+
+#[test]
+fn barrier() {
+    let connection = connect();
+    scenarios::barrier(connection.client.clone());
+}
+
+#[test]
+fn double_barrier() {
+    let connection = connect();
+    scenarios::double_barrier(connection.client.clone());
+}
+
+#[test]
+fn stm() {
+    let connection = connect();
+    scenarios::stm(connection.client.clone());
+}
+
+#[test]
+fn empty_key() {
+    let connection = connect();
+    scenarios::empty_key(connection.client.clone());
+}
@@ -0,0 +1,146 @@
+//! `TypedKv` with each codec against the in-memory fake.
+
+mod common;
+
+use common::{put, serve};
+use etcd::codec::{Bincode, Json, Protobuf};
+use etcd::kv::KeyValue;
+use etcd::rpc::WatchCreateRequest;
+use etcd::{prefix_range_end, Error, Kv, TypedEvent, TypedKv};
+use futures::executor::block_on;
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    replicas: u32,
+}
+
+fn config(replicas: u32) -> Config {
+    Config {
+        name: "web".to_string(),
+        replicas,
+    }
+}
+
+#[test]
+fn json() {
+    let (_etcd, _server, client) = serve();
+    let configs = TypedKv::<Config>::new(client.clone());
+
+    block_on(configs.put("config/web", &config(3))).unwrap();
+    assert_eq!(
+        block_on(configs.get("config/web")).unwrap(),
+        Some(config(3))
+    );
+    assert_eq!(block_on(configs.get("config/missing")).unwrap(), None);
+
+    let stored = block_on(configs.kv().get(b"config/web".to_vec()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.value, br#"{"name":"web","replicas":3}"#.to_vec());
+
+    assert!(block_on(configs.delete("config/web")).unwrap());
+    assert!(!block_on(configs.delete("config/web")).unwrap());
+}
+
+#[test]
+fn bincode() {
+    let (_etcd, _server, client) = serve();
+    let configs = TypedKv::<Config, _>::with_codec(client, Bincode);
+
+    block_on(configs.put("config/web", &config(3))).unwrap();
+    assert_eq!(
+        block_on(configs.get("config/web")).unwrap(),
+        Some(config(3))
+    );
+}
+
+#[test]
+fn protobuf() {
+    let (_etcd, _server, client) = serve();
+    let messages = TypedKv::<KeyValue, _>::with_codec(client, Protobuf);
+
+    let mut message = KeyValue::new();
+    message.key = b"nested".to_vec();
+    message.version = 7;
+    block_on(messages.put("message", &message)).unwrap();
+    assert_eq!(block_on(messages.get("message")).unwrap(), Some(message));
+}
+
+#[test]
+fn get_prefix() {
+    let (_etcd, _server, client) = serve();
+    let configs = TypedKv::<Config>::new(client);
+
+    block_on(configs.put("config/b", &config(2))).unwrap();
+    block_on(configs.put("config/a", &config(1))).unwrap();
+    assert_eq!(
+        block_on(configs.get_prefix("config/")).unwrap(),
+        vec![
+            (b"config/a".to_vec(), config(1)),
+            (b"config/b".to_vec(), config(2)),
+        ]
+    );
+
+    // An empty prefix covers every key.
+    block_on(configs.put("other", &config(3))).unwrap();
+    assert_eq!(block_on(configs.get_prefix("")).unwrap().len(), 3);
+}
+
+#[test]
+fn decode_errors() {
+    let (_etcd, _server, client) = serve();
+    let configs = TypedKv::<Config, _>::with_codec(client.clone(), Json);
+    put(&Kv::new(client.clone()), b"config/web", b"not json");
+
+    match block_on(configs.get("config/web")) {
+        Err(Error::Decode(message)) => assert!(message.starts_with("config/web: ")),
+        other => panic!("expected a decode error, got {:?}", other),
+    }
+}
+
+#[test]
+fn watch() {
+    let (etcd, _server, client) = serve();
+    let configs = TypedKv::<Config>::new(client.clone());
+
+    // The fake registers watches in the background, so start from a revision rather than now.
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = b"config/".to_vec();
+    watch_create_request.range_end = prefix_range_end(b"config/");
+    watch_create_request.start_revision = etcd.revision() + 1;
+    let events = configs.watch(watch_create_request);
+
+    block_on(configs.put("config/web", &config(3))).unwrap();
+    put(&Kv::new(client.clone()), b"config/web", b"not json");
+    block_on(configs.delete("config/web")).unwrap();
+
+    let events: Vec<_> = block_on(events.take(3).collect());
+    match &events[0] {
+        Ok(TypedEvent::Put { key, value, .. }) => {
+            assert_eq!(key, b"config/web");
+            assert_eq!(value, &config(3));
+        }
+        other => panic!("expected a put, got {:?}", other),
+    }
+    assert!(matches!(events[1], Err(Error::Decode(_))));
+    assert!(matches!(events[2], Ok(TypedEvent::Delete { .. })));
+}
+
+#[test]
+fn watch_surfaces_transport_errors() {
+    let (etcd, _server, client) = serve();
+    let configs = TypedKv::<Config>::new(client);
+
+    let mut watch_create_request = WatchCreateRequest::new();
+    watch_create_request.key = b"config/web".to_vec();
+    watch_create_request.start_revision = etcd.revision() + 1;
+    let mut events = configs.watch(watch_create_request);
+    block_on(configs.put("config/web", &config(1))).unwrap();
+    assert!(block_on(events.try_next()).unwrap().is_some());
+
+    etcd.break_watches();
+    assert!(block_on(events.try_next()).is_err());
+}
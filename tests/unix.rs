@@ -0,0 +1,88 @@
+//! Connecting over a Unix domain socket, through a proxy in front of the fake server.
+#![cfg(unix)]
+
+mod common;
+
+use common::scenarios;
+use etcd::testing::{FakeEtcd, TestServer};
+use etcd::{Client, Error, Kv};
+use std::path::PathBuf;
+use tempfile::TempDir;
+use tokio::net::{TcpStream, UnixListener};
+use tokio::runtime::Runtime;
+
+/// The fake server behind a proxy listening on a socket in a temporary directory.
+struct Proxy {
+    path: PathBuf,
+    runtime: Runtime,
+    _dir: TempDir,
+    _server: TestServer,
+}
+
+impl Proxy {
+    fn start() -> Proxy {
+        let fake = FakeEtcd::new().serve().expect("Could not serve the fake");
+        let upstream = fake.port();
+        let runtime = Runtime::new().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("etcd.sock");
+        let listener = runtime
+            .block_on(async { UnixListener::bind(&path) })
+            .unwrap();
+        runtime.spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    if let Ok(mut upstream) = TcpStream::connect(("127.0.0.1", upstream)).await {
+                        let _ = tokio::io::copy_bidirectional(&mut stream, &mut upstream).await;
+                    }
+                });
+            }
+        });
+
+        Proxy {
+            path,
+            runtime,
+            _dir: dir,
+            _server: fake,
+        }
+    }
+
+    fn connect(&self, endpoint: String) -> Result<Client, Error> {
+        self.runtime.block_on(Client::builder(endpoint).connect())
+    }
+
+    fn endpoint(&self) -> String {
+        format!("unix://{}", self.path.display())
+    }
+}
+
+#[test]
+fn kv() {
+    let proxy = Proxy::start();
+    scenarios::kv(proxy.connect(proxy.endpoint()).unwrap());
+}
+
+#[test]
+fn watch() {
+    let proxy = Proxy::start();
+    scenarios::watch(proxy.connect(proxy.endpoint()).unwrap());
+}
+
+#[test]
+fn missing_socket() {
+    let proxy = Proxy::start();
+    let endpoint = format!("unix://{}", proxy.path.with_extension("missing").display());
+    let result = proxy.runtime.block_on(async {
+        let client = Client::builder(endpoint).connect().await?;
+        Kv::new(client).get(b"key".to_vec()).await?;
+        Ok::<_, Error>(())
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn empty_path() {
+    let proxy = Proxy::start();
+    assert!(proxy.connect("unix://".to_string()).is_err());
+}